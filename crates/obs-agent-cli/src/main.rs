@@ -1,8 +1,10 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use obs_agent_core::application::ports::*;
+use obs_agent_core::domain::models::{AnomalyTransition, Metric, RetentionPolicy, Scope, TimeRange};
 use obs_agent_core::domain::services::*;
 use obs_agent_infra::*;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{error, info};
 
@@ -32,6 +34,44 @@ struct Cli {
     /// Verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Run against simulated OBS and sensors (nominal, thermal-ramp, network-collapse, memory-leak, source-unplug)
+    #[arg(long, value_name = "SCENARIO")]
+    simulate: Option<SimulationScenario>,
+
+    /// Simulated seconds per real second
+    #[arg(long, default_value = "1.0", requires = "simulate")]
+    sim_speed: f64,
+
+    /// Known upload capacity in kbps (otherwise only a bandwidth test provides it)
+    #[arg(long, value_name = "KBPS", env = "OBS_AGENT_UPLOAD_KBPS")]
+    upload_kbps: Option<u32>,
+}
+
+impl Cli {
+    /// Puertos reales o simulados según `--simulate`
+    fn ports(&self) -> (Arc<dyn OBSPort>, Arc<dyn MonitorPort>) {
+        match self.simulate {
+            Some(scenario) => simulated_ports(Arc::new(Simulation::new(scenario).with_speed(self.sim_speed))),
+            None => (
+                Arc::new(OBSAdapter::new(&self.obs_host, self.obs_port, self.obs_password.clone())),
+                Arc::new(MonitorAdapter::new()),
+            ),
+        }
+    }
+
+    /// Monitor de red real o simulado según `--simulate`
+    fn network(&self) -> Arc<dyn NetworkPort> {
+        match self.simulate {
+            Some(scenario) => Arc::new(SimulatedNetworkAdapter::new(Arc::new(
+                Simulation::new(scenario).with_speed(self.sim_speed),
+            ))),
+            None => match self.upload_kbps {
+                Some(kbps) => Arc::new(NetworkAdapter::new().with_upload_capacity(kbps)),
+                None => Arc::new(NetworkAdapter::new()),
+            },
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -44,7 +84,15 @@ enum Commands {
     },
 
     /// Detect hardware
-    Hardware,
+    Hardware {
+        /// Measure upload bandwidth against a sink server (HOST:PORT, or "local" for a bundled loopback sink)
+        #[arg(long, value_name = "ENDPOINT")]
+        bandwidth: Option<String>,
+
+        /// Bandwidth test duration in seconds
+        #[arg(long, default_value = "5", requires = "bandwidth")]
+        bandwidth_secs: u64,
+    },
 
     /// List OBS scenes
     Scenes,
@@ -60,10 +108,277 @@ enum Commands {
     },
 
     /// Optimize OBS configuration
-    Optimize,
+    Optimize {
+        /// Measure upload bandwidth first against a sink server (HOST:PORT, or "local")
+        #[arg(long, value_name = "ENDPOINT")]
+        bandwidth: Option<String>,
+
+        /// Bandwidth test duration in seconds
+        #[arg(long, default_value = "5", requires = "bandwidth")]
+        bandwidth_secs: u64,
+    },
+
+    /// Automatically fix auto-fixable anomalies
+    Fix {
+        /// Only show what would be changed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply disruptive fixes without asking
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Fallback scene to switch to when a plugin crashes
+        #[arg(long)]
+        fallback_scene: Option<String>,
+
+        /// Maximum attempts per anomaly type
+        #[arg(long, default_value = "3")]
+        max_attempts: u32,
+    },
 
     /// Test OBS connection
     Connect,
+
+    /// Continuously monitor the system and OBS
+    Monitor {
+        /// Seconds between samples
+        #[arg(short, long, default_value = "5")]
+        interval: u64,
+
+        /// Record every sample to an NDJSON file
+        #[arg(short, long)]
+        record: Option<PathBuf>,
+
+        /// Stop after this many samples
+        #[arg(short = 'n', long)]
+        samples: Option<usize>,
+
+        /// Persist samples, anomalies and health reports to a SQLite history (default location if no path)
+        #[arg(long, value_name = "DB", num_args = 0..=1)]
+        store: Option<Option<PathBuf>>,
+
+        /// Serve the latest sample as OpenMetrics on http://ADDR/metrics (e.g. 0.0.0.0:9464)
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<String>,
+
+        #[command(flatten)]
+        push: PushOptions,
+    },
+
+    /// Show metric history, anomalies and health reports from the SQLite store
+    History {
+        /// Metrics to show (comma separated); a default set if omitted
+        #[arg(short, long, value_delimiter = ',')]
+        metrics: Vec<Metric>,
+
+        /// How far back to look (e.g. 30m, 6h, 7d)
+        #[arg(long, default_value = "1h", value_parser = parse_duration)]
+        since: std::time::Duration,
+
+        /// Aggregation step (e.g. 1m); a twentieth of the window if omitted
+        #[arg(long, value_parser = parse_duration)]
+        step: Option<std::time::Duration>,
+
+        /// Metrics database (default location if omitted)
+        #[arg(long, value_name = "DB")]
+        store: Option<PathBuf>,
+
+        /// Apply the retention policy (roll up and expire old data) first
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Replay a recorded NDJSON session through the anomaly rules
+    Replay {
+        /// Recording file produced by `monitor --record`
+        file: PathBuf,
+
+        /// Playback speed factor (1 = real time); as fast as possible if omitted
+        #[arg(short, long)]
+        speed: Option<f64>,
+
+        /// Rule thresholds file (.toml or .json)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+    },
+
+    /// Run a bandwidth sink server for `hardware --bandwidth`
+    BandwidthSink {
+        /// Address to listen on (use 0.0.0.0:5201 to accept tests from other machines)
+        #[arg(short, long, default_value = "127.0.0.1:5201")]
+        listen: String,
+
+        /// Megabytes accepted per test before the sink closes it
+        #[arg(long, default_value_t = DEFAULT_SINK_MAX_BYTES / 1_000_000, value_name = "MB")]
+        max_mb: u64,
+
+        /// Tests served at the same time
+        #[arg(long, default_value_t = DEFAULT_SINK_MAX_CONNECTIONS, value_name = "N")]
+        max_connections: usize,
+    },
+
+    /// Anomaly rule tools
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommands,
+    },
+
+    /// Manage API tokens for obs-agent-server and read its audit trail
+    Tokens {
+        /// Token database (default location if omitted)
+        #[arg(long, value_name = "DB", global = true)]
+        store: Option<PathBuf>,
+
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+
+    /// Join a central obs-agent-server fleet: push heartbeats, health and anomalies and run its commands
+    Join {
+        /// Central server URL (e.g. http://central:8080)
+        server: String,
+
+        /// API token for the central server (operator scope; one per machine, rate limits are per token)
+        #[arg(long, env = "OBS_AGENT_FLEET_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// Id of this machine in the fleet (hostname if omitted)
+        #[arg(long)]
+        host_id: Option<String>,
+
+        /// Rule thresholds file (.toml or .json)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokenCommands {
+    /// Create a token; the secret is shown only once
+    Create {
+        /// Unique name, shown in the audit trail
+        name: String,
+
+        /// Access level (read, operator, admin)
+        #[arg(short, long, default_value = "read")]
+        scope: Scope,
+    },
+
+    /// List tokens, including revoked ones
+    List,
+
+    /// Revoke a token by name or id
+    Revoke {
+        name: String,
+    },
+
+    /// Show who triggered which mutation
+    Audit {
+        /// How far back to look (e.g. 30m, 6h, 7d)
+        #[arg(long, default_value = "24h", value_parser = parse_duration)]
+        since: std::time::Duration,
+
+        /// Maximum number of entries
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesCommands {
+    /// Run scenario files against the anomaly rules
+    Test {
+        /// Scenario files (.toml or .json)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Rule thresholds file overriding each scenario's [rules] section
+        #[arg(long)]
+        rules: Option<PathBuf>,
+    },
+}
+
+/// Envío de métricas a InfluxDB / StatsD durante `monitor`
+#[derive(clap::Args)]
+struct PushOptions {
+    /// Push samples in InfluxDB line protocol to this write URL (e.g. http://influx:8086/api/v2/write?org=o&bucket=b)
+    #[arg(long, value_name = "URL")]
+    influx: Option<String>,
+
+    /// InfluxDB API token
+    #[arg(long, env = "INFLUX_TOKEN", hide_env_values = true)]
+    influx_token: Option<String>,
+
+    /// Send samples as StatsD gauges over UDP to HOST:PORT
+    #[arg(long, value_name = "HOST:PORT")]
+    statsd: Option<String>,
+
+    /// StatsD tag syntax (datadog, influx, none)
+    #[arg(long, default_value = "datadog")]
+    statsd_format: StatsdTagFormat,
+
+    /// Tag added to every pushed metric (repeatable); host defaults to this machine's name
+    #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tags: Vec<(String, String)>,
+
+    /// Buffered lines (Influx) or metrics (StatsD) that trigger an early push
+    #[arg(long, value_name = "N")]
+    push_batch: Option<usize>,
+
+    /// Seconds between pushes of buffered metrics
+    #[arg(long, default_value = "10", value_name = "SECS")]
+    push_interval: u64,
+}
+
+impl PushOptions {
+    /// Sinks configurados; vacío si no se pidió ningún envío
+    async fn sinks(&self, hostname: impl FnOnce() -> Option<String>) -> Result<Vec<Arc<dyn TelemetrySink>>> {
+        let mut sinks: Vec<Arc<dyn TelemetrySink>> = Vec::new();
+        if self.influx.is_none() && self.statsd.is_none() {
+            return Ok(sinks);
+        }
+
+        let mut tags = self.tags.clone();
+        if !tags.iter().any(|(key, _)| key == "host") {
+            if let Some(host) = hostname() {
+                tags.insert(0, ("host".to_string(), host));
+            }
+        }
+
+        if let Some(url) = &self.influx {
+            let mut sink = InfluxLineSink::new(url).with_tags(tags.clone());
+            if let Some(token) = &self.influx_token {
+                sink = sink.with_token(token);
+            }
+            if let Some(batch) = self.push_batch {
+                sink = sink.with_batch_size(batch);
+            }
+            println!("📤 Pushing line protocol to {}", url);
+            sinks.push(Arc::new(sink));
+        }
+        if let Some(target) = &self.statsd {
+            let mut sink = StatsdSink::connect(target)
+                .await?
+                .with_tags(tags.clone())
+                .with_tag_format(self.statsd_format);
+            if let Some(batch) = self.push_batch {
+                sink = sink.with_batch_size(batch);
+            }
+            println!("📤 Sending StatsD gauges to {}", target);
+            sinks.push(Arc::new(sink));
+        }
+
+        Ok(sinks)
+    }
+}
+
+/// Etiquetas como `KEY=VALUE`
+fn parse_tag(value: &str) -> std::result::Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, tag)) if !key.trim().is_empty() => Ok((key.trim().to_string(), tag.trim().to_string())),
+        _ => Err(format!("invalid tag '{}' (use KEY=VALUE)", value)),
+    }
 }
 
 #[tokio::main]
@@ -83,32 +398,72 @@ async fn main() -> Result<()> {
     // Run command
     match &cli.command {
         Commands::Connect => cmd_connect(&cli).await,
-        Commands::Hardware => cmd_hardware(&cli).await,
+        Commands::Hardware { bandwidth, bandwidth_secs } => {
+            cmd_hardware(&cli, bandwidth.as_deref(), *bandwidth_secs).await
+        }
         Commands::Scenes => cmd_scenes(&cli).await,
         Commands::Stats => cmd_stats(&cli).await,
         Commands::Health { quick } => cmd_health(&cli, *quick).await,
         Commands::Scan { severity } => cmd_scan(&cli, severity).await,
-        Commands::Optimize => cmd_optimize(&cli).await,
+        Commands::Optimize { bandwidth, bandwidth_secs } => {
+            cmd_optimize(&cli, bandwidth.as_deref(), *bandwidth_secs).await
+        }
+        Commands::Fix { dry_run, yes, fallback_scene, max_attempts } => {
+            cmd_fix(&cli, *dry_run, *yes, fallback_scene.clone(), *max_attempts).await
+        }
+        Commands::Monitor { interval, record, samples, store, metrics_listen, push } => {
+            let store = store.as_ref().map(|path| path.clone().unwrap_or_else(SqliteMetricsStore::default_path));
+            cmd_monitor(&cli, *interval, record.as_deref(), *samples, store.as_deref(), metrics_listen.as_deref(), push)
+                .await
+        }
+        Commands::History { metrics, since, step, store, prune } => {
+            cmd_history(metrics, *since, *step, store.as_deref(), *prune).await
+        }
+        Commands::BandwidthSink { listen, max_mb, max_connections } => {
+            cmd_bandwidth_sink(listen, *max_mb, *max_connections).await
+        }
+        Commands::Replay { file, speed, rules } => cmd_replay(file, *speed, rules.as_deref()).await,
+        Commands::Rules { command: RulesCommands::Test { files, rules } } => {
+            cmd_rules_test(files, rules.as_deref())
+        }
+        Commands::Tokens { store, command } => cmd_tokens(command, store.as_deref()).await,
+        Commands::Join { server, token, host_id, rules } => {
+            cmd_join(&cli, server, token.as_deref(), host_id.as_deref(), rules.as_deref()).await
+        }
     }
 }
 
 async fn cmd_connect(cli: &Cli) -> Result<()> {
     info!("Testing OBS connection...");
 
-    let adapter = OBSAdapter::new(&cli.obs_host, cli.obs_port, cli.obs_password.clone());
+    let (adapter, _) = cli.ports();
     adapter.connect().await?;
 
-    println!("✅ Successfully connected to OBS at {}:{}", cli.obs_host, cli.obs_port);
+    match cli.simulate {
+        Some(scenario) => println!("✅ Successfully connected to simulated OBS ({})", scenario),
+        None => println!("✅ Successfully connected to OBS at {}:{}", cli.obs_host, cli.obs_port),
+    }
 
     adapter.disconnect().await?;
     Ok(())
 }
 
-async fn cmd_hardware(_cli: &Cli) -> Result<()> {
+async fn cmd_hardware(cli: &Cli, bandwidth: Option<&str>, bandwidth_secs: u64) -> Result<()> {
     info!("Detecting hardware...");
 
-    let monitor = MonitorAdapter::new();
-    let hardware = monitor.detect_hardware()?;
+    let (_, monitor) = cli.ports();
+    let mut hardware = monitor.detect_hardware()?;
+
+    let bandwidth_test = match bandwidth {
+        Some(endpoint) => {
+            let endpoint = Some(endpoint).filter(|e| !e.eq_ignore_ascii_case("local"));
+            let duration = std::time::Duration::from_secs(bandwidth_secs.max(1));
+            let test = run_bandwidth_test(cli.network().as_ref(), endpoint, duration).await?;
+            hardware.limit_to_upload(test.sustained_upload_kbps());
+            Some(test)
+        }
+        None => None,
+    };
 
     println!("\n🖥️  HARDWARE INFORMATION");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -118,34 +473,99 @@ async fn cmd_hardware(_cli: &Cli) -> Result<()> {
     println!("  Name: {}", hardware.cpu.brand);
     println!("  Cores: {} physical / {} logical", hardware.cpu.cores_physical, hardware.cpu.cores_logical);
     println!("  Frequency: {:.0} MHz", hardware.cpu.frequency_mhz);
-    println!("\n🎮 GPU:");
-    if let Some(gpu) = &hardware.gpu {
-        println!("  Name: {}", gpu.name);
-        println!("  Vendor: {:?}", gpu.vendor);
-        println!("  NVENC: {}", if gpu.supports_nvenc { "Yes" } else { "No" });
-        println!("  AMF: {}", if gpu.supports_amf { "Yes" } else { "No" });
-        println!("  QSV: {}", if gpu.supports_qsv { "Yes" } else { "No" });
-    } else {
+    match monitor.get_cpu_temp() {
+        Ok(Some(temp)) => println!("  Temperature: {:.1}°C", temp),
+        Ok(None) => println!("  Temperature: unavailable (no sensor)"),
+        Err(e) => println!("  Temperature: unavailable ({})", e),
+    }
+    for core in monitor.get_cpu_core_temps().unwrap_or_default() {
+        println!("    {}: {:.1}°C", core.label, core.celsius);
+    }
+    println!("\n🎮 GPUs:");
+    if hardware.gpus.is_empty() {
         println!("  Not detected");
     }
+    for (index, gpu) in hardware.gpus.iter().enumerate() {
+        let mut roles = Vec::new();
+        if hardware.render_gpu == Some(index) {
+            roles.push("render");
+        }
+        if hardware.encode_gpu == Some(index) {
+            roles.push("encode");
+        }
+        let roles = if roles.is_empty() { String::new() } else { format!(" [{}]", roles.join(", ")) };
+
+        println!("  #{} {}{}", index, gpu.name, roles);
+        println!("    Vendor: {:?}", gpu.vendor);
+        println!("    NVENC: {}", if gpu.supports_nvenc { "Yes" } else { "No" });
+        println!("    AMF: {}", if gpu.supports_amf { "Yes" } else { "No" });
+        println!("    QSV: {}", if gpu.supports_qsv { "Yes" } else { "No" });
+        println!("    VAAPI: {}", if gpu.supports_vaapi { "Yes" } else { "No" });
+        if let Some(pci_id) = &gpu.pci_id {
+            println!("    PCI ID: {} (driver {})", pci_id, gpu.driver_version);
+        }
+        if gpu.memory_total_mb > 0 {
+            println!("    VRAM: {} MB ({} MB free)", gpu.memory_total_mb, gpu.memory_free_mb);
+        }
+        if let Some(temp) = gpu.temperature_c {
+            println!("    Temperature: {:.1}°C", temp);
+        }
+        if let Some(usage) = gpu.usage_percent {
+            println!("    Usage: {:.0}%", usage);
+        }
+        if let Some(sessions) = gpu.encoder_sessions {
+            println!("    Encoder: {} session(s), {:.0}% used", sessions, gpu.encoder_usage_percent.unwrap_or(0.0));
+        }
+    }
     println!("\n💾 RAM:");
     println!("  Total: {:.1} GB", hardware.ram.total_gb);
     println!("  Available: {:.1} GB", hardware.ram.available_gb);
     println!("  Used: {:.1}%", hardware.ram.used_percent);
+    if let Some(power) = monitor.get_power_status().ok().flatten() {
+        println!("\n🔌 POWER:");
+        println!("  Source: {}", if power.on_battery { "Battery" } else { "AC" });
+        if let Some(percent) = power.battery_percent {
+            println!("  Battery: {:.0}%", percent);
+        }
+        println!("  Profile: {}", power.profile.map_or("Unknown".to_string(), |p| format!("{:?}", p)));
+    }
+    if let Some(test) = &bandwidth_test {
+        println!("\n📶 UPLOAD:");
+        println!("  Server: {}", test.endpoint);
+        println!("  Throughput: {:.0} kbps (±{:.0} kbps over {:.1}s)", test.throughput_kbps, test.stddev_kbps, test.duration_secs);
+        println!("  Range: {:.0} - {:.0} kbps", test.min_kbps, test.max_kbps);
+        println!("  Sustained: {} kbps (safe stream bitrate {} kbps)", test.sustained_upload_kbps(), test.safe_bitrate_kbps());
+    }
     println!("\n📊 RECOMMENDATIONS:");
     println!("  Encoder: {:?}", hardware.recommended_encoder);
     println!("  Preset: {}", hardware.recommended_preset);
     println!("  Resolution: {}x{}", hardware.recommended_resolution.0, hardware.recommended_resolution.1);
     println!("  FPS: {}", hardware.recommended_fps);
-    println!("  Bitrate: {} kbps", hardware.recommended_bitrate);
+    match &bandwidth_test {
+        Some(test) => println!("  Bitrate: {} kbps (upload allows up to {} kbps)", hardware.recommended_bitrate, test.safe_bitrate_kbps()),
+        None => println!("  Bitrate: {} kbps", hardware.recommended_bitrate),
+    }
 
     Ok(())
 }
 
+async fn cmd_bandwidth_sink(listen: &str, max_mb: u64, max_connections: usize) -> Result<()> {
+    let sink = BandwidthSink::bind(listen)
+        .await?
+        .with_max_bytes(max_mb.saturating_mul(1_000_000))
+        .with_max_connections(max_connections);
+    println!("📶 Bandwidth sink listening on {} (Ctrl+C to stop)", sink.local_addr()?);
+
+    tokio::select! {
+        result = sink.serve() => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
 async fn cmd_scenes(cli: &Cli) -> Result<()> {
     info!("Fetching OBS scenes...");
 
-    let obs = Arc::new(OBSAdapter::new(&cli.obs_host, cli.obs_port, cli.obs_password.clone())) as Arc<dyn OBSPort>;
+    let (obs, _) = cli.ports();
     obs.connect().await?;
 
     let scenes = obs.get_scenes().await?;
@@ -172,7 +592,7 @@ async fn cmd_scenes(cli: &Cli) -> Result<()> {
 async fn cmd_stats(cli: &Cli) -> Result<()> {
     info!("Getting OBS stats...");
 
-    let obs = Arc::new(OBSAdapter::new(&cli.obs_host, cli.obs_port, cli.obs_password.clone())) as Arc<dyn OBSPort>;
+    let (obs, _) = cli.ports();
     obs.connect().await?;
 
     let stats = obs.get_stats().await?;
@@ -206,10 +626,9 @@ async fn cmd_health(cli: &Cli, quick: bool) -> Result<()> {
         info!("Running full health check...");
     }
 
-    let obs = Arc::new(OBSAdapter::new(&cli.obs_host, cli.obs_port, cli.obs_password.clone())) as Arc<dyn OBSPort>;
-    let monitor = Arc::new(MonitorAdapter::new()) as Arc<dyn MonitorPort>;
+    let (obs, monitor) = cli.ports();
 
-    let service = HealthCheckService::new(obs, monitor);
+    let service = HealthCheckService::new(obs, monitor).with_network(cli.network());
 
     if quick {
         let is_healthy = service.quick_check().await?;
@@ -270,13 +689,14 @@ async fn cmd_scan(cli: &Cli, severity: &str) -> Result<()> {
 
     info!("Scanning for anomalies (min severity: {:?})...", min_severity);
 
-    let monitor = MonitorAdapter::new();
+    let (_, monitor) = cli.ports();
     let detector = AnomalyDetector::with_default_rules();
 
     let context = SystemContext {
-        cpu_temp: monitor.get_cpu_temp().unwrap_or(0.0),
-        gpu_temp: monitor.get_gpu_temp().unwrap_or(0.0),
+        cpu_temp: monitor.get_cpu_temp().ok().flatten().unwrap_or(0.0),
+        gpu_temp: monitor.get_gpu_temp().ok().flatten().unwrap_or(0.0),
         cpu_usage: monitor.get_cpu_usage().unwrap_or(0.0),
+        cpu_frequency: monitor.get_cpu_frequency().ok().flatten(),
         memory_used_percent: monitor.get_memory_info()?.used_percent,
         disk_free_gb: monitor.get_disk_space()?.free_gb,
         obs_dropped_frames_percent: 0.0,
@@ -284,6 +704,11 @@ async fn cmd_scan(cli: &Cli, severity: &str) -> Result<()> {
         missing_sources: vec![],
         audio_peak_db: None,
         network_bitrate: None,
+        recording_minutes_left: None,
+        obs_process: monitor.get_obs_process().ok().flatten(),
+        network: None,
+        power: monitor.get_power_status().ok().flatten(),
+        timestamp: chrono::Utc::now(),
     };
 
     let anomalies = detector.scan_filtered(&context, min_severity);
@@ -311,17 +736,35 @@ async fn cmd_scan(cli: &Cli, severity: &str) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_optimize(cli: &Cli) -> Result<()> {
+async fn cmd_optimize(cli: &Cli, bandwidth: Option<&str>, bandwidth_secs: u64) -> Result<()> {
     let api_key = cli.gemini_api_key.as_ref()
         .ok_or_else(|| anyhow::anyhow!("GEMINI_API_KEY not set"))?;
 
     info!("Optimizing OBS configuration with AI...");
 
-    let monitor = MonitorAdapter::new();
+    let (obs, monitor) = cli.ports();
     let hardware = monitor.detect_hardware()?;
 
+    // La capacidad de subida sale de `--upload-kbps` o de una prueba de subida;
+    // el tráfico de una muestra aislada no dice nada de ella
+    let network_port = cli.network();
+    if let Some(endpoint) = bandwidth {
+        let endpoint = Some(endpoint).filter(|e| !e.eq_ignore_ascii_case("local"));
+        let duration = std::time::Duration::from_secs(bandwidth_secs.max(1));
+        run_bandwidth_test(network_port.as_ref(), endpoint, duration).await?;
+    }
+    let ingest = obs.get_stream_settings().await.ok().and_then(|s| s.ingest());
+    let network = network_port.sample(ingest.as_ref()).await?;
+
     let ai = Arc::new(AIAdapter::new(api_key)) as Arc<dyn AIPort>;
-    let optimizer = ConfigOptimizer::new(ai);
+    let mut optimizer = ConfigOptimizer::new(ai);
+    if let Some(upload) = network.estimated_upload_kbps {
+        optimizer = optimizer.with_upload_kbps(upload);
+    }
+    let power = monitor.get_power_status().ok().flatten();
+    if let Some(power) = &power {
+        optimizer = optimizer.with_power_status(power.clone());
+    }
 
     let config = optimizer.optimize(&hardware).await?;
 
@@ -333,9 +776,592 @@ async fn cmd_optimize(cli: &Cli) -> Result<()> {
     println!("Encoder: {}", config.encoder);
     println!("Preset: {}", config.preset);
     println!("Bitrate: {} kbps", config.bitrate);
+    match (network.estimated_upload_kbps, optimizer.max_bitrate()) {
+        (Some(upload), Some(max)) => println!("Network: ~{} kbps upload (max {} kbps for streaming)", upload, max),
+        _ => println!("Network: upload bandwidth unknown (use --bandwidth or --upload-kbps)"),
+    }
+    if let Some(power) = power.filter(|p| p.is_power_saving()) {
+        println!(
+            "Power: {}{} (lighter preset, max 30 FPS)",
+            if power.on_battery { "on battery" } else { "power saver profile" },
+            power.battery_percent.map_or(String::new(), |p| format!(", {:.0}%", p))
+        );
+    }
 
     let is_valid = optimizer.validate_config(&config, &hardware);
     println!("\nValidation: {}", if is_valid { "✅ Valid" } else { "❌ Invalid" });
 
     Ok(())
 }
+
+/// Pide confirmación por stdin antes de aplicar una acción
+struct StdinConfirmation;
+
+impl ConfirmationHandler for StdinConfirmation {
+    fn confirm(&self, anomaly: &obs_agent_core::domain::models::Anomaly, action: &RemediationAction) -> bool {
+        use std::io::Write;
+
+        print!("❓ {:?}: {} → {}? [y/N] ", anomaly.anomaly_type, anomaly.details, action.describe());
+        let _ = std::io::stdout().flush();
+
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
+async fn cmd_fix(
+    cli: &Cli,
+    dry_run: bool,
+    yes: bool,
+    fallback_scene: Option<String>,
+    max_attempts: u32,
+) -> Result<()> {
+    info!("Running health check before remediation...");
+
+    let (obs, monitor) = cli.ports();
+
+    let detector = Arc::new(AnomalyDetector::with_default_rules());
+    let health = Arc::new(HealthCheckService::new(Arc::clone(&obs), monitor).with_detector(Arc::clone(&detector)));
+    let report = health.check().await?;
+
+    let mut registry = RemediationRegistry::with_default_actions();
+    if let Some(scene) = fallback_scene {
+        registry = registry.with_fallback_scene(scene);
+    }
+
+    let policy = RemediationPolicy {
+        dry_run,
+        confirmation: if yes { ConfirmationPolicy::Never } else { ConfirmationPolicy::Disruptive },
+        max_attempts,
+        ..Default::default()
+    };
+
+    let engine = RemediationEngine::new(obs)
+        .with_registry(registry)
+        .with_policy(policy)
+        .with_confirmation_handler(Arc::new(StdinConfirmation))
+        .with_verifier(health, detector);
+
+    let reports = engine.remediate_all(&report.anomalies).await;
+
+    println!("\n🔧 REMEDIATION{}", if dry_run { " (dry-run)" } else { "" });
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    if reports.is_empty() {
+        println!("✅ No anomalies to fix");
+        return Ok(());
+    }
+
+    for report in &reports {
+        let action = report.action.as_ref()
+            .map(|a| a.describe())
+            .unwrap_or_else(|| "-".to_string());
+        println!("\n{:?}", report.anomaly_type);
+        println!("  Action: {}", action);
+        println!("  Outcome: {:?}", report.outcome);
+        for change in &report.changes {
+            println!("    → {}", change);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_rules_test(files: &[PathBuf], rules: Option<&std::path::Path>) -> Result<()> {
+    let rule_override = rules.map(RuleConfig::load).transpose()?;
+    let mut failed = 0;
+
+    for file in files {
+        let scenario = Scenario::load(file)?;
+        let rules = rule_override.as_ref().unwrap_or(&scenario.rules);
+        let report = ScenarioRunner::new(AnomalyDetector::from_config(rules)).run(&scenario)?;
+
+        println!("\n🧪 {} ({})", report.name, file.display());
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        for step in &report.steps {
+            let status = if step.passed() { "✅" } else { "❌" };
+            println!("{} step {} @ {}s: {} anomalies", status, step.index, step.at, step.detected.len());
+            for expected in &step.missing {
+                match expected.severity {
+                    Some(severity) => println!("    missing: {:?} ({:?})", expected.anomaly_type, severity),
+                    None => println!("    missing: {:?}", expected.anomaly_type),
+                }
+            }
+            for (anomaly_type, severity) in &step.unexpected {
+                println!("    unexpected: {:?} ({:?})", anomaly_type, severity);
+            }
+        }
+
+        if !report.passed() {
+            failed += 1;
+        }
+    }
+
+    println!("\n{}/{} scenario(s) passed", files.len() - failed, files.len());
+
+    if failed > 0 {
+        anyhow::bail!("{} scenario(s) failed", failed);
+    }
+    Ok(())
+}
+
+async fn cmd_monitor(
+    cli: &Cli,
+    interval: u64,
+    record: Option<&std::path::Path>,
+    samples: Option<usize>,
+    store: Option<&std::path::Path>,
+    metrics_listen: Option<&str>,
+    push: &PushOptions,
+) -> Result<()> {
+    info!("Starting monitoring every {}s...", interval);
+
+    let (obs, monitor) = cli.ports();
+    // El sampler lee el hardware; el servicio trabaja sobre sus muestras
+    let sampler = MonitorSampler::new(monitor)
+        .with_interval(std::time::Duration::from_secs(interval.max(1)))
+        .spawn();
+    let service = HealthCheckService::new(obs, sampler.monitor()).with_network(cli.network());
+    let detector = AnomalyDetector::with_default_rules();
+    let recorder = record.map(NdjsonTelemetryRecorder::create).transpose()?;
+    let store = match store {
+        Some(path) => Some(SqliteMetricsStore::open(path).await?),
+        None => None,
+    };
+    let exporter = Arc::new(OpenMetricsExporter::new());
+    let metrics_server = match metrics_listen {
+        Some(addr) => {
+            let server = MetricsServer::bind(addr, Arc::clone(&exporter)).await?;
+            println!("📊 OpenMetrics on http://{}/metrics", server.local_addr()?);
+            Some(server.spawn())
+        }
+        None => None,
+    };
+    let sinks = push
+        .sinks(|| sampler.monitor().detect_hardware().ok().map(|hardware| hardware.hostname))
+        .await?;
+    let flusher = (!sinks.is_empty()).then(|| {
+        let sinks = sinks.clone();
+        let every = std::time::Duration::from_secs(push.push_interval.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                for sink in &sinks {
+                    if let Err(e) = sink.flush().await {
+                        error!("Metric push failed: {:#}", e);
+                    }
+                }
+            }
+        })
+    });
+    let retention = RetentionPolicy::default();
+    let mut last_retention: Option<std::time::Instant> = None;
+    let mut tracker = AnomalyTracker::new();
+
+    println!("\n📡 MONITORING (Ctrl+C to stop)");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let mut updates = sampler.subscribe();
+    let mut taken = 0;
+
+    loop {
+        let snapshot = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(snapshot) => snapshot,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    error!("Monitor fell behind, skipped {} sample(s)", skipped);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        let sample = match service.sample().await {
+            Ok(sample) => sample,
+            Err(e) => {
+                error!("Sampling failed: {}", e);
+                continue;
+            }
+        };
+
+        if let Some(recorder) = &recorder {
+            recorder.record(&sample).await?;
+        }
+
+        for sink in &sinks {
+            // Un destino caído no detiene el monitoreo
+            if let Err(e) = sink.record(&sample).await {
+                error!("Metric push failed: {:#}", e);
+            }
+        }
+
+        let anomalies = detector.scan(&sample.context);
+        exporter.update(&sample, &anomalies);
+        if let Some(store) = &store {
+            store.record(&sample).await?;
+
+            // Solo transiciones: una anomalía persistente se guarda una vez por severidad
+            let transitions = tracker.update(&anomalies, sample.timestamp);
+            if !transitions.is_empty() || taken == 0 {
+                let raised: Vec<_> = transitions
+                    .into_iter()
+                    .filter_map(|transition| match transition {
+                        AnomalyTransition::Raised { anomaly } => Some(anomaly),
+                        AnomalyTransition::Cleared { .. } => None,
+                    })
+                    .collect();
+                store.record_anomalies(&raised).await?;
+                let report = HealthReport::from_anomalies(
+                    anomalies.clone(),
+                    sample.ram_available_gb.unwrap_or(f64::MAX),
+                    sample.timestamp,
+                );
+                store.record_health(&report).await?;
+            }
+
+            if !matches!(last_retention, Some(at) if at.elapsed() < std::time::Duration::from_secs(3600)) {
+                store.apply_retention(&retention, chrono::Utc::now()).await?;
+                last_retention = Some(std::time::Instant::now());
+            }
+        }
+        let gpus = &snapshot.gpus;
+        let context = &sample.context;
+        let frequency = context.cpu_frequency.as_ref().map_or(String::new(), |f| {
+            format!(
+                " {:.1} GHz{}",
+                f.average_mhz().unwrap_or(0.0) / 1000.0,
+                f.throttling_ratio()
+                    .filter(|ratio| *ratio > 0.0)
+                    .map_or(String::new(), |ratio| format!(" (-{:.0}% base)", ratio * 100.0))
+            )
+        });
+        println!(
+            "[{}] CPU {:.0}% {:.0}°C{} | GPU {:.0}°C | RAM {:.0}% | Dropped {:.2}% | {} anomalies",
+            sample.timestamp.format("%H:%M:%S"),
+            context.cpu_usage,
+            context.cpu_temp,
+            frequency,
+            context.gpu_temp,
+            context.memory_used_percent,
+            context.obs_dropped_frames_percent,
+            anomalies.len()
+        );
+        for (index, gpu) in gpus.iter().enumerate() {
+            println!(
+                "    GPU #{} {}: {} | {}",
+                index,
+                gpu.name,
+                gpu.temperature_c.map_or("--".to_string(), |t| format!("{:.0}°C", t)),
+                gpu.usage_percent.map_or("--".to_string(), |u| format!("{:.0}%", u))
+            );
+        }
+        if let Some(network) = &context.network {
+            let latency = network.latency.as_ref().map_or("--".to_string(), |l| {
+                format!("{} {:.0} ms ±{:.0} ms ({}/{} failed)", l.endpoint, l.avg_ms, l.jitter_ms, l.failures, l.attempts)
+            });
+            println!(
+                "    NET tx {:.0} kbps rx {:.0} kbps | ingest {} | upload ~{}",
+                network.tx_kbps(),
+                network.rx_kbps(),
+                latency,
+                network.estimated_upload_kbps.map_or("--".to_string(), |u| format!("{} kbps", u))
+            );
+        }
+        if let Some(power) = context.power.as_ref().filter(|p| p.is_power_saving()) {
+            println!(
+                "    POWER {}{}{}",
+                if power.on_battery { "battery" } else { "AC" },
+                power.battery_percent.map_or(String::new(), |p| format!(" {:.0}%", p)),
+                power.profile.map_or(String::new(), |p| format!(" | profile {:?}", p))
+            );
+        }
+        if let Some(process) = &context.obs_process {
+            match &process.main {
+                Some(main) => println!(
+                    "    OBS pid {}: CPU {:.0}% | RSS {:.0} MB | {} helper(s) | threads {} | fds {} | up {}s | restarts {}",
+                    main.pid,
+                    process.total_cpu_percent(),
+                    process.total_memory_mb(),
+                    process.helpers.len(),
+                    main.threads.map_or("--".to_string(), |t| t.to_string()),
+                    main.open_files.map_or("--".to_string(), |f| f.to_string()),
+                    process.uptime_secs,
+                    process.restarts
+                ),
+                None => println!("    OBS process not running"),
+            }
+        }
+        for anomaly in &anomalies {
+            println!("    [{:?}] {:?}: {}", anomaly.severity, anomaly.anomaly_type, anomaly.details);
+        }
+
+        taken += 1;
+        if samples.is_some_and(|n| taken >= n) {
+            break;
+        }
+    }
+
+    if let Some(recorder) = &recorder {
+        recorder.flush().await?;
+        println!("\n💾 Recorded {} sample(s) to {}", taken, recorder.path().display());
+    }
+    if let Some(store) = &store {
+        println!("\n🗄️  Stored {} sample(s) in {}", taken, store.path().display());
+    }
+    if let Some(server) = metrics_server {
+        server.abort();
+    }
+    if let Some(flusher) = flusher {
+        flusher.abort();
+    }
+    for sink in &sinks {
+        if let Err(e) = sink.flush().await {
+            error!("Final metric push failed: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_history(
+    metrics: &[Metric],
+    since: std::time::Duration,
+    step: Option<std::time::Duration>,
+    store: Option<&std::path::Path>,
+    prune: bool,
+) -> Result<()> {
+    let path = store.map(PathBuf::from).unwrap_or_else(SqliteMetricsStore::default_path);
+    let store = SqliteMetricsStore::open(&path).await?;
+
+    if prune {
+        let summary = store.apply_retention(&RetentionPolicy::default(), chrono::Utc::now()).await?;
+        println!(
+            "🧹 Rolled up {} sample(s) into {}, deleted {} expired row(s)",
+            summary.samples_rolled_up, summary.rollups_created, summary.rows_deleted
+        );
+    }
+
+    let range = TimeRange::last(since);
+    let step = step.unwrap_or(since / 20).max(std::time::Duration::from_secs(1));
+    let metrics = if metrics.is_empty() {
+        &[
+            Metric::CpuUsagePercent,
+            Metric::CpuTempCelsius,
+            Metric::GpuTempCelsius,
+            Metric::MemoryUsedPercent,
+            Metric::ObsDroppedFramesPercent,
+        ][..]
+    } else {
+        metrics
+    };
+
+    println!("\n📈 HISTORY: last {}s, {}s step ({})", since.as_secs(), step.as_secs(), path.display());
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    for metric in metrics {
+        let points = store.query_metric(*metric, range, Some(step)).await?;
+        let samples: u64 = points.iter().map(|p| p.samples).sum();
+        if samples == 0 {
+            println!("{:<28} no data", metric.name());
+            continue;
+        }
+        let avg = points.iter().map(|p| p.avg * p.samples as f64).sum::<f64>() / samples as f64;
+        let min = points.iter().map(|p| p.min).fold(f64::INFINITY, f64::min);
+        let max = points.iter().map(|p| p.max).fold(f64::NEG_INFINITY, f64::max);
+        println!(
+            "{:<28} {} avg {:.1} | min {:.1} | max {:.1} | {} sample(s)",
+            metric.name(),
+            sparkline(&points.iter().map(|p| p.avg).collect::<Vec<_>>()),
+            avg,
+            min,
+            max,
+            samples
+        );
+    }
+
+    let anomalies = store.query_anomalies(range).await?;
+    println!("\n🔍 ANOMALIES: {}", anomalies.len());
+    for anomaly in &anomalies {
+        println!(
+            "  [{}] [{:?}] {:?}: {}",
+            anomaly.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            anomaly.severity,
+            anomaly.anomaly_type,
+            anomaly.details
+        );
+    }
+
+    let reports = store.query_health(range).await?;
+    let unhealthy = reports.iter().filter(|r| !r.is_healthy).count();
+    println!("\n🏥 HEALTH REPORTS: {} ({} unhealthy)", reports.len(), unhealthy);
+    if let Some(last) = reports.last() {
+        println!("  Latest: {} - {}", last.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S"), last.summary());
+    }
+
+    Ok(())
+}
+
+async fn cmd_join(
+    cli: &Cli,
+    server: &str,
+    token: Option<&str>,
+    host_id: Option<&str>,
+    rules: Option<&std::path::Path>,
+) -> Result<()> {
+    let config = rules.map(RuleConfig::load).transpose()?.unwrap_or_default();
+    let (obs, monitor) = cli.ports();
+    let sampler = MonitorSampler::new(monitor).spawn();
+    let hardware = sampler.monitor().detect_hardware()?;
+    let host_id = host_id.map_or_else(|| hardware.hostname.clone(), str::to_string);
+
+    let client = match token {
+        Some(token) => FleetClient::new(server).with_token(token),
+        None => FleetClient::new(server),
+    };
+    let health = HealthCheckService::new(Arc::clone(&obs), sampler.monitor())
+        .with_network(cli.network())
+        .with_detector(Arc::new(AnomalyDetector::from_config(&config)));
+    let agent = FleetAgent::new(Arc::new(client), obs, health, AgentRegistration::new(host_id, hardware));
+
+    // Un servidor o token incorrecto se detecta antes de entrar en el bucle
+    let ack = agent.register().await?;
+    println!(
+        "🛰️  Joined the fleet at {} as '{}', heartbeat every {}s (Ctrl+C to leave)",
+        server, ack.host_id, ack.heartbeat_interval_secs
+    );
+
+    tokio::select! {
+        _ = agent.run() => Ok(()),
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+async fn cmd_tokens(command: &TokenCommands, store: Option<&std::path::Path>) -> Result<()> {
+    let path = store.map(PathBuf::from).unwrap_or_else(SqliteTokenStore::default_path);
+    let store = SqliteTokenStore::open(&path).await?;
+    let local = |time: chrono::DateTime<chrono::Utc>| {
+        time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string()
+    };
+
+    match command {
+        TokenCommands::Create { name, scope } => {
+            let (token, secret) = store.create_token(name, *scope).await?;
+            println!("🔑 Created {} token '{}' ({})", token.scope, token.name, token.id);
+            println!("\n   {}\n", secret);
+            println!("Store it now: it cannot be shown again. Send it as `Authorization: Bearer <token>`.");
+        }
+        TokenCommands::List => {
+            let tokens = store.list_tokens().await?;
+            println!("\n🔑 API TOKENS ({})", path.display());
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            if tokens.is_empty() {
+                println!("No tokens yet (create one with `obs-agent tokens create <name> --scope admin`)");
+            }
+            for token in &tokens {
+                let status = match token.revoked_at {
+                    Some(revoked) => format!("revoked {}", local(revoked)),
+                    None => match token.last_used_at {
+                        Some(used) => format!("last used {}", local(used)),
+                        None => "never used".to_string(),
+                    },
+                };
+                println!(
+                    "{:<20} {:<9} created {} | {}",
+                    token.name,
+                    token.scope.name(),
+                    local(token.created_at),
+                    status
+                );
+            }
+        }
+        TokenCommands::Revoke { name } => {
+            if store.revoke_token(name).await? {
+                println!("✅ Revoked '{}'", name);
+            } else {
+                anyhow::bail!("No active token named '{}'", name);
+            }
+        }
+        TokenCommands::Audit { since, limit } => {
+            let entries = store.query_audit(TimeRange::last(*since), *limit).await?;
+            println!("\n📜 AUDIT: last {}s ({} entries)", since.as_secs(), entries.len());
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            for entry in &entries {
+                println!(
+                    "[{}] {} {} {} by {}{}{}",
+                    local(entry.timestamp),
+                    entry.status,
+                    entry.method,
+                    entry.path,
+                    entry.token_name.as_deref().unwrap_or("<no valid token>"),
+                    entry.remote_addr.as_deref().map(|addr| format!(" from {}", addr)).unwrap_or_default(),
+                    entry.details.as_deref().map(|details| format!(" ({})", details)).unwrap_or_default(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Serie en una línea de bloques (▁ mínimo, █ máximo)
+fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            let level = if max > min { (value - min) / (max - min) } else { 0.5 };
+            BARS[((level * (BARS.len() - 1) as f64).round() as usize).min(BARS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Duraciones como `90s`, `30m`, `6h` o `7d`
+fn parse_duration(value: &str) -> std::result::Result<std::time::Duration, String> {
+    obs_agent_core::domain::models::parse_duration(value).map_err(|e| e.to_string())
+}
+
+async fn cmd_replay(file: &std::path::Path, speed: Option<f64>, rules: Option<&std::path::Path>) -> Result<()> {
+    let records = read_recording(file)?;
+    let config = rules.map(RuleConfig::load).transpose()?.unwrap_or_default();
+
+    let speed = match speed {
+        Some(1.0) => ReplaySpeed::RealTime,
+        Some(factor) => ReplaySpeed::Accelerated(factor),
+        None => ReplaySpeed::Unlimited,
+    };
+
+    println!("\n⏪ REPLAY: {} ({} samples)", file.display(), records.len());
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let replayer = TelemetryReplayer::new(AnomalyDetector::from_config(&config)).with_speed(speed);
+    let summary = replayer
+        .replay(&records, |step| {
+            let time = step.record.timestamp.format("%Y-%m-%d %H:%M:%S");
+            for anomaly in step.report.anomalies.iter().filter(|a| step.raised.contains(&a.anomaly_type)) {
+                println!("[{}] ⚠️  {:?} {:?}: {}", time, anomaly.severity, anomaly.anomaly_type, anomaly.details);
+            }
+            for anomaly_type in &step.cleared {
+                println!("[{}] ✅ {:?} cleared", time, anomaly_type);
+            }
+        })
+        .await;
+
+    println!("\n📊 SUMMARY");
+    println!("  Samples: {}", summary.samples);
+    println!("  Unhealthy samples: {}", summary.unhealthy_samples);
+    if let Some(first) = summary.first_critical {
+        println!("  First critical: {}", first);
+    }
+    for (anomaly_type, count) in &summary.anomalies_by_type {
+        println!("  {:?}: {} sample(s)", anomaly_type, count);
+    }
+
+    Ok(())
+}
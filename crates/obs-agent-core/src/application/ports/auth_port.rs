@@ -0,0 +1,28 @@
+use crate::domain::models::{ApiToken, AuditEntry, Scope, TimeRange};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Puerto para los tokens de la API y la auditoría de acciones
+///
+/// Los secretos nunca se guardan: solo su hash, y se muestran una única vez
+/// al crearlos.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Crea un token y devuelve sus datos junto al secreto en claro
+    async fn create_token(&self, name: &str, scope: Scope) -> Result<(ApiToken, String)>;
+
+    /// Todos los tokens, incluidos los revocados
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>>;
+
+    /// Revoca un token por nombre o id; `false` si no existía o ya estaba revocado
+    async fn revoke_token(&self, name_or_id: &str) -> Result<bool>;
+
+    /// Token activo que corresponde al secreto (`None` si no existe o está revocado)
+    async fn authenticate(&self, secret: &str) -> Result<Option<ApiToken>>;
+
+    /// Registra una acción en la auditoría
+    async fn record_audit(&self, entry: &AuditEntry) -> Result<()>;
+
+    /// Auditoría del rango, de la más reciente a la más antigua
+    async fn query_audit(&self, range: TimeRange, limit: usize) -> Result<Vec<AuditEntry>>;
+}
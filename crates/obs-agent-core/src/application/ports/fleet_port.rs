@@ -0,0 +1,122 @@
+use crate::domain::models::HardwareInfo;
+use crate::domain::services::{HealthReport, TelemetryRecord};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Puerto del agente hacia el servidor central de la flota
+///
+/// El agente se registra una vez y luego envía latidos; los comandos para
+/// el equipo vuelven en la respuesta al latido, así que el servidor nunca
+/// abre conexiones hacia los agentes.
+#[async_trait]
+pub trait FleetPort: Send + Sync {
+    /// Da de alta (o actualiza) el equipo en el inventario
+    async fn register(&self, registration: &AgentRegistration) -> Result<RegistrationAck>;
+
+    /// Envía un latido; `None` si el servidor no conoce el equipo y hay que
+    /// registrarse de nuevo (p.ej. tras reiniciarse el servidor)
+    async fn heartbeat(&self, host_id: &str, heartbeat: &Heartbeat) -> Result<Option<Vec<FleetCommand>>>;
+}
+
+/// Alta de un agente en el servidor central
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AgentRegistration {
+    /// Identificador estable del equipo (el hostname si no se indica otro)
+    pub host_id: String,
+    pub agent_version: String,
+    /// `HardwareInfo::fingerprint` del equipo
+    pub fingerprint: String,
+    pub hardware: HardwareInfo,
+}
+
+impl AgentRegistration {
+    pub fn new(host_id: impl Into<String>, hardware: HardwareInfo) -> Self {
+        Self {
+            host_id: host_id.into(),
+            agent_version: env!("CARGO_PKG_VERSION").to_string(),
+            fingerprint: hardware.fingerprint(),
+            hardware,
+        }
+    }
+}
+
+/// Respuesta al alta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistrationAck {
+    pub host_id: String,
+    /// Cada cuánto espera el servidor un latido
+    pub heartbeat_interval_secs: u64,
+}
+
+/// Latido periódico de un agente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Heartbeat {
+    pub timestamp: DateTime<Utc>,
+    /// Muestra de OBS y del sistema; `None` si OBS no responde
+    pub sample: Option<TelemetryRecord>,
+    /// Salud según la muestra
+    pub health: Option<HealthReport>,
+    /// Por qué no hay muestra
+    pub error: Option<String>,
+    /// Resultados de los comandos ejecutados desde el latido anterior
+    #[serde(default)]
+    pub results: Vec<CommandResult>,
+}
+
+/// Respuesta al latido: comandos pendientes para el equipo
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HeartbeatAck {
+    pub commands: Vec<FleetCommand>,
+}
+
+/// Acción que el servidor central pide a un agente
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommandKind {
+    SwitchScene { scene: String },
+    /// `stream` o `record`
+    StartOutput { output: String },
+    StopOutput { output: String },
+    RunHealthCheck,
+}
+
+impl std::fmt::Display for CommandKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SwitchScene { scene } => write!(f, "switch to scene '{}'", scene),
+            Self::StartOutput { output } => write!(f, "start {}", output),
+            Self::StopOutput { output } => write!(f, "stop {}", output),
+            Self::RunHealthCheck => f.write_str("run a health check"),
+        }
+    }
+}
+
+/// Comando en cola para un equipo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FleetCommand {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub kind: CommandKind,
+    pub issued_at: DateTime<Utc>,
+    /// Token que lo pidió (o `local` sin autenticación)
+    pub issued_by: String,
+}
+
+/// Resultado de un comando ejecutado por el agente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CommandResult {
+    pub command_id: Uuid,
+    pub success: bool,
+    pub message: String,
+    pub finished_at: DateTime<Utc>,
+}
@@ -0,0 +1,32 @@
+use crate::application::ports::TelemetrySink;
+use crate::domain::models::{Anomaly, Metric, MetricPoint, RetentionPolicy, RetentionSummary, TimeRange};
+use crate::domain::services::HealthReport;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Puerto para el histórico de métricas, anomalías y reportes de salud
+///
+/// Las muestras se registran vía [`TelemetrySink`]; cada una se descompone en
+/// las [`Metric`] que contenga.
+#[async_trait]
+pub trait MetricsStore: TelemetrySink {
+    /// Registra anomalías detectadas (con su propio timestamp)
+    async fn record_anomalies(&self, anomalies: &[Anomaly]) -> Result<()>;
+
+    /// Registra un reporte de salud completo
+    async fn record_health(&self, report: &HealthReport) -> Result<()>;
+
+    /// Serie de una métrica en el rango; con `step`, agregada en intervalos de ese tamaño
+    async fn query_metric(&self, metric: Metric, range: TimeRange, step: Option<Duration>) -> Result<Vec<MetricPoint>>;
+
+    /// Anomalías del rango, de la más antigua a la más reciente
+    async fn query_anomalies(&self, range: TimeRange) -> Result<Vec<Anomaly>>;
+
+    /// Reportes de salud del rango, del más antiguo al más reciente
+    async fn query_health(&self, range: TimeRange) -> Result<Vec<HealthReport>>;
+
+    /// Agrega y borra el histórico antiguo según la política
+    async fn apply_retention(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<RetentionSummary>;
+}
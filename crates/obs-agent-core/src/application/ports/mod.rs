@@ -1,9 +1,19 @@
 pub mod obs_port;
 pub mod ai_port;
+pub mod auth_port;
+pub mod fleet_port;
+pub mod metrics_port;
 pub mod monitor_port;
+pub mod network_port;
 pub mod plugin_port;
+pub mod telemetry_port;
 
 pub use obs_port::*;
 pub use ai_port::*;
+pub use auth_port::*;
+pub use fleet_port::*;
+pub use metrics_port::*;
 pub use monitor_port::*;
+pub use network_port::*;
 pub use plugin_port::*;
+pub use telemetry_port::*;
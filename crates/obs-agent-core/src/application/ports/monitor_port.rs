@@ -3,21 +3,51 @@ use anyhow::Result;
 
 /// Puerto para monitorear hardware del sistema
 pub trait MonitorPort: Send + Sync {
-    /// Obtiene temperatura de CPU
-    fn get_cpu_temp(&self) -> Result<f32>;
+    /// Obtiene temperatura de CPU (`None` si no hay sensor)
+    fn get_cpu_temp(&self) -> Result<Option<f32>>;
 
-    /// Obtiene temperatura de GPU
-    fn get_gpu_temp(&self) -> Result<f32>;
+    /// Obtiene la temperatura de cada core/CCD (vacío si la plataforma no la expone)
+    fn get_cpu_core_temps(&self) -> Result<Vec<CoreTemperature>> {
+        Ok(vec![])
+    }
+
+    /// Obtiene la frecuencia actual de cada core (`None` si la plataforma no la expone)
+    fn get_cpu_frequency(&self) -> Result<Option<CpuFrequencyStats>> {
+        Ok(None)
+    }
+
+    /// Obtiene la temperatura de la GPU más caliente (`None` si no hay sensor)
+    fn get_gpu_temp(&self) -> Result<Option<f32>>;
+
+    /// Obtiene el estado actual de cada GPU (temperatura, uso, encoder)
+    fn get_gpus(&self) -> Result<Vec<GPUInfo>> {
+        Ok(vec![])
+    }
 
     /// Obtiene uso de CPU
     fn get_cpu_usage(&self) -> Result<f32>;
 
+    /// Obtiene la fuente de alimentación y el perfil de energía (`None` si se desconoce)
+    fn get_power_status(&self) -> Result<Option<PowerStatus>> {
+        Ok(None)
+    }
+
     /// Obtiene información de memoria
     fn get_memory_info(&self) -> Result<RAMInfo>;
 
     /// Obtiene información de disco
     fn get_disk_space(&self) -> Result<DiskInfo>;
 
+    /// Obtiene información del disco que contiene `path`
+    fn get_disk_space_for(&self, _path: &std::path::Path) -> Result<DiskInfo> {
+        self.get_disk_space()
+    }
+
+    /// Obtiene el uso de recursos del proceso de OBS (`None` si no se monitorea)
+    fn get_obs_process(&self) -> Result<Option<ObsProcessInfo>> {
+        Ok(None)
+    }
+
     /// Detecta todo el hardware
     fn detect_hardware(&self) -> Result<HardwareInfo>;
 }
@@ -28,10 +58,40 @@ pub struct DiskInfo {
     pub total_gb: f64,
     pub free_gb: f64,
     pub used_percent: f64,
+    /// Punto de montaje del disco, si se conoce
+    pub mount_point: Option<String>,
 }
 
 impl DiskInfo {
     pub fn is_low_space(&self) -> bool {
         self.free_gb < 10.0 || self.used_percent > 90.0
     }
+
+    /// Minutos de grabación que caben en el espacio libre a `bitrate_kbps`
+    pub fn minutes_until_full(&self, bitrate_kbps: u32) -> Option<f64> {
+        if bitrate_kbps == 0 {
+            return None;
+        }
+        let free_bits = self.free_gb * 1_073_741_824.0 * 8.0;
+        Some(free_bits / (bitrate_kbps as f64 * 1000.0) / 60.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minutes_until_full() {
+        let disk = DiskInfo {
+            total_gb: 100.0,
+            free_gb: 10.0,
+            used_percent: 90.0,
+            mount_point: None,
+        };
+        // 10 GiB a 6000 kbps ≈ 238 minutos
+        let minutes = disk.minutes_until_full(6000).unwrap();
+        assert!((minutes - 238.6).abs() < 0.1, "{}", minutes);
+        assert_eq!(disk.minutes_until_full(0), None);
+    }
 }
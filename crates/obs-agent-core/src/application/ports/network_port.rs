@@ -0,0 +1,17 @@
+use crate::domain::models::{BandwidthTest, IngestEndpoint, NetworkStats};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Puerto para monitorear la red del equipo
+#[async_trait]
+pub trait NetworkPort: Send + Sync {
+    /// Muestrea el tráfico de las interfaces y, si se indica, la latencia
+    /// TCP al servidor de ingesta
+    async fn sample(&self, ingest: Option<&IngestEndpoint>) -> Result<NetworkStats>;
+
+    /// Mide la subida enviando datos a `endpoint` (`host:port`) durante `duration`
+    async fn bandwidth_test(&self, _endpoint: &str, _duration: Duration) -> Result<BandwidthTest> {
+        anyhow::bail!("Bandwidth test not supported")
+    }
+}
@@ -11,6 +11,11 @@ pub trait OBSPort: Send + Sync {
     /// Desconecta del servidor
     async fn disconnect(&self) -> Result<()>;
 
+    /// OBS corre en esta máquina (sus rutas y discos son los locales)
+    fn is_local(&self) -> bool {
+        true
+    }
+
     /// Obtiene todas las escenas
     async fn get_scenes(&self) -> Result<Vec<Scene>>;
 
@@ -31,6 +36,46 @@ pub trait OBSPort: Send + Sync {
 
     /// Toma screenshot de una fuente
     async fn take_screenshot(&self, source: &str) -> Result<Vec<u8>>;
+
+    /// Cambia la escena de programa actual
+    async fn set_current_scene(&self, scene_name: &str) -> Result<()>;
+
+    /// Muestra u oculta una fuente dentro de una escena
+    async fn set_source_visible(&self, scene_name: &str, source_name: &str, visible: bool) -> Result<()>;
+
+    /// Obtiene la configuración del encoder de streaming
+    async fn get_encoder_settings(&self) -> Result<EncoderSettings>;
+
+    /// Configura el encoder de streaming
+    async fn set_encoder_settings(&self, settings: &EncoderSettings) -> Result<()>;
+
+    /// Obtiene directorio y bitrate de grabación
+    async fn get_recording_settings(&self) -> Result<RecordingSettings>;
+
+    /// Obtiene el destino de transmisión (servicio y servidor de ingesta)
+    async fn get_stream_settings(&self) -> Result<StreamSettings>;
+
+    /// Estado de las salidas de stream y grabación
+    async fn get_output_status(&self) -> Result<Vec<OutputStatus>> {
+        Ok(Vec::new())
+    }
+
+    /// Inicia o detiene una salida (`stream` o `record`)
+    async fn set_output_active(&self, output: &str, active: bool) -> Result<()> {
+        let _ = active;
+        anyhow::bail!("Controlling the '{}' output is not supported", output)
+    }
+
+    /// Indica si el replay buffer está activo
+    async fn get_replay_buffer_active(&self) -> Result<bool> {
+        anyhow::bail!("Reading the replay buffer status is not supported")
+    }
+
+    /// Inicia el replay buffer
+    async fn start_replay_buffer(&self) -> Result<()>;
+
+    /// Detiene el replay buffer
+    async fn stop_replay_buffer(&self) -> Result<()>;
 }
 
 /// Reporte de validación de escena
@@ -0,0 +1,13 @@
+use crate::domain::services::TelemetryRecord;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Puerto para destinos de telemetría (archivos, bases de datos, exportadores)
+#[async_trait]
+pub trait TelemetrySink: Send + Sync {
+    /// Registra una muestra
+    async fn record(&self, record: &TelemetryRecord) -> Result<()>;
+
+    /// Vacía cualquier buffer pendiente
+    async fn flush(&self) -> Result<()>;
+}
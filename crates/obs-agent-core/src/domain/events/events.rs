@@ -5,6 +5,7 @@ use uuid::Uuid;
 
 /// Eventos del dominio
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(tag = "type")]
 pub enum DomainEvent {
     AnomalyDetected(AnomalyDetectedEvent),
@@ -15,6 +16,7 @@ pub enum DomainEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AnomalyDetectedEvent {
     pub event_id: Uuid,
     pub timestamp: DateTime<Utc>,
@@ -22,6 +24,7 @@ pub struct AnomalyDetectedEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct StreamStartedEvent {
     pub event_id: Uuid,
     pub timestamp: DateTime<Utc>,
@@ -29,6 +32,7 @@ pub struct StreamStartedEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct StreamStoppedEvent {
     pub event_id: Uuid,
     pub timestamp: DateTime<Utc>,
@@ -36,6 +40,7 @@ pub struct StreamStoppedEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HealthCheckCompletedEvent {
     pub event_id: Uuid,
     pub timestamp: DateTime<Utc>,
@@ -44,6 +49,7 @@ pub struct HealthCheckCompletedEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ConfigurationChangedEvent {
     pub event_id: Uuid,
     pub timestamp: DateTime<Utc>,
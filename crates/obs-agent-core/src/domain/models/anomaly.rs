@@ -1,9 +1,11 @@
+use super::SourceRef;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Tipos de anomalías detectables
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum AnomalyType {
     /// Fuente desconectada o no disponible
     MissingSource,
@@ -11,6 +13,8 @@ pub enum AnomalyType {
     HighCPUTemp,
     /// Temperatura de GPU alta
     HighGPUTemp,
+    /// CPU por debajo de su frecuencia base bajo carga (thermal/power throttling)
+    CpuThrottling,
     /// Fuga de memoria detectada
     MemoryLeak,
     /// Frames perdidos en encoder
@@ -23,16 +27,21 @@ pub enum AnomalyType {
     DiskSpaceLow,
     /// Plugin crasheado
     PluginCrash,
+    /// OBS se cerró inesperadamente o se reinició
+    ObsCrash,
     /// Configuración inválida
     InvalidConfig,
     /// Red inestable
     NetworkUnstable,
+    /// Equipo en batería o en perfil de ahorro de energía
+    PowerSaving,
     /// Bitrate inconsistente
     BitrateIssue,
 }
 
 /// Severidad de la anomalía
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum Severity {
     Info,
     Warning,
@@ -41,6 +50,7 @@ pub enum Severity {
 
 /// Anomalía detectada en el sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Anomaly {
     pub id: Uuid,
     pub anomaly_type: AnomalyType,
@@ -48,6 +58,9 @@ pub struct Anomaly {
     pub timestamp: DateTime<Utc>,
     pub details: String,
     pub source: Option<String>,
+    /// Fuentes afectadas, para las acciones que actúan sobre ellas
+    #[serde(default)]
+    pub sources: Vec<SourceRef>,
     pub recommended_action: String,
     pub auto_fixable: bool,
 }
@@ -61,6 +74,7 @@ impl Default for Anomaly {
             timestamp: Utc::now(),
             details: String::new(),
             source: None,
+            sources: Vec::new(),
             recommended_action: String::new(),
             auto_fixable: false,
         }
@@ -80,6 +94,7 @@ impl Anomaly {
             timestamp: Utc::now(),
             details: details.into(),
             source: None,
+            sources: Vec::new(),
             recommended_action: String::new(),
             auto_fixable: false,
         }
@@ -90,6 +105,14 @@ impl Anomaly {
         self
     }
 
+    /// Fuentes afectadas; `source` pasa a ser su lista legible
+    pub fn with_sources(mut self, sources: Vec<SourceRef>) -> Self {
+        let names: Vec<String> = sources.iter().map(ToString::to_string).collect();
+        self.source = Some(names.join(", "));
+        self.sources = sources;
+        self
+    }
+
     pub fn with_action(mut self, action: impl Into<String>) -> Self {
         self.recommended_action = action.into();
         self
@@ -101,6 +124,22 @@ impl Anomaly {
     }
 }
 
+/// Cambio en las anomalías activas entre dos muestras
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "transition", rename_all = "snake_case")]
+pub enum AnomalyTransition {
+    /// Aparece (o cambia de severidad)
+    Raised { anomaly: Anomaly },
+    /// Deja de detectarse
+    Cleared {
+        anomaly_type: AnomalyType,
+        severity: Severity,
+        source: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
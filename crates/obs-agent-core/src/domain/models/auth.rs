@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Nivel de acceso de un token de la API (cada nivel incluye los anteriores)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Solo lectura: hardware, escenas, estadísticas, salud, eventos
+    Read,
+    /// Además cambia escenas y ejecuta acciones sobre OBS
+    Operator,
+    /// Además consulta la auditoría y administra el servidor
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::Read, Scope::Operator, Scope::Admin];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Operator => "operator",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// El token con este alcance puede usar algo que exige `required`
+    pub fn allows(&self, required: Scope) -> bool {
+        *self >= required
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Scope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "read" | "read-only" | "readonly" => Ok(Self::Read),
+            "operator" => Ok(Self::Operator),
+            "admin" => Ok(Self::Admin),
+            _ => anyhow::bail!("Unknown scope '{}' (read, operator, admin)", s),
+        }
+    }
+}
+
+/// Token de la API (sin el secreto: solo se guarda su hash)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    /// Nombre único para identificarlo en la auditoría y revocarlo
+    pub name: String,
+    pub scope: Scope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
+
+/// Registro de quién ejecutó (o intentó ejecutar) una acción que modifica algo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// `None` si la petición no traía un token válido
+    pub token_id: Option<String>,
+    pub token_name: Option<String>,
+    pub method: String,
+    pub path: String,
+    /// Código HTTP de la respuesta
+    pub status: u16,
+    /// Detalle de la acción (p.ej. la escena elegida)
+    pub details: Option<String>,
+    pub remote_addr: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_hierarchy() {
+        assert!(Scope::Admin.allows(Scope::Operator));
+        assert!(Scope::Operator.allows(Scope::Read));
+        assert!(!Scope::Read.allows(Scope::Operator));
+        assert!(!Scope::Operator.allows(Scope::Admin));
+
+        for scope in Scope::ALL {
+            assert_eq!(scope.name().parse::<Scope>().unwrap(), scope);
+        }
+        assert!("root".parse::<Scope>().is_err());
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// Información de CPU
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CPUInfo {
     pub name: String,
     pub brand: String,
@@ -11,8 +12,119 @@ pub struct CPUInfo {
     pub arch: String,
 }
 
+/// Lectura de un sensor de temperatura de CPU
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoreTemperature {
+    /// Etiqueta del sensor (p.ej. "Core 3", "Tccd1")
+    pub label: String,
+    /// Índice del core si el sensor corresponde a uno concreto
+    pub core: Option<usize>,
+    pub celsius: f32,
+}
+
+/// Frecuencia actual de un core frente a sus límites (cpufreq)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct CoreFrequency {
+    pub core: usize,
+    pub current_mhz: f64,
+    /// Frecuencia base/nominal (sin turbo), si el driver la expone
+    pub base_mhz: Option<f64>,
+    pub max_mhz: Option<f64>,
+    /// Uso del core desde la muestra anterior, si se conoce
+    pub usage_percent: Option<f32>,
+}
+
+/// Uso a partir del cual un core cuenta como ocupado para medir throttling
+pub const BUSY_CORE_PERCENT: f32 = 50.0;
+
+impl CoreFrequency {
+    /// El core está bajo carga (sin uso conocido se considera que sí)
+    pub fn is_busy(&self) -> bool {
+        !matches!(self.usage_percent, Some(usage) if usage < BUSY_CORE_PERCENT)
+    }
+}
+
+/// Frecuencias actuales de los cores de la CPU
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct CpuFrequencyStats {
+    pub cores: Vec<CoreFrequency>,
+}
+
+impl CpuFrequencyStats {
+    pub fn average_mhz(&self) -> Option<f64> {
+        if self.cores.is_empty() {
+            return None;
+        }
+        Some(self.cores.iter().map(|c| c.current_mhz).sum::<f64>() / self.cores.len() as f64)
+    }
+
+    pub fn slowest(&self) -> Option<&CoreFrequency> {
+        self.cores.iter().min_by(|a, b| a.current_mhz.total_cmp(&b.current_mhz))
+    }
+
+    /// Fracción de la frecuencia base perdida en los cores ocupados (0 = a
+    /// base o por encima). Los cores en reposo bajan de frecuencia a propósito,
+    /// y sin frecuencia base (solo la máxima con turbo) no hay referencia fiable.
+    pub fn throttling_ratio(&self) -> Option<f64> {
+        let (current, reference) = self
+            .cores
+            .iter()
+            .filter(|core| core.is_busy())
+            .filter_map(|core| Some((core.current_mhz, core.base_mhz.filter(|mhz| *mhz > 0.0)?)))
+            .fold((0.0, 0.0), |(current, reference), (c, r)| (current + c, reference + r));
+        if reference <= 0.0 {
+            return None;
+        }
+        Some((1.0 - current / reference).clamp(0.0, 1.0))
+    }
+}
+
+/// Perfil de energía activo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum PowerProfile {
+    PowerSaver,
+    Balanced,
+    Performance,
+}
+
+impl PowerProfile {
+    /// Nombres de power-profiles-daemon (`power-saver`) y de ACPI `platform_profile` (`low-power`, `quiet`...)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "power-saver" | "low-power" | "quiet" | "cool" => Some(Self::PowerSaver),
+            "balanced" | "balanced-performance" => Some(Self::Balanced),
+            "performance" => Some(Self::Performance),
+            _ => None,
+        }
+    }
+}
+
+/// Fuente de alimentación y perfil de energía del equipo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    /// Carga de la batería (`None` en equipos sin batería)
+    pub battery_percent: Option<f32>,
+    pub profile: Option<PowerProfile>,
+}
+
+impl PowerStatus {
+    /// En batería o en modo ahorro: CPU/GPU limitadas por el sistema
+    pub fn is_power_saving(&self) -> bool {
+        self.on_battery || self.profile == Some(PowerProfile::PowerSaver)
+    }
+}
+
 /// Vendor de GPU
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum GPUVendor {
     NVIDIA,
     AMD,
@@ -20,8 +132,21 @@ pub enum GPUVendor {
     Unknown,
 }
 
+impl GPUVendor {
+    /// Vendor a partir del ID de vendor PCI
+    pub fn from_pci_vendor(vendor_id: u16) -> Self {
+        match vendor_id {
+            0x10de => Self::NVIDIA,
+            0x1002 | 0x1022 => Self::AMD,
+            0x8086 => Self::Intel,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Información de GPU
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GPUInfo {
     pub name: String,
     pub vendor: GPUVendor,
@@ -32,10 +157,29 @@ pub struct GPUInfo {
     pub supports_nvenc_hevc: bool,
     pub supports_amf: bool,
     pub supports_qsv: bool,
+    /// Soporta VA-API (Linux)
+    #[serde(default)]
+    pub supports_vaapi: bool,
+    /// Identificador PCI `vendor:device` (p.ej. "10de:2684")
+    #[serde(default)]
+    pub pci_id: Option<String>,
+    /// Temperatura en °C, si el driver la expone
+    #[serde(default)]
+    pub temperature_c: Option<f32>,
+    /// Uso de la GPU en %, si el driver lo expone
+    #[serde(default)]
+    pub usage_percent: Option<f32>,
+    /// Uso del encoder hardware en %
+    #[serde(default)]
+    pub encoder_usage_percent: Option<f32>,
+    /// Sesiones de encoding activas (NVENC)
+    #[serde(default)]
+    pub encoder_sessions: Option<u32>,
 }
 
 /// Información de RAM
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RAMInfo {
     pub total_gb: f64,
     pub available_gb: f64,
@@ -44,6 +188,7 @@ pub struct RAMInfo {
 
 /// Tipo de encoder recomendado
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum EncoderType {
     NVENC,     // NVIDIA
     AMF,       // AMD
@@ -52,27 +197,91 @@ pub enum EncoderType {
     X265,      // Software HEVC
 }
 
+impl EncoderType {
+    /// Encoder hardware que ofrece una GPU, si tiene alguno
+    fn hardware_for(gpu: &GPUInfo, cpu_cores: usize) -> Option<Self> {
+        if gpu.supports_nvenc {
+            Some(Self::NVENC)
+        } else if gpu.supports_qsv && cpu_cores >= 4 {
+            Some(Self::QSV)
+        } else if gpu.supports_amf {
+            Some(Self::AMF)
+        } else {
+            None
+        }
+    }
+
+    /// Preferencia entre encoders hardware (mayor es mejor)
+    fn rank(&self) -> u8 {
+        match self {
+            Self::NVENC => 3,
+            Self::QSV => 2,
+            Self::AMF => 1,
+            Self::X264 | Self::X265 => 0,
+        }
+    }
+
+    /// Mejor encoder entre todas las GPUs y el índice de la GPU que lo ofrece.
+    /// A igualdad, prefiere una GPU distinta a la de render para no competir con ella.
+    pub fn best_for(gpus: &[GPUInfo], render_gpu: Option<usize>, cpu_cores: usize) -> (Self, Option<usize>) {
+        gpus.iter()
+            .enumerate()
+            .filter_map(|(index, gpu)| Self::hardware_for(gpu, cpu_cores).map(|encoder| (encoder, index)))
+            .max_by_key(|(encoder, index)| (encoder.rank(), Some(*index) != render_gpu, std::cmp::Reverse(*index)))
+            .map(|(encoder, index)| (encoder, Some(index)))
+            .unwrap_or((Self::X264, None))
+    }
+}
+
 /// Información completa del sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HardwareInfo {
     pub os: String,
     pub os_version: String,
     pub hostname: String,
     pub cpu: CPUInfo,
-    pub gpu: Option<GPUInfo>,
+    /// Todas las GPUs detectadas
+    #[serde(default)]
+    pub gpus: Vec<GPUInfo>,
+    /// Índice en `gpus` de la GPU donde renderiza OBS
+    #[serde(default)]
+    pub render_gpu: Option<usize>,
+    /// Índice en `gpus` de la GPU que usa el encoder recomendado
+    #[serde(default)]
+    pub encode_gpu: Option<usize>,
     pub ram: RAMInfo,
     pub recommended_encoder: EncoderType,
     pub recommended_preset: String,
     pub recommended_resolution: (u32, u32),
     pub recommended_fps: u32,
     pub recommended_bitrate: u32,
+    /// Subida sostenida medida con una prueba de ancho de banda
+    #[serde(default)]
+    pub measured_upload_kbps: Option<u32>,
 }
 
 impl HardwareInfo {
+    pub fn render_gpu(&self) -> Option<&GPUInfo> {
+        self.render_gpu.and_then(|index| self.gpus.get(index))
+    }
+
+    pub fn encode_gpu(&self) -> Option<&GPUInfo> {
+        self.encode_gpu.and_then(|index| self.gpus.get(index))
+    }
+
+    /// Limita el bitrate recomendado a una fracción segura de la subida medida
+    pub fn limit_to_upload(&mut self, upload_kbps: u32) {
+        self.measured_upload_kbps = Some(upload_kbps);
+        self.recommended_bitrate = self.recommended_bitrate.min(super::max_stream_bitrate(upload_kbps));
+    }
+
+    /// Hay alguna GPU con un encoder que `EncoderType` sepa recomendar
+    /// (VA-API solo no cuenta: no hay `EncoderType` para él)
     pub fn has_hardware_encoder(&self) -> bool {
-        self.gpu.as_ref().map_or(false, |gpu| {
-            gpu.supports_nvenc || gpu.supports_amf || gpu.supports_qsv
-        })
+        self.gpus
+            .iter()
+            .any(|gpu| gpu.supports_nvenc || gpu.supports_amf || gpu.supports_qsv)
     }
 
     pub fn can_handle_1080p(&self) -> bool {
@@ -82,4 +291,182 @@ impl HardwareInfo {
     pub fn can_handle_60fps(&self) -> bool {
         self.has_hardware_encoder() || self.cpu.cores_physical >= 8
     }
+
+    /// Huella del equipo: cambia con la CPU, las GPUs, la RAM o el sistema,
+    /// no con la carga, las temperaturas ni las actualizaciones del sistema
+    pub fn fingerprint(&self) -> String {
+        let mut gpus: Vec<&str> = self.gpus.iter().map(|gpu| gpu.name.as_str()).collect();
+        gpus.sort_unstable();
+        let canonical = format!(
+            "{}|{}|{}|{}|{}|{:.0}|{}",
+            self.os,
+            self.cpu.brand,
+            self.cpu.arch,
+            self.cpu.cores_physical,
+            self.cpu.cores_logical,
+            self.ram.total_gb,
+            gpus.join(",")
+        );
+        // FNV-1a: estable entre versiones y plataformas, a diferencia de `DefaultHasher`
+        let hash = canonical
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        format!("{:016x}", hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(vendor: GPUVendor, nvenc: bool, amf: bool, qsv: bool) -> GPUInfo {
+        GPUInfo {
+            name: format!("{:?}", vendor),
+            vendor,
+            memory_total_mb: 0,
+            memory_free_mb: 0,
+            driver_version: String::new(),
+            supports_nvenc: nvenc,
+            supports_nvenc_hevc: nvenc,
+            supports_amf: amf,
+            supports_qsv: qsv,
+            supports_vaapi: amf || qsv,
+            pci_id: None,
+            temperature_c: None,
+            usage_percent: None,
+            encoder_usage_percent: None,
+            encoder_sessions: None,
+        }
+    }
+
+    #[test]
+    fn test_throttling_ratio() {
+        let core = |core, current_mhz, base_mhz| CoreFrequency {
+            core,
+            current_mhz,
+            base_mhz,
+            max_mhz: Some(5000.0),
+            usage_percent: None,
+        };
+        let stats = CpuFrequencyStats {
+            cores: vec![core(0, 1800.0, Some(3000.0)), core(1, 2400.0, Some(3000.0))],
+        };
+        assert_eq!(stats.average_mhz(), Some(2100.0));
+        assert_eq!(stats.slowest().unwrap().core, 0);
+        assert!((stats.throttling_ratio().unwrap() - 0.3).abs() < 1e-9);
+
+        // Turbo por encima de la base no cuenta como throttling
+        let boosting = CpuFrequencyStats {
+            cores: vec![core(0, 4200.0, Some(3000.0))],
+        };
+        assert_eq!(boosting.throttling_ratio(), Some(0.0));
+        assert_eq!(CpuFrequencyStats::default().throttling_ratio(), None);
+
+        // Sin frecuencia base no se estima (la máxima incluye turbo)
+        let no_base = CpuFrequencyStats {
+            cores: vec![core(0, 1800.0, None)],
+        };
+        assert_eq!(no_base.throttling_ratio(), None);
+
+        // Solo cuentan los cores ocupados: uno ocupado a base y otro en reposo a 800 MHz
+        let busy = |core, current_mhz, usage| CoreFrequency {
+            usage_percent: Some(usage),
+            ..CoreFrequency { core, current_mhz, base_mhz: Some(3000.0), max_mhz: None, usage_percent: None }
+        };
+        let mixed = CpuFrequencyStats {
+            cores: vec![busy(0, 3000.0, 95.0), busy(1, 800.0, 3.0)],
+        };
+        assert_eq!(mixed.throttling_ratio(), Some(0.0));
+        let idle = CpuFrequencyStats {
+            cores: vec![busy(0, 800.0, 3.0)],
+        };
+        assert_eq!(idle.throttling_ratio(), None);
+    }
+
+    #[test]
+    fn test_best_encoder_across_gpus() {
+        let intel = gpu(GPUVendor::Intel, false, false, true);
+        let amd = gpu(GPUVendor::AMD, false, true, false);
+        let nvidia = gpu(GPUVendor::NVIDIA, true, false, false);
+
+        // iGPU QSV codifica mientras la AMD discreta renderiza
+        assert_eq!(
+            EncoderType::best_for(&[intel.clone(), amd.clone()], Some(1), 8),
+            (EncoderType::QSV, Some(0))
+        );
+        // NVENC gana aunque sea la GPU de render
+        assert_eq!(
+            EncoderType::best_for(&[intel.clone(), nvidia], Some(1), 8),
+            (EncoderType::NVENC, Some(1))
+        );
+        // Pocas cores: sin QSV, queda AMF
+        assert_eq!(EncoderType::best_for(&[intel, amd], Some(1), 2), (EncoderType::AMF, Some(1)));
+        assert_eq!(EncoderType::best_for(&[], None, 8), (EncoderType::X264, None));
+    }
+
+    #[test]
+    fn test_prefers_non_render_gpu_on_tie() {
+        let a = gpu(GPUVendor::AMD, false, true, false);
+        let b = gpu(GPUVendor::AMD, false, true, false);
+        assert_eq!(EncoderType::best_for(&[a, b], Some(0), 8), (EncoderType::AMF, Some(1)));
+    }
+
+    fn hardware(gpus: Vec<GPUInfo>) -> HardwareInfo {
+        HardwareInfo {
+            os: "Windows".to_string(),
+            os_version: "11 (22631)".to_string(),
+            hostname: "studio-1".to_string(),
+            cpu: CPUInfo {
+                name: "cpu0".to_string(),
+                brand: "AMD Ryzen 7 7700X".to_string(),
+                cores_physical: 8,
+                cores_logical: 16,
+                frequency_mhz: 4500.0,
+                arch: "x86_64".to_string(),
+            },
+            gpus,
+            render_gpu: Some(0),
+            encode_gpu: Some(0),
+            ram: RAMInfo {
+                total_gb: 31.9,
+                available_gb: 20.0,
+                used_percent: 37.0,
+            },
+            recommended_encoder: EncoderType::NVENC,
+            recommended_preset: "p5".to_string(),
+            recommended_resolution: (1920, 1080),
+            recommended_fps: 60,
+            recommended_bitrate: 6000,
+            measured_upload_kbps: None,
+        }
+    }
+
+    #[test]
+    fn test_vaapi_alone_is_not_a_hardware_encoder() {
+        let mut vaapi_only = gpu(GPUVendor::Intel, false, false, false);
+        vaapi_only.supports_vaapi = true;
+        assert!(!hardware(vec![vaapi_only]).has_hardware_encoder());
+        assert!(hardware(vec![gpu(GPUVendor::Intel, false, false, true)]).has_hardware_encoder());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_load() {
+        let hardware = hardware(vec![
+            gpu(GPUVendor::NVIDIA, true, false, false),
+            gpu(GPUVendor::Intel, false, false, true),
+        ]);
+        let fingerprint = hardware.fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+
+        let mut busy = hardware.clone();
+        busy.ram.available_gb = 2.0;
+        busy.cpu.frequency_mhz = 5400.0;
+        busy.os_version = "11 (26100)".to_string();
+        busy.gpus.reverse();
+        assert_eq!(busy.fingerprint(), fingerprint);
+
+        let mut upgraded = hardware;
+        upgraded.ram.total_gb = 64.0;
+        assert_ne!(upgraded.fingerprint(), fingerprint);
+    }
 }
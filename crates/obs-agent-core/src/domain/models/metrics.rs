@@ -0,0 +1,293 @@
+use crate::domain::services::SystemContext;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Métricas numéricas que se extraen de cada muestra para el histórico
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    CpuUsagePercent,
+    CpuTempCelsius,
+    CpuFrequencyMhz,
+    CpuThrottlingRatio,
+    GpuTempCelsius,
+    MemoryUsedPercent,
+    DiskFreeGb,
+    RecordingMinutesLeft,
+    ObsDroppedFramesPercent,
+    ObsCpuPercent,
+    ObsProcessMemoryMb,
+    AudioPeakDb,
+    UploadEstimateKbps,
+    NetworkTxKbps,
+    NetworkRxKbps,
+    IngestLatencyMs,
+    BatteryPercent,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 17] = [
+        Metric::CpuUsagePercent,
+        Metric::CpuTempCelsius,
+        Metric::CpuFrequencyMhz,
+        Metric::CpuThrottlingRatio,
+        Metric::GpuTempCelsius,
+        Metric::MemoryUsedPercent,
+        Metric::DiskFreeGb,
+        Metric::RecordingMinutesLeft,
+        Metric::ObsDroppedFramesPercent,
+        Metric::ObsCpuPercent,
+        Metric::ObsProcessMemoryMb,
+        Metric::AudioPeakDb,
+        Metric::UploadEstimateKbps,
+        Metric::NetworkTxKbps,
+        Metric::NetworkRxKbps,
+        Metric::IngestLatencyMs,
+        Metric::BatteryPercent,
+    ];
+
+    /// Nombre estable (almacenamiento, CLI, exportadores)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CpuUsagePercent => "cpu_usage_percent",
+            Self::CpuTempCelsius => "cpu_temp_celsius",
+            Self::CpuFrequencyMhz => "cpu_frequency_mhz",
+            Self::CpuThrottlingRatio => "cpu_throttling_ratio",
+            Self::GpuTempCelsius => "gpu_temp_celsius",
+            Self::MemoryUsedPercent => "memory_used_percent",
+            Self::DiskFreeGb => "disk_free_gb",
+            Self::RecordingMinutesLeft => "recording_minutes_left",
+            Self::ObsDroppedFramesPercent => "obs_dropped_frames_percent",
+            Self::ObsCpuPercent => "obs_cpu_percent",
+            Self::ObsProcessMemoryMb => "obs_process_memory_mb",
+            Self::AudioPeakDb => "audio_peak_db",
+            Self::UploadEstimateKbps => "upload_estimate_kbps",
+            Self::NetworkTxKbps => "network_tx_kbps",
+            Self::NetworkRxKbps => "network_rx_kbps",
+            Self::IngestLatencyMs => "ingest_latency_ms",
+            Self::BatteryPercent => "battery_percent",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::CpuUsagePercent => "Total CPU usage",
+            Self::CpuTempCelsius => "CPU package temperature",
+            Self::CpuFrequencyMhz => "Average CPU core frequency",
+            Self::CpuThrottlingRatio => "Fraction of base clock lost to throttling",
+            Self::GpuTempCelsius => "Hottest GPU temperature",
+            Self::MemoryUsedPercent => "System memory in use",
+            Self::DiskFreeGb => "Free space on the recording disk",
+            Self::RecordingMinutesLeft => "Recording time left on the recording disk",
+            Self::ObsDroppedFramesPercent => "Frames dropped by OBS",
+            Self::ObsCpuPercent => "CPU usage reported by OBS",
+            Self::ObsProcessMemoryMb => "Resident memory of OBS and its helpers",
+            Self::AudioPeakDb => "Loudest audio peak",
+            Self::UploadEstimateKbps => "Estimated available upload bandwidth",
+            Self::NetworkTxKbps => "Network upload throughput",
+            Self::NetworkRxKbps => "Network download throughput",
+            Self::IngestLatencyMs => "TCP connect latency to the ingest server",
+            Self::BatteryPercent => "Battery charge",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.name() == name)
+    }
+
+    /// Valor de la métrica en una muestra (`None` si no se midió)
+    pub fn value(&self, context: &SystemContext) -> Option<f64> {
+        // Los sensores ausentes se reportan como 0 °C
+        let temp = |t: f32| (t > 0.0).then_some(t as f64);
+        match self {
+            Self::CpuUsagePercent => Some(context.cpu_usage as f64),
+            Self::CpuTempCelsius => temp(context.cpu_temp),
+            Self::CpuFrequencyMhz => context.cpu_frequency.as_ref().and_then(|f| f.average_mhz()),
+            Self::CpuThrottlingRatio => context.cpu_frequency.as_ref().and_then(|f| f.throttling_ratio()),
+            Self::GpuTempCelsius => temp(context.gpu_temp),
+            Self::MemoryUsedPercent => Some(context.memory_used_percent),
+            Self::DiskFreeGb => Some(context.disk_free_gb),
+            Self::RecordingMinutesLeft => context.recording_minutes_left,
+            Self::ObsDroppedFramesPercent => Some(context.obs_dropped_frames_percent),
+            Self::ObsCpuPercent => Some(context.obs_cpu_usage),
+            Self::ObsProcessMemoryMb => context
+                .obs_process
+                .as_ref()
+                .filter(|process| process.is_running())
+                .map(|process| process.total_memory_mb()),
+            Self::AudioPeakDb => context.audio_peak_db.map(|db| db as f64),
+            Self::UploadEstimateKbps => context.network_bitrate.map(|kbps| kbps as f64),
+            Self::NetworkTxKbps => context.network.as_ref().map(|n| n.tx_kbps()),
+            Self::NetworkRxKbps => context.network.as_ref().map(|n| n.rx_kbps()),
+            Self::IngestLatencyMs => context
+                .network
+                .as_ref()
+                .and_then(|n| n.latency.as_ref())
+                .filter(|l| l.failures < l.attempts)
+                .map(|l| l.avg_ms),
+            Self::BatteryPercent => context.power.as_ref().and_then(|p| p.battery_percent).map(|p| p as f64),
+        }
+    }
+
+    /// Todas las métricas medidas en una muestra
+    pub fn values(context: &SystemContext) -> Vec<(Metric, f64)> {
+        Self::ALL
+            .into_iter()
+            .filter_map(|metric| metric.value(context).map(|value| (metric, value)))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Metric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| anyhow::anyhow!("Unknown metric '{}'", s))
+    }
+}
+
+/// Valor con etiquetas tal como lo envían los exportadores (Influx, StatsD)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub name: String,
+    pub tags: Vec<(String, String)>,
+    pub value: f64,
+}
+
+impl MetricSample {
+    pub fn new(name: impl Into<String>, value: f64) -> Self {
+        Self {
+            name: name.into(),
+            tags: Vec::new(),
+            value,
+        }
+    }
+
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Punto de una serie: una muestra cruda o el agregado de un intervalo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricPoint {
+    /// Inicio del intervalo (o momento de la muestra)
+    pub timestamp: DateTime<Utc>,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Muestras crudas agregadas en el punto
+    pub samples: u64,
+}
+
+/// Intervalo de tiempo [from, to)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl TimeRange {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Self { from, to }
+    }
+
+    /// Desde hace `duration` hasta ahora
+    pub fn last(duration: Duration) -> Self {
+        let to = Utc::now();
+        let from = to - chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX);
+        Self { from, to }
+    }
+
+    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        timestamp >= self.from && timestamp < self.to
+    }
+}
+
+/// Duraciones como `90s`, `30m`, `6h` o `7d` (sin unidad: segundos)
+pub fn parse_duration(value: &str) -> anyhow::Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len()));
+    let number: u64 = number.parse().map_err(|_| anyhow::anyhow!("invalid duration '{}'", value))?;
+    let secs = match unit {
+        "" | "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        "d" => number * 86400,
+        _ => anyhow::bail!("invalid duration unit in '{}' (use s, m, h or d)", value),
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Cuánto tiempo se conserva el histórico y con qué resolución
+///
+/// Las muestras crudas se agregan en intervalos de `rollup_interval` al
+/// superar `raw_retention`; los agregados, anomalías y reportes se borran
+/// al superar `rollup_retention`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub raw_retention: Duration,
+    pub rollup_interval: Duration,
+    pub rollup_retention: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            raw_retention: Duration::from_secs(24 * 3600),
+            rollup_interval: Duration::from_secs(60),
+            rollup_retention: Duration::from_secs(30 * 24 * 3600),
+        }
+    }
+}
+
+/// Resultado de aplicar una política de retención
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RetentionSummary {
+    /// Muestras crudas reemplazadas por agregados
+    pub samples_rolled_up: u64,
+    pub rollups_created: u64,
+    /// Filas borradas por antigüedad (métricas, anomalías y reportes)
+    pub rows_deleted: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{LatencyStats, NetworkStats};
+
+    #[test]
+    fn test_metric_values_from_context() {
+        let context = SystemContext {
+            cpu_usage: 42.0,
+            cpu_temp: 71.0,
+            network: Some(NetworkStats {
+                latency: Some(LatencyStats::from_samples("ingest:1935", &[], 3)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(Metric::CpuUsagePercent.value(&context), Some(42.0));
+        assert_eq!(Metric::CpuTempCelsius.value(&context), Some(71.0));
+        // Sin sensor de GPU ni conexiones exitosas no hay dato
+        assert_eq!(Metric::GpuTempCelsius.value(&context), None);
+        assert_eq!(Metric::IngestLatencyMs.value(&context), None);
+        assert_eq!(Metric::NetworkTxKbps.value(&context), Some(0.0));
+
+        for metric in Metric::ALL {
+            assert_eq!(Metric::parse(metric.name()), Some(metric));
+        }
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("6h").unwrap(), Duration::from_secs(6 * 3600));
+        assert!(parse_duration("3w").is_err());
+    }
+}
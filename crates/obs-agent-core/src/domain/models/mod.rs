@@ -1,7 +1,17 @@
 pub mod anomaly;
+pub mod auth;
 pub mod hardware;
+pub mod metrics;
+pub mod network;
 pub mod obs;
+pub mod process;
+pub mod snapshot;
 
 pub use anomaly::*;
+pub use auth::*;
 pub use hardware::*;
+pub use metrics::*;
+pub use network::*;
 pub use obs::*;
+pub use process::*;
+pub use snapshot::*;
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+
+/// Fracción de la subida disponible que puede usar el stream
+pub const UPLOAD_HEADROOM: f64 = 0.75;
+
+/// Bitrate máximo de stream para una subida dada (deja margen para audio y picos)
+pub fn max_stream_bitrate(upload_kbps: u32) -> u32 {
+    (upload_kbps as f64 * UPLOAD_HEADROOM) as u32
+}
+
+/// Servidor de ingesta al que transmite OBS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestEndpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl IngestEndpoint {
+    /// Extrae host y puerto de la URL del servidor de OBS
+    /// (`rtmp://live.twitch.tv/app`, `rtmps://a.rtmps.youtube.com:443/live2`, `host:port`).
+    ///
+    /// Devuelve `None` para protocolos sobre UDP (SRT, RIST), donde medir
+    /// la conexión TCP no tiene sentido.
+    pub fn parse(server: &str) -> Option<Self> {
+        let server = server.trim();
+        let (scheme, rest) = match server.split_once("://") {
+            Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+            None => ("rtmp".to_string(), server),
+        };
+
+        let default_port = match scheme.as_str() {
+            "rtmp" => 1935,
+            "rtmps" | "https" => 443,
+            "http" => 80,
+            _ => return None,
+        };
+
+        let authority = rest.split(['/', '?']).next()?;
+        let authority = authority.rsplit('@').next()?;
+        let (host, port) = match authority.strip_prefix('[') {
+            // IPv6: [::1]:1935
+            Some(bracketed) => {
+                let (host, port) = bracketed.split_once(']')?;
+                match port.strip_prefix(':') {
+                    Some(port) => (host, port.parse().ok()?),
+                    None => (host, default_port),
+                }
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, port.parse().ok()?),
+                None => (authority, default_port),
+            },
+        };
+
+        // "auto" es el servidor de selección automática de rtmp_common
+        if host.is_empty() || host == "auto" {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl std::fmt::Display for IngestEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Tráfico de una interfaz de red desde la muestra anterior
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct InterfaceStats {
+    pub name: String,
+    pub tx_kbps: f64,
+    pub rx_kbps: f64,
+    pub tx_packets: u64,
+    pub rx_packets: u64,
+    pub tx_errors: u64,
+    pub rx_errors: u64,
+}
+
+/// Latencia de conexión TCP al servidor de ingesta
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct LatencyStats {
+    pub endpoint: String,
+    pub attempts: u32,
+    pub failures: u32,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+    /// Variación media entre mediciones consecutivas
+    pub jitter_ms: f64,
+}
+
+impl LatencyStats {
+    /// Resume las mediciones exitosas (en ms) y los intentos fallidos
+    pub fn from_samples(endpoint: impl Into<String>, samples_ms: &[f64], failures: u32) -> Self {
+        let mut stats = Self {
+            endpoint: endpoint.into(),
+            attempts: samples_ms.len() as u32 + failures,
+            failures,
+            ..Default::default()
+        };
+        if samples_ms.is_empty() {
+            return stats;
+        }
+
+        stats.min_ms = samples_ms.iter().copied().fold(f64::INFINITY, f64::min);
+        stats.max_ms = samples_ms.iter().copied().fold(0.0, f64::max);
+        stats.avg_ms = samples_ms.iter().sum::<f64>() / samples_ms.len() as f64;
+        if samples_ms.len() > 1 {
+            stats.jitter_ms = samples_ms.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>()
+                / (samples_ms.len() - 1) as f64;
+        }
+        stats
+    }
+
+    pub fn failure_ratio(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.attempts as f64
+        }
+    }
+}
+
+/// Estado de la red del equipo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct NetworkStats {
+    pub interfaces: Vec<InterfaceStats>,
+    /// Latencia al servidor de ingesta, si se conoce
+    pub latency: Option<LatencyStats>,
+    /// Ancho de banda de subida disponible estimado
+    pub estimated_upload_kbps: Option<u32>,
+}
+
+impl NetworkStats {
+    pub fn tx_kbps(&self) -> f64 {
+        self.interfaces.iter().map(|i| i.tx_kbps).sum()
+    }
+
+    pub fn rx_kbps(&self) -> f64 {
+        self.interfaces.iter().map(|i| i.rx_kbps).sum()
+    }
+
+    /// Fracción de paquetes enviados con error
+    pub fn tx_error_ratio(&self) -> f64 {
+        let packets: u64 = self.interfaces.iter().map(|i| i.tx_packets).sum();
+        let errors: u64 = self.interfaces.iter().map(|i| i.tx_errors).sum();
+        if packets == 0 {
+            0.0
+        } else {
+            errors as f64 / packets as f64
+        }
+    }
+
+    /// Estima la subida disponible a partir de la capacidad del enlace
+    /// (medida con una prueba de subida o configurada), penalizada por
+    /// errores, jitter y conexiones fallidas.
+    pub fn estimate_upload_kbps(capacity_kbps: u32, latency: Option<&LatencyStats>, tx_error_ratio: f64) -> Option<u32> {
+        if capacity_kbps == 0 {
+            return None;
+        }
+
+        let mut factor = 1.0 - (tx_error_ratio * 10.0).min(0.3);
+        if let Some(latency) = latency {
+            factor -= latency.failure_ratio().min(0.4);
+            if latency.jitter_ms > 20.0 {
+                factor -= 0.1;
+            }
+        }
+
+        Some((capacity_kbps as f64 * factor.max(0.3)) as u32)
+    }
+}
+
+/// Resultado de una prueba de subida contra un servidor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BandwidthTest {
+    pub endpoint: String,
+    pub duration_secs: f64,
+    pub bytes_sent: u64,
+    /// Bytes que el servidor confirmó haber recibido (si lo reporta)
+    pub bytes_acknowledged: Option<u64>,
+    /// Throughput medio de toda la prueba
+    pub throughput_kbps: f64,
+    /// Throughput por intervalo (tras el calentamiento)
+    pub min_kbps: f64,
+    pub max_kbps: f64,
+    pub stddev_kbps: f64,
+}
+
+impl BandwidthTest {
+    /// Calcula la dispersión a partir del throughput de cada intervalo
+    pub fn with_intervals(mut self, interval_kbps: &[f64]) -> Self {
+        if interval_kbps.is_empty() {
+            return self;
+        }
+        let mean = interval_kbps.iter().sum::<f64>() / interval_kbps.len() as f64;
+        let variance =
+            interval_kbps.iter().map(|kbps| (kbps - mean).powi(2)).sum::<f64>() / interval_kbps.len() as f64;
+
+        self.min_kbps = interval_kbps.iter().copied().fold(f64::INFINITY, f64::min);
+        self.max_kbps = interval_kbps.iter().copied().fold(0.0, f64::max);
+        self.stddev_kbps = variance.sqrt();
+        self
+    }
+
+    /// Subida sostenida: throughput medio menos una desviación estándar
+    pub fn sustained_upload_kbps(&self) -> u32 {
+        (self.throughput_kbps - self.stddev_kbps).max(0.0) as u32
+    }
+
+    /// Bitrate de stream seguro para esta conexión
+    pub fn safe_bitrate_kbps(&self) -> u32 {
+        max_stream_bitrate(self.sustained_upload_kbps())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ingest_endpoints() {
+        let endpoint = |server: &str| IngestEndpoint::parse(server).map(|e| e.to_string());
+
+        assert_eq!(endpoint("rtmp://live.twitch.tv/app").as_deref(), Some("live.twitch.tv:1935"));
+        assert_eq!(endpoint("rtmps://a.rtmps.youtube.com:443/live2").as_deref(), Some("a.rtmps.youtube.com:443"));
+        assert_eq!(endpoint("127.0.0.1:8935").as_deref(), Some("127.0.0.1:8935"));
+        assert_eq!(endpoint("rtmp://[::1]:1936/live").as_deref(), Some("[::1]:1936"));
+        assert_eq!(endpoint("srt://ingest.example.com:9000?streamid=x"), None);
+        assert_eq!(endpoint("auto"), None);
+    }
+
+    #[test]
+    fn test_latency_stats_and_upload_estimate() {
+        let latency = LatencyStats::from_samples("ingest:1935", &[20.0, 30.0, 25.0, 45.0], 0);
+        assert_eq!(latency.attempts, 4);
+        assert_eq!(latency.min_ms, 20.0);
+        assert_eq!(latency.max_ms, 45.0);
+        assert_eq!(latency.avg_ms, 30.0);
+        // |30-20| + |25-30| + |45-25| = 35 / 3
+        assert!((latency.jitter_ms - 11.67).abs() < 0.01);
+
+        assert_eq!(NetworkStats::estimate_upload_kbps(8000, Some(&latency), 0.0), Some(8000));
+        assert_eq!(NetworkStats::estimate_upload_kbps(0, Some(&latency), 0.0), None);
+
+        // Conexiones fallidas y jitter alto reducen la estimación
+        let flaky = LatencyStats::from_samples("ingest:1935", &[20.0, 80.0], 2);
+        assert_eq!(flaky.failure_ratio(), 0.5);
+        assert_eq!(NetworkStats::estimate_upload_kbps(8000, Some(&flaky), 0.0), Some(4000));
+    }
+
+    #[test]
+    fn test_bandwidth_test_safe_bitrate() {
+        let test = BandwidthTest {
+            throughput_kbps: 10_000.0,
+            ..Default::default()
+        }
+        .with_intervals(&[8_000.0, 12_000.0, 8_000.0, 12_000.0]);
+
+        assert_eq!(test.min_kbps, 8_000.0);
+        assert_eq!(test.max_kbps, 12_000.0);
+        assert_eq!(test.stddev_kbps, 2_000.0);
+        assert_eq!(test.sustained_upload_kbps(), 8_000);
+        assert_eq!(test.safe_bitrate_kbps(), 6_000);
+    }
+}
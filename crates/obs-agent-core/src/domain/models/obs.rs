@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 /// Escena de OBS
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,8 +20,32 @@ pub struct Source {
     pub settings: HashMap<String, serde_json::Value>,
 }
 
+/// Fuente dentro de una escena concreta
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SourceRef {
+    pub scene: String,
+    pub source: String,
+}
+
+impl SourceRef {
+    pub fn new(scene: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            scene: scene.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for SourceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.scene, self.source)
+    }
+}
+
 /// Estadísticas de OBS
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct OBSStats {
     pub cpu_usage: f64,
     pub memory_usage: f64,
@@ -44,8 +69,37 @@ impl OBSStats {
     }
 }
 
+/// Estado de una salida de OBS (stream o grabación)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct OutputStatus {
+    /// `stream` o `record`
+    pub name: String,
+    pub active: bool,
+    pub reconnecting: bool,
+    /// Congestión de red (0-1), solo para el stream
+    pub congestion: Option<f64>,
+    /// Bytes enviados/escritos desde que se inició la salida
+    pub bytes: u64,
+    pub skipped_frames: u64,
+    pub total_frames: u64,
+    pub duration_secs: f64,
+}
+
+impl OutputStatus {
+    /// Bitrate medio desde que se inició la salida (`None` si no está activa o lleva muy poco)
+    pub fn average_kbps(&self) -> Option<u32> {
+        if !self.active || self.duration_secs < 10.0 {
+            return None;
+        }
+        Some((self.bytes as f64 * 8.0 / 1000.0 / self.duration_secs) as u32)
+    }
+}
+
 /// Configuración de video de OBS
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct VideoSettings {
     pub base_width: u32,
     pub base_height: u32,
@@ -57,6 +111,88 @@ pub struct VideoSettings {
 
 impl VideoSettings {
     pub fn fps(&self) -> f64 {
+        if self.fps_denominator == 0 {
+            return 0.0;
+        }
         self.fps_numerator as f64 / self.fps_denominator as f64
     }
 }
+
+/// Configuración del encoder de streaming
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncoderSettings {
+    pub encoder: String,
+    pub preset: String,
+    pub bitrate_kbps: u32,
+}
+
+/// Configuración de grabación de OBS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingSettings {
+    /// Directorio donde OBS guarda las grabaciones
+    pub directory: String,
+    /// Bitrate total estimado (video + audio), si se puede deducir del perfil
+    pub bitrate_kbps: Option<u32>,
+}
+
+/// Destino de transmisión configurado en OBS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSettings {
+    /// Tipo de servicio (`rtmp_common`, `rtmp_custom`, `whip_custom`...)
+    pub service_type: String,
+    /// URL del servidor de ingesta, si está configurada
+    pub server: Option<String>,
+}
+
+impl StreamSettings {
+    /// Servidor de ingesta al que medir la latencia
+    pub fn ingest(&self) -> Option<super::IngestEndpoint> {
+        self.server.as_deref().and_then(super::IngestEndpoint::parse)
+    }
+}
+
+/// Presets de x264 ordenados del más rápido al más lento
+const X264_PRESETS: &[&str] = &[
+    "ultrafast", "superfast", "veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow", "placebo",
+];
+
+/// Presets de NVENC ordenados del más rápido al más lento
+const NVENC_PRESETS: &[&str] = &["p1", "p2", "p3", "p4", "p5", "p6", "p7"];
+
+/// Presets de AMF ordenados del más rápido al más lento
+const AMF_PRESETS: &[&str] = &["speed", "balanced", "quality"];
+
+impl EncoderSettings {
+    /// Preset inmediatamente más rápido que el actual, si existe
+    pub fn faster_preset(&self) -> Option<String> {
+        let presets = [X264_PRESETS, NVENC_PRESETS, AMF_PRESETS]
+            .into_iter()
+            .find(|presets| presets.contains(&self.preset.as_str()))?;
+        let index = presets.iter().position(|p| *p == self.preset)?;
+        index.checked_sub(1).map(|i| presets[i].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faster_preset() {
+        let mut settings = EncoderSettings {
+            encoder: "x264".to_string(),
+            preset: "medium".to_string(),
+            bitrate_kbps: 6000,
+        };
+        assert_eq!(settings.faster_preset(), Some("fast".to_string()));
+
+        settings.preset = "p5".to_string();
+        assert_eq!(settings.faster_preset(), Some("p4".to_string()));
+
+        settings.preset = "ultrafast".to_string();
+        assert_eq!(settings.faster_preset(), None);
+
+        settings.preset = "custom".to_string();
+        assert_eq!(settings.faster_preset(), None);
+    }
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+/// Uso de recursos de un proceso
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// Uso de CPU (100% = un core completo)
+    pub cpu_percent: f32,
+    /// Memoria residente (RSS)
+    pub memory_mb: f64,
+    pub threads: Option<u32>,
+    /// Descriptores de archivo abiertos (solo Linux)
+    pub open_files: Option<u32>,
+    pub disk_read_bytes_per_sec: f64,
+    pub disk_write_bytes_per_sec: f64,
+}
+
+/// Cambio en el ciclo de vida de OBS entre dos muestras
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ProcessEvent {
+    /// OBS apareció por primera vez
+    Started,
+    /// OBS terminó dejando un reporte de crash
+    Crashed { pid: u32 },
+    /// OBS terminó sin reporte de crash
+    Exited { pid: u32 },
+    /// Un nuevo proceso de OBS reemplazó al anterior
+    Restarted { previous_pid: u32 },
+}
+
+/// Estado del proceso de OBS y sus helpers (p.ej. `obs-browser-page`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ObsProcessInfo {
+    /// `None` si OBS no está corriendo
+    pub main: Option<ProcessUsage>,
+    /// Procesos hijos (browser sources, etc.)
+    pub helpers: Vec<ProcessUsage>,
+    pub uptime_secs: u64,
+    /// Reinicios observados desde que empezó el monitoreo
+    pub restarts: u32,
+    /// Crashes observados desde que empezó el monitoreo
+    pub crashes: u32,
+    /// Evento detectado en esta muestra
+    pub event: Option<ProcessEvent>,
+}
+
+impl ObsProcessInfo {
+    pub fn is_running(&self) -> bool {
+        self.main.is_some()
+    }
+
+    /// CPU de OBS más sus helpers
+    pub fn total_cpu_percent(&self) -> f32 {
+        self.main.iter().chain(&self.helpers).map(|p| p.cpu_percent).sum()
+    }
+
+    /// RSS de OBS más sus helpers
+    pub fn total_memory_mb(&self) -> f64 {
+        self.main.iter().chain(&self.helpers).map(|p| p.memory_mb).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totals_include_helpers() {
+        let usage = |pid, cpu_percent, memory_mb| ProcessUsage {
+            pid,
+            cpu_percent,
+            memory_mb,
+            ..Default::default()
+        };
+        let info = ObsProcessInfo {
+            main: Some(usage(100, 40.0, 900.0)),
+            helpers: vec![usage(101, 12.5, 300.0), usage(102, 2.5, 150.0)],
+            ..Default::default()
+        };
+
+        assert!(info.is_running());
+        assert_eq!(info.total_cpu_percent(), 55.0);
+        assert_eq!(info.total_memory_mb(), 1350.0);
+        assert_eq!(ObsProcessInfo::default().total_memory_mb(), 0.0);
+    }
+}
@@ -0,0 +1,22 @@
+use super::{CpuFrequencyStats, GPUInfo, ObsProcessInfo, PowerStatus, RAMInfo};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Muestra del hardware tomada por el sampler en segundo plano
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonitorSnapshot {
+    /// Número de muestra desde que arrancó el sampler
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    /// Uso de CPU desde la muestra anterior
+    pub cpu_usage: f32,
+    pub cpu_temp: Option<f32>,
+    pub cpu_frequency: Option<CpuFrequencyStats>,
+    /// Temperatura de la GPU más caliente
+    pub gpu_temp: Option<f32>,
+    pub gpus: Vec<GPUInfo>,
+    pub memory: Option<RAMInfo>,
+    pub power: Option<PowerStatus>,
+    pub obs_process: Option<ObsProcessInfo>,
+}
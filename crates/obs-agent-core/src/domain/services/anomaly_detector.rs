@@ -1,21 +1,75 @@
-use crate::domain::models::{Anomaly, AnomalyType, Severity};
+use crate::domain::models::{
+    Anomaly, AnomalyTransition, AnomalyType, CpuFrequencyStats, NetworkStats, ObsProcessInfo, PowerProfile, PowerStatus,
+    ProcessEvent, Severity, SourceRef,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rayon::prelude::*;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use tracing::{debug, warn};
 
 /// Contexto del sistema para detección de anomalías
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct SystemContext {
     pub cpu_temp: f32,
     pub gpu_temp: f32,
     pub cpu_usage: f32,
+    /// Frecuencia actual de cada core frente a su base
+    pub cpu_frequency: Option<CpuFrequencyStats>,
     pub memory_used_percent: f64,
     pub disk_free_gb: f64,
     pub obs_dropped_frames_percent: f64,
     pub obs_cpu_usage: f64,
-    pub missing_sources: Vec<String>,
+    pub missing_sources: Vec<SourceRef>,
     pub audio_peak_db: Option<f32>,
     pub network_bitrate: Option<u32>,
+    /// Minutos de grabación restantes en el disco de grabación
+    pub recording_minutes_left: Option<f64>,
+    /// Recursos del proceso de OBS y sus helpers
+    pub obs_process: Option<ObsProcessInfo>,
+    /// Tráfico de red y latencia al servidor de ingesta
+    pub network: Option<NetworkStats>,
+    /// Fuente de alimentación y perfil de energía
+    pub power: Option<PowerStatus>,
+    /// Momento de la muestra (reloj real o virtual; el actual si falta)
+    #[cfg_attr(feature = "openapi", schema(default = json!(null)))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Default for SystemContext {
+    /// Contexto nominal: sistema sano y sin datos opcionales
+    fn default() -> Self {
+        Self {
+            cpu_temp: 0.0,
+            gpu_temp: 0.0,
+            cpu_usage: 0.0,
+            cpu_frequency: None,
+            memory_used_percent: 0.0,
+            disk_free_gb: 1000.0,
+            obs_dropped_frames_percent: 0.0,
+            obs_cpu_usage: 0.0,
+            missing_sources: vec![],
+            audio_peak_db: None,
+            network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: None,
+            network: None,
+            power: None,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Fuente de contextos del sistema (OBS + hardware)
+#[async_trait]
+pub trait ContextProvider: Send + Sync {
+    /// Recolecta un contexto actualizado del sistema
+    async fn collect(&self) -> Result<SystemContext>;
 }
 
 /// Regla de detección de anomalías
@@ -36,9 +90,9 @@ impl AnomalyRule for MissingSourceRule {
                     Severity::Critical,
                     format!("Missing {} source(s)", context.missing_sources.len()),
                 )
-                .with_source(context.missing_sources.join(", "))
+                .with_sources(context.missing_sources.clone())
                 .with_action("Connect or remove missing sources before streaming")
-                .auto_fixable(false),
+                .auto_fixable(true),
             )
         } else {
             None
@@ -88,6 +142,97 @@ impl AnomalyRule for HighCPUTempRule {
     }
 }
 
+/// Regla: CPU por debajo de su frecuencia base de forma sostenida mientras
+/// está cargada o caliente (thermal o power throttling)
+pub struct CpuThrottlingRule {
+    /// Fracción mínima de la frecuencia base perdida
+    pub ratio_threshold: f64,
+    /// Segundos que debe mantenerse antes de reportar
+    pub sustained_secs: f64,
+    pub cpu_usage_threshold: f32,
+    pub cpu_temp_threshold: f32,
+    /// Inicio del throttling en curso (según el reloj de los contextos)
+    since: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl CpuThrottlingRule {
+    pub fn new(ratio_threshold: f64, sustained_secs: f64, cpu_usage_threshold: f32, cpu_temp_threshold: f32) -> Self {
+        Self {
+            ratio_threshold,
+            sustained_secs,
+            cpu_usage_threshold,
+            cpu_temp_threshold,
+            since: Mutex::new(None),
+        }
+    }
+}
+
+impl AnomalyRule for CpuThrottlingRule {
+    fn check(&self, context: &SystemContext) -> Option<Anomaly> {
+        let frequency = context.cpu_frequency.as_ref();
+        let hot = context.cpu_temp >= self.cpu_temp_threshold;
+        let loaded = context.cpu_usage >= self.cpu_usage_threshold;
+
+        // Sin frecuencia base no hay referencia; en reposo la CPU baja de
+        // frecuencia a propósito, así que solo cuenta bajo presión
+        let mut since = self.since.lock().unwrap();
+        let Some(ratio) = frequency.and_then(CpuFrequencyStats::throttling_ratio) else {
+            *since = None;
+            return None;
+        };
+        if ratio < self.ratio_threshold || !(hot || loaded) {
+            *since = None;
+            return None;
+        }
+        let started = *since.get_or_insert(context.timestamp);
+        let throttled_secs = (context.timestamp - started).num_milliseconds() as f64 / 1000.0;
+        if throttled_secs < 0.0 {
+            // Contextos de otra ejecución (p.ej. otro escenario): reiniciar
+            *since = Some(context.timestamp);
+            return None;
+        }
+        if throttled_secs < self.sustained_secs {
+            return None;
+        }
+
+        let frequency = frequency?;
+        let severity = if ratio >= self.ratio_threshold * 2.0 {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        };
+        let (cause, action) = if hot {
+            ("thermal", "Improve cooling or lower the encoder preset")
+        } else {
+            ("power limit", "Switch to a performance power profile and check the power supply")
+        };
+
+        Some(
+            Anomaly::new(
+                AnomalyType::CpuThrottling,
+                severity,
+                format!(
+                    "CPU throttled {:.0}% below base clock for {:.0}s ({} MHz avg, slowest core {} at {:.0} MHz) at {:.0}°C / {:.0}% usage ({})",
+                    ratio * 100.0,
+                    throttled_secs,
+                    frequency.average_mhz().unwrap_or(0.0).round(),
+                    frequency.slowest().map_or(0, |c| c.core),
+                    frequency.slowest().map_or(0.0, |c| c.current_mhz),
+                    context.cpu_temp,
+                    context.cpu_usage,
+                    cause
+                ),
+            )
+            .with_action(action)
+            .auto_fixable(false),
+        )
+    }
+
+    fn name(&self) -> &str {
+        "CpuThrottling"
+    }
+}
+
 /// Regla: GPU temperatura alta
 pub struct HighGPUTempRule {
     pub warning_threshold: f32,
@@ -114,7 +259,7 @@ impl AnomalyRule for HighGPUTempRule {
                     format!("GPU temperature is {}°C (high)", context.gpu_temp),
                 )
                 .with_action("Consider reducing resolution or frame rate")
-                .auto_fixable(true),
+                .auto_fixable(false),
             )
         } else {
             None
@@ -184,12 +329,40 @@ impl AnomalyRule for LowMemoryRule {
 }
 
 /// Regla: Espacio en disco bajo
+///
+/// Con bitrate de grabación conocido avisa por minutos restantes;
+/// si no, usa un umbral fijo de GB libres.
 pub struct LowDiskSpaceRule {
     pub threshold_gb: f64,
+    pub warning_minutes: f64,
+    pub critical_minutes: f64,
 }
 
 impl AnomalyRule for LowDiskSpaceRule {
     fn check(&self, context: &SystemContext) -> Option<Anomaly> {
+        if let Some(minutes) = context.recording_minutes_left {
+            let severity = if minutes <= self.critical_minutes {
+                Severity::Critical
+            } else if minutes <= self.warning_minutes {
+                Severity::Warning
+            } else {
+                return None;
+            };
+
+            return Some(
+                Anomaly::new(
+                    AnomalyType::DiskSpaceLow,
+                    severity,
+                    format!(
+                        "Only {:.0} minutes of recording left ({:.1} GB free)",
+                        minutes, context.disk_free_gb
+                    ),
+                )
+                .with_action("Free up disk space or lower the recording bitrate")
+                .auto_fixable(false),
+            );
+        }
+
         if context.disk_free_gb <= self.threshold_gb {
             Some(
                 Anomaly::new(
@@ -210,6 +383,161 @@ impl AnomalyRule for LowDiskSpaceRule {
     }
 }
 
+/// Regla: Proceso de OBS (crashes, reinicios y memoria)
+pub struct ObsProcessRule {
+    pub memory_mb_threshold: f64,
+}
+
+impl AnomalyRule for ObsProcessRule {
+    fn check(&self, context: &SystemContext) -> Option<Anomaly> {
+        let process = context.obs_process.as_ref()?;
+
+        match process.event {
+            Some(ProcessEvent::Crashed { pid }) => {
+                return Some(
+                    Anomaly::new(
+                        AnomalyType::ObsCrash,
+                        Severity::Critical,
+                        format!("OBS crashed (pid {}, {} crash(es) so far)", pid, process.crashes),
+                    )
+                    .with_action("Check the OBS crash report and restart OBS")
+                    .auto_fixable(false),
+                );
+            }
+            Some(ProcessEvent::Restarted { previous_pid }) => {
+                return Some(
+                    Anomaly::new(
+                        AnomalyType::ObsCrash,
+                        Severity::Warning,
+                        format!("OBS restarted (previous pid {})", previous_pid),
+                    )
+                    .with_action("Check the OBS log for the cause of the restart")
+                    .auto_fixable(false),
+                );
+            }
+            _ => {}
+        }
+
+        let memory_mb = process.total_memory_mb();
+        if memory_mb >= self.memory_mb_threshold {
+            Some(
+                Anomaly::new(
+                    AnomalyType::MemoryLeak,
+                    Severity::Warning,
+                    format!(
+                        "OBS using {:.0} MB RAM ({} helper process(es))",
+                        memory_mb,
+                        process.helpers.len()
+                    ),
+                )
+                .with_source("obs")
+                .with_action("Remove unused browser sources, restart the replay buffer or restart OBS")
+                .auto_fixable(true),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> &str {
+        "ObsProcess"
+    }
+}
+
+/// Regla: Red inestable (latencia irregular o conexiones fallidas al servidor de ingesta)
+pub struct NetworkRule {
+    pub jitter_ms_threshold: f64,
+}
+
+impl AnomalyRule for NetworkRule {
+    fn check(&self, context: &SystemContext) -> Option<Anomaly> {
+        let latency = context.network.as_ref()?.latency.as_ref()?;
+
+        if latency.attempts > 0 && latency.failures == latency.attempts {
+            return Some(
+                Anomaly::new(
+                    AnomalyType::NetworkUnstable,
+                    Severity::Critical,
+                    format!("Ingest server {} unreachable", latency.endpoint),
+                )
+                .with_source(latency.endpoint.clone())
+                .with_action("Check the internet connection or pick another ingest server")
+                .auto_fixable(false),
+            );
+        }
+
+        if latency.failures > 0 || latency.jitter_ms >= self.jitter_ms_threshold {
+            Some(
+                Anomaly::new(
+                    AnomalyType::NetworkUnstable,
+                    Severity::Warning,
+                    format!(
+                        "Unstable connection to {}: {:.0} ms avg, {:.0} ms jitter, {}/{} failed",
+                        latency.endpoint, latency.avg_ms, latency.jitter_ms, latency.failures, latency.attempts
+                    ),
+                )
+                .with_source(latency.endpoint.clone())
+                .with_action("Use a wired connection or lower the bitrate")
+                .auto_fixable(false),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Network"
+    }
+}
+
+/// Regla: Stream en batería o con perfil de ahorro de energía
+pub struct PowerSourceRule {
+    pub battery_critical_percent: f32,
+}
+
+impl AnomalyRule for PowerSourceRule {
+    fn check(&self, context: &SystemContext) -> Option<Anomaly> {
+        let power = context.power.as_ref().filter(|p| p.is_power_saving())?;
+        // Solo importa mientras OBS está abierto (si se monitorea su proceso)
+        if context.obs_process.as_ref().is_some_and(|p| !p.is_running()) {
+            return None;
+        }
+
+        if power.on_battery {
+            let low = power.battery_percent.is_some_and(|p| p <= self.battery_critical_percent);
+            return Some(
+                Anomaly::new(
+                    AnomalyType::PowerSaving,
+                    if low { Severity::Critical } else { Severity::Warning },
+                    match power.battery_percent {
+                        Some(percent) => format!("Running on battery ({:.0}% left)", percent),
+                        None => "Running on battery".to_string(),
+                    },
+                )
+                .with_action("Plug in the charger; on battery the CPU and GPU are power-limited")
+                .auto_fixable(false),
+            );
+        }
+
+        if power.profile == Some(PowerProfile::PowerSaver) {
+            return Some(
+                Anomaly::new(
+                    AnomalyType::PowerSaving,
+                    Severity::Warning,
+                    "Power saver profile is active".to_string(),
+                )
+                .with_action("Switch to the balanced or performance power profile")
+                .auto_fixable(false),
+            );
+        }
+        None
+    }
+
+    fn name(&self) -> &str {
+        "PowerSource"
+    }
+}
+
 /// Regla: Audio saturado
 pub struct AudioClippingRule;
 
@@ -236,6 +564,64 @@ impl AnomalyRule for AudioClippingRule {
     }
 }
 
+/// Umbrales configurables de las reglas por defecto (un umbral mal escrito es un error)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleConfig {
+    pub cpu_temp_warning: f32,
+    pub cpu_temp_critical: f32,
+    pub gpu_temp_warning: f32,
+    pub gpu_temp_critical: f32,
+    pub dropped_frames_percent: f64,
+    pub memory_used_percent: f64,
+    pub disk_free_gb: f64,
+    pub recording_minutes_warning: f64,
+    pub recording_minutes_critical: f64,
+    pub obs_memory_mb: f64,
+    pub network_jitter_ms: f64,
+    pub throttle_ratio: f64,
+    pub throttle_sustained_secs: f64,
+    pub throttle_cpu_usage: f32,
+    pub battery_critical_percent: f32,
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        Self {
+            cpu_temp_warning: 75.0,
+            cpu_temp_critical: 85.0,
+            gpu_temp_warning: 80.0,
+            gpu_temp_critical: 90.0,
+            dropped_frames_percent: 1.0,
+            memory_used_percent: 90.0,
+            disk_free_gb: 10.0,
+            recording_minutes_warning: 60.0,
+            recording_minutes_critical: 15.0,
+            obs_memory_mb: 6144.0,
+            network_jitter_ms: 30.0,
+            throttle_ratio: 0.15,
+            throttle_sustained_secs: 30.0,
+            throttle_cpu_usage: 60.0,
+            battery_critical_percent: 20.0,
+        }
+    }
+}
+
+impl RuleConfig {
+    /// Carga umbrales desde archivo (.json o .toml)
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        use anyhow::Context;
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rule config {}", path.display()))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&content).context("Invalid JSON rule config"),
+            _ => toml::from_str(&content).context("Invalid TOML rule config"),
+        }
+    }
+}
+
 /// Motor de detección de anomalías con Rayon
 pub struct AnomalyDetector {
     rules: Vec<Arc<dyn AnomalyRule>>,
@@ -252,23 +638,47 @@ impl AnomalyDetector {
     }
 
     pub fn with_default_rules() -> Self {
+        Self::from_config(&RuleConfig::default())
+    }
+
+    /// Reglas por defecto con umbrales personalizados
+    pub fn from_config(config: &RuleConfig) -> Self {
         Self::new()
             .with_rule(Arc::new(MissingSourceRule))
             .with_rule(Arc::new(HighCPUTempRule {
-                warning_threshold: 75.0,
-                critical_threshold: 85.0,
+                warning_threshold: config.cpu_temp_warning,
+                critical_threshold: config.cpu_temp_critical,
             }))
+            .with_rule(Arc::new(CpuThrottlingRule::new(
+                config.throttle_ratio,
+                config.throttle_sustained_secs,
+                config.throttle_cpu_usage,
+                config.cpu_temp_warning,
+            )))
             .with_rule(Arc::new(HighGPUTempRule {
-                warning_threshold: 80.0,
-                critical_threshold: 90.0,
+                warning_threshold: config.gpu_temp_warning,
+                critical_threshold: config.gpu_temp_critical,
             }))
             .with_rule(Arc::new(DroppedFramesRule {
-                threshold_percent: 1.0,
+                threshold_percent: config.dropped_frames_percent,
             }))
             .with_rule(Arc::new(LowMemoryRule {
-                threshold_percent: 90.0,
+                threshold_percent: config.memory_used_percent,
+            }))
+            .with_rule(Arc::new(LowDiskSpaceRule {
+                threshold_gb: config.disk_free_gb,
+                warning_minutes: config.recording_minutes_warning,
+                critical_minutes: config.recording_minutes_critical,
+            }))
+            .with_rule(Arc::new(ObsProcessRule {
+                memory_mb_threshold: config.obs_memory_mb,
+            }))
+            .with_rule(Arc::new(NetworkRule {
+                jitter_ms_threshold: config.network_jitter_ms,
+            }))
+            .with_rule(Arc::new(PowerSourceRule {
+                battery_critical_percent: config.battery_critical_percent,
             }))
-            .with_rule(Arc::new(LowDiskSpaceRule { threshold_gb: 10.0 }))
             .with_rule(Arc::new(AudioClippingRule))
     }
 
@@ -281,8 +691,10 @@ impl AnomalyDetector {
             .rules
             .par_iter()
             .filter_map(|rule| {
-                let result = rule.check(context);
-                if let Some(ref anomaly) = result {
+                let mut result = rule.check(context);
+                if let Some(ref mut anomaly) = result {
+                    // La anomalía ocurre en el instante de la muestra
+                    anomaly.timestamp = context.timestamp;
                     warn!(
                         "Rule '{}' detected {:?} anomaly: {}",
                         rule.name(),
@@ -313,6 +725,63 @@ impl Default for AnomalyDetector {
     }
 }
 
+/// Sigue las anomalías activas entre muestras y reporta solo los cambios
+///
+/// Una anomalía se identifica por tipo, severidad y fuente: si cambia de
+/// severidad se reporta como resuelta la anterior y aparecida la nueva.
+/// Mientras sigue activa conserva el `id` con el que apareció.
+#[derive(Debug, Default)]
+pub struct AnomalyTracker {
+    active: BTreeMap<(AnomalyType, Severity, Option<String>), Anomaly>,
+}
+
+impl AnomalyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Actualiza con las anomalías de una muestra y devuelve las transiciones
+    pub fn update(&mut self, anomalies: &[Anomaly], timestamp: DateTime<Utc>) -> Vec<AnomalyTransition> {
+        let current: BTreeMap<_, _> = anomalies
+            .iter()
+            .map(|a| {
+                let key = (a.anomaly_type, a.severity, a.source.clone());
+                let mut anomaly = a.clone();
+                if let Some(previous) = self.active.get(&key) {
+                    anomaly.id = previous.id;
+                }
+                (key, anomaly)
+            })
+            .collect();
+
+        let mut transitions: Vec<AnomalyTransition> = self
+            .active
+            .keys()
+            .filter(|key| !current.contains_key(*key))
+            .map(|(anomaly_type, severity, source)| AnomalyTransition::Cleared {
+                anomaly_type: *anomaly_type,
+                severity: *severity,
+                source: source.clone(),
+                timestamp,
+            })
+            .collect();
+        transitions.extend(
+            current
+                .iter()
+                .filter(|(key, _)| !self.active.contains_key(*key))
+                .map(|(_, anomaly)| AnomalyTransition::Raised { anomaly: anomaly.clone() }),
+        );
+
+        self.active = current;
+        transitions
+    }
+
+    /// Anomalías activas según la última muestra
+    pub fn active(&self) -> impl Iterator<Item = &Anomaly> {
+        self.active.values()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,18 +793,28 @@ mod tests {
             cpu_temp: 50.0,
             gpu_temp: 60.0,
             cpu_usage: 30.0,
+            cpu_frequency: None,
             memory_used_percent: 50.0,
             disk_free_gb: 100.0,
             obs_dropped_frames_percent: 0.0,
             obs_cpu_usage: 10.0,
-            missing_sources: vec!["webcam".to_string()],
+            missing_sources: vec![SourceRef::new("Live", "webcam")],
             audio_peak_db: None,
             network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: None,
+            network: None,
+            power: None,
+            timestamp: Utc::now(),
         };
 
         let anomalies = detector.scan(&context);
-        assert!(!anomalies.is_empty());
-        assert!(anomalies.iter().any(|a| a.anomaly_type == AnomalyType::MissingSource));
+        let missing = anomalies
+            .iter()
+            .find(|a| a.anomaly_type == AnomalyType::MissingSource)
+            .unwrap();
+        assert_eq!(missing.source.as_deref(), Some("Live:webcam"));
+        assert_eq!(missing.sources, vec![SourceRef::new("Live", "webcam")]);
     }
 
     #[test]
@@ -345,6 +824,7 @@ mod tests {
             cpu_temp: 90.0, // Critical!
             gpu_temp: 60.0,
             cpu_usage: 30.0,
+            cpu_frequency: None,
             memory_used_percent: 50.0,
             disk_free_gb: 100.0,
             obs_dropped_frames_percent: 0.0,
@@ -352,6 +832,11 @@ mod tests {
             missing_sources: vec![],
             audio_peak_db: None,
             network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: None,
+            network: None,
+            power: None,
+            timestamp: Utc::now(),
         };
 
         let anomalies = detector.scan(&context);
@@ -369,6 +854,7 @@ mod tests {
             cpu_temp: 50.0,
             gpu_temp: 60.0,
             cpu_usage: 30.0,
+            cpu_frequency: None,
             memory_used_percent: 50.0,
             disk_free_gb: 100.0,
             obs_dropped_frames_percent: 0.0,
@@ -376,6 +862,11 @@ mod tests {
             missing_sources: vec![],
             audio_peak_db: None,
             network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: None,
+            network: None,
+            power: None,
+            timestamp: Utc::now(),
         };
 
         let start = Instant::now();
@@ -385,4 +876,175 @@ mod tests {
         println!("Scan completed in {:?} with {} anomalies", duration, anomalies.len());
         assert!(duration.as_millis() < 100); // Debe ser rápido
     }
+
+    #[test]
+    fn test_disk_space_by_recording_minutes() {
+        let detector = AnomalyDetector::default();
+        let severity = |minutes: Option<f64>, disk_free_gb: f64| {
+            let context = SystemContext {
+                disk_free_gb,
+                recording_minutes_left: minutes,
+                ..Default::default()
+            };
+            detector
+                .scan(&context)
+                .into_iter()
+                .find(|a| a.anomaly_type == AnomalyType::DiskSpaceLow)
+                .map(|a| a.severity)
+        };
+
+        // 8 GB libres pero a bajo bitrate caben horas de grabación
+        assert_eq!(severity(Some(300.0), 8.0), None);
+        assert_eq!(severity(Some(45.0), 50.0), Some(Severity::Warning));
+        assert_eq!(severity(Some(10.0), 50.0), Some(Severity::Critical));
+        // Sin bitrate conocido se usa el umbral fijo
+        assert_eq!(severity(None, 8.0), Some(Severity::Critical));
+    }
+
+    #[test]
+    fn test_obs_process_crash_and_memory() {
+        use crate::domain::models::ProcessUsage;
+
+        let detector = AnomalyDetector::default();
+        let scan = |obs_process: ObsProcessInfo| {
+            let context = SystemContext {
+                obs_process: Some(obs_process),
+                ..Default::default()
+            };
+            detector
+                .scan(&context)
+                .into_iter()
+                .map(|a| (a.anomaly_type, a.severity))
+                .collect::<Vec<_>>()
+        };
+
+        let crashed = ObsProcessInfo {
+            crashes: 1,
+            event: Some(ProcessEvent::Crashed { pid: 4242 }),
+            ..Default::default()
+        };
+        assert_eq!(scan(crashed), vec![(AnomalyType::ObsCrash, Severity::Critical)]);
+
+        let bloated = ObsProcessInfo {
+            main: Some(ProcessUsage {
+                memory_mb: 2048.0,
+                ..Default::default()
+            }),
+            helpers: vec![
+                ProcessUsage {
+                    memory_mb: 4500.0,
+                    ..Default::default()
+                };
+                3
+            ],
+            ..Default::default()
+        };
+        assert_eq!(scan(bloated), vec![(AnomalyType::MemoryLeak, Severity::Warning)]);
+
+        // Proceso sano: sin anomalías
+        assert!(scan(ObsProcessInfo::default()).is_empty());
+    }
+
+    #[test]
+    fn test_cpu_throttling_must_be_sustained() {
+        use crate::domain::models::CoreFrequency;
+
+        let detector = AnomalyDetector::default();
+        let start = Utc::now();
+        let scan = |secs: i64, current_mhz: f64, cpu_usage: f32, cpu_temp: f32| {
+            let context = SystemContext {
+                cpu_usage,
+                cpu_temp,
+                cpu_frequency: Some(CpuFrequencyStats {
+                    cores: (0..4)
+                        .map(|core| CoreFrequency {
+                            core,
+                            current_mhz,
+                            base_mhz: Some(3600.0),
+                            max_mhz: Some(5000.0),
+                            usage_percent: Some(cpu_usage),
+                        })
+                        .collect(),
+                }),
+                timestamp: start + chrono::Duration::seconds(secs),
+                ..Default::default()
+            };
+            detector
+                .scan(&context)
+                .into_iter()
+                .find(|a| a.anomaly_type == AnomalyType::CpuThrottling)
+                .map(|a| a.severity)
+        };
+
+        // En reposo la frecuencia baja sin que sea throttling
+        assert_eq!(scan(0, 800.0, 5.0, 45.0), None);
+        // Bajo carga: se reporta solo si se sostiene
+        assert_eq!(scan(10, 2700.0, 90.0, 70.0), None);
+        assert_eq!(scan(25, 2700.0, 90.0, 70.0), None);
+        assert_eq!(scan(45, 2700.0, 90.0, 70.0), Some(Severity::Warning));
+        assert_eq!(scan(60, 1800.0, 90.0, 95.0), Some(Severity::Critical));
+        // Vuelve a la base: se reinicia
+        assert_eq!(scan(70, 4200.0, 90.0, 70.0), None);
+        assert_eq!(scan(80, 2700.0, 90.0, 70.0), None);
+    }
+
+    #[test]
+    fn test_power_source() {
+        let detector = AnomalyDetector::default();
+        let scan = |power: PowerStatus, obs_process: Option<ObsProcessInfo>| {
+            let context = SystemContext {
+                power: Some(power),
+                obs_process,
+                ..Default::default()
+            };
+            detector
+                .scan(&context)
+                .into_iter()
+                .filter(|a| a.anomaly_type == AnomalyType::PowerSaving)
+                .map(|a| a.severity)
+                .collect::<Vec<_>>()
+        };
+        let battery = |percent| PowerStatus {
+            on_battery: true,
+            battery_percent: Some(percent),
+            profile: Some(PowerProfile::Balanced),
+        };
+
+        assert_eq!(scan(battery(80.0), None), vec![Severity::Warning]);
+        assert_eq!(scan(battery(12.0), None), vec![Severity::Critical]);
+        let power_saver = PowerStatus {
+            profile: Some(PowerProfile::PowerSaver),
+            ..Default::default()
+        };
+        assert_eq!(scan(power_saver, None), vec![Severity::Warning]);
+        // Enchufado y en equilibrado: nada que reportar
+        assert!(scan(PowerStatus::default(), None).is_empty());
+        // OBS cerrado: no hay stream que proteger
+        assert!(scan(battery(12.0), Some(ObsProcessInfo::default())).is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_tracker_transitions() {
+        let mut tracker = AnomalyTracker::new();
+        let now = Utc::now();
+        let warm = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "warm");
+        let hot = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Critical, "hot");
+
+        assert!(matches!(tracker.update(std::slice::from_ref(&warm), now).as_slice(), [AnomalyTransition::Raised { .. }]));
+        // Persistente: sin cambios y con el id de cuando apareció
+        let rescanned = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "warmer");
+        assert!(tracker.update(std::slice::from_ref(&rescanned), now).is_empty());
+        assert_eq!(tracker.active().next().map(|a| (a.id, a.details.as_str())), Some((warm.id, "warmer")));
+        // Escala: se resuelve la advertencia y aparece la crítica
+        let transitions = tracker.update(std::slice::from_ref(&hot), now);
+        assert!(matches!(
+            transitions.as_slice(),
+            [
+                AnomalyTransition::Cleared { severity: Severity::Warning, .. },
+                AnomalyTransition::Raised { anomaly }
+            ] if anomaly.severity == Severity::Critical
+        ));
+        assert_eq!(tracker.active().count(), 1);
+        assert!(matches!(tracker.update(&[], now).as_slice(), [AnomalyTransition::Cleared { .. }]));
+    }
 }
@@ -1,23 +1,83 @@
 use crate::application::ports::{AIPort, OBSConfig};
-use crate::domain::models::HardwareInfo;
+use crate::domain::models::{max_stream_bitrate, EncoderSettings, HardwareInfo, PowerStatus};
 use anyhow::Result;
 use std::sync::Arc;
 use tracing::info;
 
+/// Preset más lento admitido en batería o modo ahorro (x264, NVENC, AMF/QSV)
+const POWER_SAVING_PRESETS: &[&str] = &["veryfast", "p3", "speed"];
+
+/// FPS máximos en batería o modo ahorro
+const POWER_SAVING_MAX_FPS: u32 = 30;
+
 /// Servicio para optimizar configuración de OBS
 pub struct ConfigOptimizer {
     ai_port: Arc<dyn AIPort>,
+    upload_kbps: Option<u32>,
+    power: Option<PowerStatus>,
 }
 
 impl ConfigOptimizer {
     pub fn new(ai_port: Arc<dyn AIPort>) -> Self {
-        Self { ai_port }
+        Self {
+            ai_port,
+            upload_kbps: None,
+            power: None,
+        }
+    }
+
+    /// Limita el bitrate a la subida disponible (dejando margen)
+    pub fn with_upload_kbps(mut self, upload_kbps: u32) -> Self {
+        self.upload_kbps = Some(upload_kbps);
+        self
+    }
+
+    /// Ajusta la configuración a la fuente de alimentación (batería, modo ahorro)
+    pub fn with_power_status(mut self, power: PowerStatus) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// El equipo está en batería o en modo ahorro
+    pub fn is_power_saving(&self) -> bool {
+        self.power.as_ref().is_some_and(PowerStatus::is_power_saving)
+    }
+
+    /// Bitrate máximo de video que soporta la red, si se conoce
+    pub fn max_bitrate(&self) -> Option<u32> {
+        self.upload_kbps.map(max_stream_bitrate)
     }
 
     /// Optimiza configuración basada en hardware
     pub async fn optimize(&self, hardware: &HardwareInfo) -> Result<OBSConfig> {
         info!("Optimizing OBS config for hardware");
-        self.ai_port.optimize_settings(hardware).await
+        let mut config = self.ai_port.optimize_settings(hardware).await?;
+
+        if let Some(max_bitrate) = self.max_bitrate() {
+            if config.bitrate > max_bitrate {
+                info!("Capping bitrate {} -> {} kbps to fit upload bandwidth", config.bitrate, max_bitrate);
+                config.bitrate = max_bitrate;
+            }
+        }
+
+        if self.is_power_saving() {
+            self.apply_power_limits(&mut config);
+        }
+
+        Ok(config)
+    }
+
+    /// Preset más ligero y como mucho 30 FPS para no pelear con los límites de energía
+    pub fn apply_power_limits(&self, config: &mut OBSConfig) {
+        if let Some(preset) = power_saving_preset(&config.preset) {
+            info!("Power saving: preset {} -> {}", config.preset, preset);
+            config.preset = preset;
+        }
+        if config.video.fps() > POWER_SAVING_MAX_FPS as f64 {
+            info!("Power saving: limiting to {} FPS", POWER_SAVING_MAX_FPS);
+            config.video.fps_numerator = POWER_SAVING_MAX_FPS;
+            config.video.fps_denominator = 1;
+        }
     }
 
     /// Valida si una configuración es apropiada para el hardware
@@ -39,13 +99,152 @@ impl ConfigOptimizer {
             return false;
         }
 
+        // Validar bitrate contra la red
+        if self.max_bitrate().is_some_and(|max| config.bitrate > max) {
+            return false;
+        }
+
+        // Validar contra el estado de energía
+        if self.is_power_saving()
+            && (config.video.fps() > POWER_SAVING_MAX_FPS as f64 || power_saving_preset(&config.preset).is_some())
+        {
+            return false;
+        }
+
         true
     }
 }
 
+/// Preset que sustituye a `preset` en batería o modo ahorro (`None` si ya es ligero)
+fn power_saving_preset(preset: &str) -> Option<String> {
+    let mut settings = EncoderSettings {
+        encoder: String::new(),
+        preset: preset.to_lowercase(),
+        bitrate_kbps: 0,
+    };
+    let mut lighter = None;
+    // Bajar un escalón cada vez hasta el preset de ahorro; si se pasa de él ya era ligero
+    while !POWER_SAVING_PRESETS.contains(&settings.preset.as_str()) {
+        settings.preset = settings.faster_preset()?;
+        lighter = Some(settings.preset.clone());
+    }
+    lighter
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // Tests requieren implementación de mock AIPort
+    use crate::application::ports::{ConfigAnalysis, ImageAnalysis, OverlayDesign};
+    use crate::domain::models::{Anomaly, VideoSettings};
+
+    /// IA de prueba que siempre propone 1080p60 con x264 medium a 6000 kbps
+    struct NoAI;
+
+    #[async_trait::async_trait]
+    impl AIPort for NoAI {
+        async fn analyze_config(&self, _: &HardwareInfo) -> Result<ConfigAnalysis> {
+            Ok(ConfigAnalysis {
+                is_optimal: true,
+                issues: Vec::new(),
+                recommendations: Vec::new(),
+                required_plugins: Vec::new(),
+            })
+        }
+
+        async fn suggest_fix(&self, anomaly: &Anomaly) -> Result<String> {
+            Ok(anomaly.recommended_action.clone())
+        }
+
+        async fn analyze_image(&self, _: &[u8]) -> Result<ImageAnalysis> {
+            Ok(ImageAnalysis {
+                description: String::new(),
+                elements: Vec::new(),
+                text_detected: None,
+            })
+        }
+
+        async fn optimize_settings(&self, _: &HardwareInfo) -> Result<OBSConfig> {
+            Ok(OBSConfig {
+                video: VideoSettings {
+                    base_width: 1920,
+                    base_height: 1080,
+                    output_width: 1920,
+                    output_height: 1080,
+                    fps_numerator: 60,
+                    fps_denominator: 1,
+                },
+                encoder: "x264".to_string(),
+                preset: "medium".to_string(),
+                bitrate: 6000,
+                audio_settings: serde_json::Value::Null,
+            })
+        }
+
+        async fn generate_overlay_design(&self, _: &str) -> Result<OverlayDesign> {
+            Ok(OverlayDesign {
+                colors: Vec::new(),
+                layout: String::new(),
+                elements: Vec::new(),
+            })
+        }
+
+        async fn generate(&self, _: &str) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    #[test]
+    fn test_max_bitrate_from_upload() {
+        assert_eq!(ConfigOptimizer::new(Arc::new(NoAI)).max_bitrate(), None);
+        // 8 Mbps de subida -> 6 Mbps para el stream
+        assert_eq!(ConfigOptimizer::new(Arc::new(NoAI)).with_upload_kbps(8000).max_bitrate(), Some(6000));
+    }
+
+    #[test]
+    fn test_power_saving_limits() {
+        use crate::domain::models::PowerProfile;
+
+        let config = |preset: &str, fps: u32| OBSConfig {
+            video: VideoSettings {
+                base_width: 1920,
+                base_height: 1080,
+                output_width: 1920,
+                output_height: 1080,
+                fps_numerator: fps,
+                fps_denominator: 1,
+            },
+            encoder: "x264".to_string(),
+            preset: preset.to_string(),
+            bitrate: 6000,
+            audio_settings: serde_json::Value::Null,
+        };
+        let optimizer = ConfigOptimizer::new(Arc::new(NoAI)).with_power_status(PowerStatus {
+            profile: Some(PowerProfile::PowerSaver),
+            ..Default::default()
+        });
+        assert!(optimizer.is_power_saving());
+
+        let mut x264 = config("medium", 60);
+        optimizer.apply_power_limits(&mut x264);
+        assert_eq!(x264.preset, "veryfast");
+        assert_eq!(x264.video.fps(), 30.0);
+
+        let mut nvenc = config("p5", 30);
+        optimizer.apply_power_limits(&mut nvenc);
+        assert_eq!(nvenc.preset, "p3");
+
+        let mut placebo = config("placebo", 30);
+        optimizer.apply_power_limits(&mut placebo);
+        assert_eq!(placebo.preset, "veryfast");
+
+        let mut amf = config("quality", 30);
+        optimizer.apply_power_limits(&mut amf);
+        assert_eq!(amf.preset, "speed");
+
+        // Presets ya ligeros no cambian
+        let mut fast = config("superfast", 30);
+        optimizer.apply_power_limits(&mut fast);
+        assert_eq!(fast.preset, "superfast");
+    }
 }
@@ -0,0 +1,175 @@
+use crate::application::ports::{
+    AgentRegistration, CommandKind, CommandResult, FleetCommand, FleetPort, Heartbeat, OBSPort, RegistrationAck,
+};
+use crate::domain::services::health_check::{HealthCheckService, HealthReport};
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Latido por defecto hasta que el servidor indique otro
+const DEFAULT_HEARTBEAT_SECS: u64 = 15;
+
+/// Agente de la flota: informa al servidor central y ejecuta sus comandos
+///
+/// Cada latido lleva una muestra, la salud que se deduce de ella y los
+/// resultados de los comandos del latido anterior.
+pub struct FleetAgent {
+    fleet: Arc<dyn FleetPort>,
+    obs_port: Arc<dyn OBSPort>,
+    /// Sus reglas son las que evalúan cada muestra
+    health: HealthCheckService,
+    registration: AgentRegistration,
+    registered: AtomicBool,
+    interval_secs: AtomicU64,
+    /// Resultados aún no entregados al servidor
+    results: Mutex<Vec<CommandResult>>,
+}
+
+impl FleetAgent {
+    pub fn new(
+        fleet: Arc<dyn FleetPort>,
+        obs_port: Arc<dyn OBSPort>,
+        health: HealthCheckService,
+        registration: AgentRegistration,
+    ) -> Self {
+        Self {
+            fleet,
+            obs_port,
+            health,
+            registration,
+            registered: AtomicBool::new(false),
+            interval_secs: AtomicU64::new(DEFAULT_HEARTBEAT_SECS),
+            results: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn host_id(&self) -> &str {
+        &self.registration.host_id
+    }
+
+    /// Intervalo de latido pedido por el servidor
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.load(Ordering::Relaxed).max(1))
+    }
+
+    pub async fn register(&self) -> Result<RegistrationAck> {
+        let ack = self.fleet.register(&self.registration).await?;
+        self.interval_secs.store(ack.heartbeat_interval_secs, Ordering::Relaxed);
+        self.registered.store(true, Ordering::Relaxed);
+        info!("Registered in the fleet as '{}' (heartbeat every {}s)", ack.host_id, ack.heartbeat_interval_secs);
+        Ok(ack)
+    }
+
+    /// Latido con una muestra nueva (sin los resultados pendientes)
+    pub async fn heartbeat(&self) -> Heartbeat {
+        let timestamp = chrono::Utc::now();
+        match self.health.sample().await {
+            Ok(sample) => {
+                let anomalies = self.health.detector().scan(&sample.context);
+                let ram_available_gb = sample.ram_available_gb.unwrap_or_default();
+                let health = HealthReport::from_anomalies(anomalies, ram_available_gb, sample.timestamp);
+                Heartbeat {
+                    timestamp,
+                    sample: Some(sample),
+                    health: Some(health),
+                    error: None,
+                    results: Vec::new(),
+                }
+            }
+            Err(e) => Heartbeat {
+                timestamp,
+                sample: None,
+                health: None,
+                error: Some(format!("{:#}", e)),
+                results: Vec::new(),
+            },
+        }
+    }
+
+    /// Envía un latido y ejecuta los comandos recibidos; devuelve cuántos
+    pub async fn beat(&self) -> Result<usize> {
+        if !self.registered.load(Ordering::Relaxed) {
+            self.register().await?;
+        }
+
+        let mut heartbeat = self.heartbeat().await;
+        heartbeat.results = std::mem::take(&mut *self.results.lock().await);
+        let commands = match self.send(&heartbeat).await {
+            Ok(commands) => commands,
+            Err(e) => {
+                // Los resultados se entregan en el siguiente latido
+                self.results.lock().await.splice(0..0, heartbeat.results);
+                return Err(e);
+            }
+        };
+
+        for command in &commands {
+            let result = self.execute(command).await;
+            self.results.lock().await.push(result);
+        }
+        Ok(commands.len())
+    }
+
+    async fn send(&self, heartbeat: &Heartbeat) -> Result<Vec<FleetCommand>> {
+        if let Some(commands) = self.fleet.heartbeat(self.host_id(), heartbeat).await? {
+            return Ok(commands);
+        }
+        warn!("The fleet server does not know '{}', registering again", self.host_id());
+        self.registered.store(false, Ordering::Relaxed);
+        self.register().await?;
+        self.fleet
+            .heartbeat(self.host_id(), heartbeat)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("The fleet server rejected heartbeats from '{}'", self.host_id()))
+    }
+
+    /// Ejecuta un comando sobre OBS; los fallos quedan en el resultado
+    pub async fn execute(&self, command: &FleetCommand) -> CommandResult {
+        info!("Fleet command from {}: {}", command.issued_by, command.kind);
+        let outcome = match &command.kind {
+            CommandKind::SwitchScene { scene } => self
+                .obs_port
+                .set_current_scene(scene)
+                .await
+                .map(|_| format!("Switched to scene '{}'", scene)),
+            CommandKind::StartOutput { output } => self
+                .obs_port
+                .set_output_active(output, true)
+                .await
+                .map(|_| format!("Started {}", output)),
+            CommandKind::StopOutput { output } => self
+                .obs_port
+                .set_output_active(output, false)
+                .await
+                .map(|_| format!("Stopped {}", output)),
+            CommandKind::RunHealthCheck => self.health.check().await.map(|report| report.summary()),
+        };
+        if let Err(e) = &outcome {
+            warn!("Fleet command '{}' failed: {:#}", command.kind, e);
+        }
+
+        CommandResult {
+            command_id: command.id,
+            success: outcome.is_ok(),
+            message: outcome.unwrap_or_else(|e| format!("{:#}", e)),
+            finished_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Latidos hasta que se cancele la tarea; los fallos se registran y se reintenta
+    ///
+    /// Tras ejecutar comandos se late enseguida para informar los resultados.
+    pub async fn run(&self) {
+        loop {
+            match self.beat().await {
+                Ok(0) => {}
+                Ok(_) => continue,
+                Err(e) => warn!("Fleet heartbeat failed: {:#}", e),
+            }
+            tokio::time::sleep(self.interval()).await;
+        }
+    }
+}
@@ -1,13 +1,17 @@
-use crate::application::ports::{MonitorPort, OBSPort};
-use crate::domain::models::{Anomaly, Severity};
-use crate::domain::services::anomaly_detector::{AnomalyDetector, SystemContext};
+use crate::application::ports::{DiskInfo, MonitorPort, NetworkPort, OBSPort};
+use crate::domain::models::{Anomaly, HardwareInfo, NetworkStats, OBSStats, Severity, SourceRef};
+use crate::domain::services::anomaly_detector::{AnomalyDetector, ContextProvider, SystemContext};
+use crate::domain::services::telemetry::TelemetryRecord;
 use anyhow::Result;
+use async_trait::async_trait;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, warn};
 
 /// Reporte de salud del sistema
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HealthReport {
     pub is_healthy: bool,
     pub timestamp: chrono::DateTime<chrono::Utc>,
@@ -19,6 +23,40 @@ pub struct HealthReport {
 }
 
 impl HealthReport {
+    /// Construye el reporte a partir de anomalías ya detectadas
+    pub fn from_anomalies(
+        anomalies: Vec<Anomaly>,
+        ram_available_gb: f64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        // Categorizar anomalías
+        let critical_issues: Vec<String> = anomalies
+            .iter()
+            .filter(|a| a.severity == Severity::Critical)
+            .map(|a| a.details.clone())
+            .collect();
+
+        let warnings: Vec<String> = anomalies
+            .iter()
+            .filter(|a| a.severity == Severity::Warning)
+            .map(|a| a.details.clone())
+            .collect();
+
+        let can_stream = critical_issues.is_empty();
+        let can_record = critical_issues.is_empty() && ram_available_gb > 2.0;
+        let is_healthy = critical_issues.is_empty() && warnings.is_empty();
+
+        Self {
+            is_healthy,
+            timestamp,
+            anomalies,
+            can_stream,
+            can_record,
+            warnings,
+            critical_issues,
+        }
+    }
+
     pub fn summary(&self) -> String {
         if self.is_healthy {
             "System is healthy and ready to stream".to_string()
@@ -34,7 +72,8 @@ impl HealthReport {
 pub struct HealthCheckService {
     obs_port: Arc<dyn OBSPort>,
     monitor_port: Arc<dyn MonitorPort>,
-    detector: AnomalyDetector,
+    network_port: Option<Arc<dyn NetworkPort>>,
+    detector: Arc<AnomalyDetector>,
 }
 
 impl HealthCheckService {
@@ -45,104 +84,185 @@ impl HealthCheckService {
         Self {
             obs_port,
             monitor_port,
-            detector: AnomalyDetector::with_default_rules(),
+            network_port: None,
+            detector: Arc::new(AnomalyDetector::with_default_rules()),
         }
     }
 
-    pub fn with_detector(mut self, detector: AnomalyDetector) -> Self {
+    /// Usa un detector compartido (las reglas con estado necesitan ver todas las muestras)
+    pub fn with_detector(mut self, detector: Arc<AnomalyDetector>) -> Self {
         self.detector = detector;
         self
     }
 
-    /// Ejecuta health check completo
-    pub async fn check(&self) -> Result<HealthReport> {
-        info!("Starting health check...");
+    pub fn detector(&self) -> &AnomalyDetector {
+        &self.detector
+    }
 
+    /// Mide también la red (tráfico y latencia al servidor de ingesta de OBS)
+    pub fn with_network(mut self, network_port: Arc<dyn NetworkPort>) -> Self {
+        self.network_port = Some(network_port);
+        self
+    }
+
+    /// Tráfico de red y latencia al servidor de ingesta configurado en OBS
+    async fn sample_network(&self) -> Option<NetworkStats> {
+        let network_port = self.network_port.as_ref()?;
+
+        let ingest = self
+            .obs_port
+            .get_stream_settings()
+            .await
+            .map_err(|e| warn!("Could not read stream settings: {}", e))
+            .ok()
+            .and_then(|settings| settings.ingest());
+
+        network_port
+            .sample(ingest.as_ref())
+            .await
+            .map_err(|e| warn!("Network sampling failed: {}", e))
+            .ok()
+    }
+
+    /// Disco donde graba OBS y minutos de grabación que caben en él.
+    /// Con OBS remoto, o si no se puede leer, se usa el disco por defecto sin estimar minutos.
+    async fn recording_disk(&self) -> Result<(DiskInfo, Option<f64>)> {
+        if self.obs_port.is_local() {
+            match self.obs_port.get_recording_settings().await {
+                Ok(recording) => {
+                    match self.monitor_port.get_disk_space_for(std::path::Path::new(&recording.directory)) {
+                        Ok(disk) => {
+                            let bitrate = match recording.bitrate_kbps {
+                                Some(bitrate) => Some(bitrate),
+                                None => self.measured_recording_bitrate().await,
+                            };
+                            let minutes_left = bitrate.and_then(|bitrate| disk.minutes_until_full(bitrate));
+                            return Ok((disk, minutes_left));
+                        }
+                        Err(e) => warn!("Could not read free space for {}: {}", recording.directory, e),
+                    }
+                }
+                Err(e) => warn!("Could not read recording settings: {}", e),
+            }
+        }
+        Ok((self.monitor_port.get_disk_space()?, None))
+    }
+
+    /// Bitrate de la grabación en curso, para cuando el perfil no lo indica (modo Avanzado)
+    async fn measured_recording_bitrate(&self) -> Option<u32> {
+        let outputs = self.obs_port.get_output_status().await.ok()?;
+        outputs.iter().find(|o| o.name == "record")?.average_kbps()
+    }
+
+    /// Recolecta hardware, estadísticas de OBS y contexto del sistema
+    async fn gather(&self) -> Result<(HardwareInfo, OBSStats, SystemContext)> {
         // Recolectar datos del sistema (paralelo)
-        let (hardware_result, obs_stats_result, scenes_result) = tokio::join!(
+        let (hardware_result, obs_stats_result, scenes_result, disk_result, network) = tokio::join!(
             async { self.monitor_port.detect_hardware() },
             async { self.obs_port.get_stats().await },
             async { self.obs_port.get_scenes().await },
+            self.recording_disk(),
+            self.sample_network(),
         );
 
         let hardware = hardware_result?;
         let obs_stats = obs_stats_result?;
         let scenes = scenes_result?;
+        let (disk, recording_minutes_left) = disk_result?;
 
         // Validar escenas en paralelo con Rayon
-        let missing_sources: Vec<String> = scenes
+        let missing_sources: Vec<SourceRef> = scenes
             .par_iter()
             .flat_map(|scene| {
                 scene
                     .sources
                     .iter()
                     .filter(|s| !s.is_available)
-                    .map(|s| format!("{}:{}", scene.name, s.name))
+                    .map(|s| SourceRef::new(&scene.name, &s.name))
                     .collect::<Vec<_>>()
             })
             .collect();
 
         // Construir contexto del sistema
         let context = SystemContext {
-            cpu_temp: self.monitor_port.get_cpu_temp().unwrap_or(0.0),
-            gpu_temp: self.monitor_port.get_gpu_temp().unwrap_or(0.0),
+            cpu_temp: self.monitor_port.get_cpu_temp().ok().flatten().unwrap_or(0.0),
+            gpu_temp: self.monitor_port.get_gpu_temp().ok().flatten().unwrap_or(0.0),
             cpu_usage: self.monitor_port.get_cpu_usage().unwrap_or(0.0),
+            cpu_frequency: self.monitor_port.get_cpu_frequency().ok().flatten(),
             memory_used_percent: hardware.ram.used_percent,
-            disk_free_gb: self.monitor_port.get_disk_space()?.free_gb,
+            disk_free_gb: disk.free_gb,
             obs_dropped_frames_percent: obs_stats.dropped_frames_percent(),
             obs_cpu_usage: obs_stats.cpu_usage,
             missing_sources,
             audio_peak_db: None, // TODO: Implementar
-            network_bitrate: None, // TODO: Implementar
+            network_bitrate: network.as_ref().and_then(|n| n.estimated_upload_kbps),
+            recording_minutes_left,
+            obs_process: self
+                .monitor_port
+                .get_obs_process()
+                .map_err(|e| warn!("Could not read OBS process stats: {}", e))
+                .ok()
+                .flatten(),
+            network,
+            power: self.monitor_port.get_power_status().ok().flatten(),
+            timestamp: chrono::Utc::now(),
         };
 
-        // Detectar anomalías (paralelo con Rayon)
-        let anomalies = self.detector.scan(&context);
+        Ok((hardware, obs_stats, context))
+    }
 
-        // Categorizar anomalías
-        let critical_issues: Vec<String> = anomalies
-            .iter()
-            .filter(|a| a.severity == Severity::Critical)
-            .map(|a| a.details.clone())
-            .collect();
+    /// Toma una muestra de telemetría (para grabación o métricas)
+    pub async fn sample(&self) -> Result<TelemetryRecord> {
+        let (gathered, scene, outputs) = tokio::join!(
+            self.gather(),
+            self.obs_port.get_current_scene(),
+            self.obs_port.get_output_status(),
+        );
+        let (hardware, obs_stats, context) = gathered?;
+        Ok(TelemetryRecord {
+            timestamp: context.timestamp,
+            context,
+            obs_stats: Some(obs_stats),
+            ram_available_gb: Some(hardware.ram.available_gb),
+            scene: scene.ok(),
+            outputs: outputs.map_err(|e| warn!("Could not read OBS outputs: {}", e)).unwrap_or_default(),
+        })
+    }
 
-        let warnings: Vec<String> = anomalies
-            .iter()
-            .filter(|a| a.severity == Severity::Warning)
-            .map(|a| a.details.clone())
-            .collect();
+    /// Ejecuta health check completo
+    pub async fn check(&self) -> Result<HealthReport> {
+        info!("Starting health check...");
 
-        let can_stream = critical_issues.is_empty();
-        let can_record = critical_issues.is_empty() && hardware.ram.available_gb > 2.0;
-        let is_healthy = critical_issues.is_empty() && warnings.is_empty();
+        let (hardware, _, context) = self.gather().await?;
+
+        // Detectar anomalías (paralelo con Rayon)
+        let anomalies = self.detector.scan(&context);
+        let report = HealthReport::from_anomalies(
+            anomalies,
+            hardware.ram.available_gb,
+            chrono::Utc::now(),
+        );
 
-        if !is_healthy {
+        if !report.is_healthy {
             warn!(
                 "Health check found {} critical issue(s) and {} warning(s)",
-                critical_issues.len(),
-                warnings.len()
+                report.critical_issues.len(),
+                report.warnings.len()
             );
         } else {
             info!("Health check passed - system is healthy");
         }
 
-        Ok(HealthReport {
-            is_healthy,
-            timestamp: chrono::Utc::now(),
-            anomalies,
-            can_stream,
-            can_record,
-            warnings,
-            critical_issues,
-        })
+        Ok(report)
     }
 
     /// Check rápido (solo crítico)
     pub async fn quick_check(&self) -> Result<bool> {
         let context = SystemContext {
-            cpu_temp: self.monitor_port.get_cpu_temp().unwrap_or(0.0),
-            gpu_temp: self.monitor_port.get_gpu_temp().unwrap_or(0.0),
+            cpu_temp: self.monitor_port.get_cpu_temp().ok().flatten().unwrap_or(0.0),
+            gpu_temp: self.monitor_port.get_gpu_temp().ok().flatten().unwrap_or(0.0),
             cpu_usage: self.monitor_port.get_cpu_usage().unwrap_or(0.0),
+            cpu_frequency: self.monitor_port.get_cpu_frequency().ok().flatten(),
             memory_used_percent: self.monitor_port.get_memory_info()?.used_percent,
             disk_free_gb: self.monitor_port.get_disk_space()?.free_gb,
             obs_dropped_frames_percent: 0.0,
@@ -150,6 +270,11 @@ impl HealthCheckService {
             missing_sources: vec![],
             audio_peak_db: None,
             network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: self.monitor_port.get_obs_process().ok().flatten(),
+            network: None,
+            power: self.monitor_port.get_power_status().ok().flatten(),
+            timestamp: chrono::Utc::now(),
         };
 
         let anomalies = self.detector.scan_filtered(&context, Severity::Critical);
@@ -157,18 +282,40 @@ impl HealthCheckService {
     }
 }
 
+#[async_trait]
+impl ContextProvider for HealthCheckService {
+    async fn collect(&self) -> Result<SystemContext> {
+        let (_, _, context) = self.gather().await?;
+        Ok(context)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::application::ports::*;
     use async_trait::async_trait;
 
-    struct MockOBSPort;
+    struct MockOBSPort {
+        remote: bool,
+        /// Bitrate según el perfil (`None` como en modo Avanzado)
+        recording_bitrate: Option<u32>,
+    }
+
+    impl MockOBSPort {
+        fn new() -> Self {
+            Self {
+                remote: false,
+                recording_bitrate: Some(6160),
+            }
+        }
+    }
 
     #[async_trait]
     impl OBSPort for MockOBSPort {
         async fn connect(&self) -> Result<()> { Ok(()) }
         async fn disconnect(&self) -> Result<()> { Ok(()) }
+        fn is_local(&self) -> bool { !self.remote }
         async fn get_scenes(&self) -> Result<Vec<crate::domain::models::Scene>> { Ok(vec![]) }
         async fn get_current_scene(&self) -> Result<String> { Ok("Scene 1".to_string()) }
         async fn validate_scene(&self, _: &str) -> Result<ValidationReport> {
@@ -201,13 +348,50 @@ mod tests {
         }
         async fn set_video_settings(&self, _: &crate::domain::models::VideoSettings) -> Result<()> { Ok(()) }
         async fn take_screenshot(&self, _: &str) -> Result<Vec<u8>> { Ok(vec![]) }
+        async fn set_current_scene(&self, _: &str) -> Result<()> { Ok(()) }
+        async fn set_source_visible(&self, _: &str, _: &str, _: bool) -> Result<()> { Ok(()) }
+        async fn get_encoder_settings(&self) -> Result<crate::domain::models::EncoderSettings> {
+            Ok(crate::domain::models::EncoderSettings {
+                encoder: "x264".to_string(),
+                preset: "veryfast".to_string(),
+                bitrate_kbps: 6000,
+            })
+        }
+        async fn set_encoder_settings(&self, _: &crate::domain::models::EncoderSettings) -> Result<()> { Ok(()) }
+        async fn start_replay_buffer(&self) -> Result<()> { Ok(()) }
+        async fn get_recording_settings(&self) -> Result<crate::domain::models::RecordingSettings> {
+            Ok(crate::domain::models::RecordingSettings {
+                directory: "/recordings".to_string(),
+                bitrate_kbps: self.recording_bitrate,
+            })
+        }
+        async fn get_output_status(&self) -> Result<Vec<crate::domain::models::OutputStatus>> {
+            // Grabando desde hace un minuto a 8000 kbps
+            Ok(vec![crate::domain::models::OutputStatus {
+                name: "record".to_string(),
+                active: true,
+                bytes: 60_000_000,
+                duration_secs: 60.0,
+                ..Default::default()
+            }])
+        }
+        async fn get_stream_settings(&self) -> Result<crate::domain::models::StreamSettings> {
+            Ok(crate::domain::models::StreamSettings {
+                service_type: "rtmp_custom".to_string(),
+                server: Some("rtmp://live.example.com/app".to_string()),
+            })
+        }
+        async fn stop_replay_buffer(&self) -> Result<()> { Ok(()) }
     }
 
-    struct MockMonitorPort;
+    #[derive(Default)]
+    struct MockMonitorPort {
+        recording_disk_fails: bool,
+    }
 
     impl MonitorPort for MockMonitorPort {
-        fn get_cpu_temp(&self) -> Result<f32> { Ok(50.0) }
-        fn get_gpu_temp(&self) -> Result<f32> { Ok(60.0) }
+        fn get_cpu_temp(&self) -> Result<Option<f32>> { Ok(Some(50.0)) }
+        fn get_gpu_temp(&self) -> Result<Option<f32>> { Ok(Some(60.0)) }
         fn get_cpu_usage(&self) -> Result<f32> { Ok(30.0) }
         fn get_memory_info(&self) -> Result<crate::domain::models::RAMInfo> {
             Ok(crate::domain::models::RAMInfo {
@@ -221,8 +405,15 @@ mod tests {
                 total_gb: 500.0,
                 free_gb: 250.0,
                 used_percent: 50.0,
+                mount_point: None,
             })
         }
+        fn get_disk_space_for(&self, path: &std::path::Path) -> Result<DiskInfo> {
+            if self.recording_disk_fails {
+                anyhow::bail!("{} does not exist", path.display());
+            }
+            self.get_disk_space()
+        }
         fn detect_hardware(&self) -> Result<crate::domain::models::HardwareInfo> {
             Ok(crate::domain::models::HardwareInfo {
                 os: "Windows".to_string(),
@@ -236,7 +427,9 @@ mod tests {
                     frequency_mhz: 3600.0,
                     arch: "x86_64".to_string(),
                 },
-                gpu: None,
+                gpus: vec![],
+                render_gpu: None,
+                encode_gpu: None,
                 ram: crate::domain::models::RAMInfo {
                     total_gb: 16.0,
                     available_gb: 8.0,
@@ -247,14 +440,90 @@ mod tests {
                 recommended_resolution: (1920, 1080),
                 recommended_fps: 60,
                 recommended_bitrate: 6000,
+                measured_upload_kbps: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recording_minutes_in_context() {
+        let service = HealthCheckService::new(Arc::new(MockOBSPort::new()), Arc::new(MockMonitorPort::default()));
+        let context = service.collect().await.unwrap();
+
+        // 250 GiB a 6160 kbps ≈ 96 horas
+        let minutes = context.recording_minutes_left.unwrap();
+        assert!((minutes - 5810.0).abs() < 1.0, "{}", minutes);
+    }
+
+    #[tokio::test]
+    async fn test_recording_minutes_from_measured_bitrate() {
+        let obs = MockOBSPort {
+            recording_bitrate: None,
+            ..MockOBSPort::new()
+        };
+        let service = HealthCheckService::new(Arc::new(obs), Arc::new(MockMonitorPort::default()));
+        let context = service.collect().await.unwrap();
+
+        // Sin bitrate en el perfil se usa el de la grabación en curso (8000 kbps)
+        let minutes = context.recording_minutes_left.unwrap();
+        assert!((minutes - 4473.9).abs() < 1.0, "{}", minutes);
+    }
+
+    #[tokio::test]
+    async fn test_recording_disk_skipped_when_unavailable() {
+        let remote = MockOBSPort {
+            remote: true,
+            ..MockOBSPort::new()
+        };
+        let service = HealthCheckService::new(Arc::new(remote), Arc::new(MockMonitorPort::default()));
+        assert!(service.collect().await.unwrap().recording_minutes_left.is_none());
+
+        // Un directorio de grabación ilegible no tumba el health check
+        let monitor = MockMonitorPort {
+            recording_disk_fails: true,
+        };
+        let service = HealthCheckService::new(Arc::new(MockOBSPort::new()), Arc::new(monitor));
+        let context = service.collect().await.unwrap();
+        assert!(context.recording_minutes_left.is_none());
+        assert_eq!(context.disk_free_gb, 250.0);
+    }
+
+    struct MockNetworkPort;
+
+    #[async_trait]
+    impl NetworkPort for MockNetworkPort {
+        async fn sample(&self, ingest: Option<&crate::domain::models::IngestEndpoint>) -> Result<NetworkStats> {
+            let endpoint = ingest.expect("ingest server from stream settings").to_string();
+            Ok(NetworkStats {
+                latency: Some(crate::domain::models::LatencyStats::from_samples(endpoint, &[18.0, 22.0], 0)),
+                estimated_upload_kbps: Some(9500),
+                ..Default::default()
             })
         }
     }
 
+    #[tokio::test]
+    async fn test_network_in_context() {
+        let service = HealthCheckService::new(Arc::new(MockOBSPort::new()), Arc::new(MockMonitorPort::default()))
+            .with_network(Arc::new(MockNetworkPort));
+        let context = service.collect().await.unwrap();
+
+        assert_eq!(context.network_bitrate, Some(9500));
+        let latency = context.network.unwrap().latency.unwrap();
+        assert_eq!(latency.endpoint, "live.example.com:1935");
+
+        // Sin puerto de red no se mide nada
+        let context = HealthCheckService::new(Arc::new(MockOBSPort::new()), Arc::new(MockMonitorPort::default()))
+            .collect()
+            .await
+            .unwrap();
+        assert!(context.network.is_none());
+    }
+
     #[tokio::test]
     async fn test_health_check() {
-        let obs_port = Arc::new(MockOBSPort) as Arc<dyn OBSPort>;
-        let monitor_port = Arc::new(MockMonitorPort) as Arc<dyn MonitorPort>;
+        let obs_port = Arc::new(MockOBSPort::new()) as Arc<dyn OBSPort>;
+        let monitor_port = Arc::new(MockMonitorPort::default()) as Arc<dyn MonitorPort>;
 
         let service = HealthCheckService::new(obs_port, monitor_port);
         let report = service.check().await.unwrap();
@@ -1,7 +1,15 @@
 pub mod anomaly_detector;
 pub mod health_check;
 pub mod config_optimizer;
+pub mod fleet_agent;
+pub mod remediation;
+pub mod scenario;
+pub mod telemetry;
 
 pub use anomaly_detector::*;
 pub use health_check::*;
 pub use config_optimizer::*;
+pub use fleet_agent::*;
+pub use remediation::*;
+pub use scenario::*;
+pub use telemetry::*;
@@ -0,0 +1,777 @@
+use crate::application::ports::OBSPort;
+use crate::domain::models::{Anomaly, AnomalyType, Severity};
+use crate::domain::services::anomaly_detector::{AnomalyDetector, ContextProvider};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Acción concreta que el motor puede aplicar sobre OBS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RemediationAction {
+    /// Reduce el bitrate del encoder multiplicándolo por `factor`, sin bajar de `min_kbps`
+    /// (se guarda en el perfil: OBS lo usa desde el próximo inicio del stream)
+    LowerBitrate { factor: f64, min_kbps: u32 },
+    /// Cambia al preset inmediatamente más rápido del encoder actual (desde el próximo inicio)
+    FasterPreset,
+    /// Oculta las fuentes reportadas en `Anomaly::sources`
+    HideMissingSources,
+    /// Cambia a una escena de respaldo
+    SwitchScene { scene: String },
+    /// Reinicia el replay buffer activo (libera la memoria que retiene)
+    RestartReplayBuffer,
+}
+
+impl RemediationAction {
+    /// Acciones que solo cambian el perfil y no afectan a una salida ya iniciada
+    pub fn takes_effect_on_restart(&self) -> bool {
+        matches!(self, Self::LowerBitrate { .. } | Self::FasterPreset)
+    }
+
+    /// Acciones que interrumpen lo que ve la audiencia
+    pub fn is_disruptive(&self) -> bool {
+        matches!(
+            self,
+            Self::HideMissingSources | Self::SwitchScene { .. }
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::LowerBitrate { factor, min_kbps } => format!(
+                "Lower bitrate to {:.0}% (min {} kbps)",
+                factor * 100.0,
+                min_kbps
+            ),
+            Self::FasterPreset => "Switch encoder to a faster preset".to_string(),
+            Self::HideMissingSources => "Hide missing sources".to_string(),
+            Self::SwitchScene { scene } => format!("Switch to fallback scene '{}'", scene),
+            Self::RestartReplayBuffer => "Restart replay buffer".to_string(),
+        }
+    }
+}
+
+/// Registro que asocia tipos de anomalía con acciones de remediación
+#[derive(Debug, Clone, Default)]
+pub struct RemediationRegistry {
+    actions: HashMap<AnomalyType, RemediationAction>,
+}
+
+impl RemediationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_action(mut self, anomaly_type: AnomalyType, action: RemediationAction) -> Self {
+        self.actions.insert(anomaly_type, action);
+        self
+    }
+
+    /// Registro por defecto (sin escena de respaldo)
+    ///
+    /// Con x264 el preset es lo que más carga la CPU, así que un preset más rápido la enfría.
+    /// La temperatura de GPU no se remedia: depende sobre todo del render de escenas o juegos,
+    /// que ni el bitrate ni el preset alivian.
+    pub fn with_default_actions() -> Self {
+        let lower_bitrate = RemediationAction::LowerBitrate {
+            factor: 0.8,
+            min_kbps: 1500,
+        };
+
+        Self::new()
+            .with_action(AnomalyType::DroppedFrames, lower_bitrate.clone())
+            .with_action(AnomalyType::NetworkUnstable, lower_bitrate.clone())
+            .with_action(AnomalyType::BitrateIssue, lower_bitrate)
+            .with_action(AnomalyType::HighCPUTemp, RemediationAction::FasterPreset)
+            .with_action(AnomalyType::EncoderOverload, RemediationAction::FasterPreset)
+            .with_action(AnomalyType::MissingSource, RemediationAction::HideMissingSources)
+            .with_action(AnomalyType::MemoryLeak, RemediationAction::RestartReplayBuffer)
+    }
+
+    /// Añade la escena de respaldo para plugins caídos
+    pub fn with_fallback_scene(self, scene: impl Into<String>) -> Self {
+        self.with_action(
+            AnomalyType::PluginCrash,
+            RemediationAction::SwitchScene {
+                scene: scene.into(),
+            },
+        )
+    }
+
+    pub fn action_for(&self, anomaly_type: AnomalyType) -> Option<&RemediationAction> {
+        self.actions.get(&anomaly_type)
+    }
+}
+
+/// Cuándo pedir confirmación antes de aplicar una acción
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfirmationPolicy {
+    /// Aplicar sin preguntar
+    Never,
+    /// Preguntar solo para acciones disruptivas o anomalías críticas
+    Disruptive,
+    /// Preguntar siempre
+    Always,
+}
+
+/// Política de ejecución del motor de remediación
+#[derive(Debug, Clone)]
+pub struct RemediationPolicy {
+    /// Solo planificar, sin tocar OBS
+    pub dry_run: bool,
+    pub confirmation: ConfirmationPolicy,
+    /// Intentos máximos por tipo de anomalía
+    pub max_attempts: u32,
+    /// Espera antes de verificar que la anomalía desapareció
+    pub verify_delay: Duration,
+    /// Tiempo sin intentos tras el que se olvida el contador de un tipo de anomalía
+    pub attempt_window: Duration,
+}
+
+impl Default for RemediationPolicy {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            confirmation: ConfirmationPolicy::Disruptive,
+            max_attempts: 3,
+            verify_delay: Duration::from_secs(5),
+            attempt_window: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+/// Decide si se aprueba una acción (p.ej. prompt en CLI o diálogo en GUI)
+pub trait ConfirmationHandler: Send + Sync {
+    fn confirm(&self, anomaly: &Anomaly, action: &RemediationAction) -> bool;
+}
+
+/// Motivo por el que no se aplicó una acción
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum SkipReason {
+    NotAutoFixable,
+    NoActionRegistered,
+    MaxAttemptsReached,
+    NotConfirmed,
+}
+
+/// Resultado de un intento de remediación
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RemediationOutcome {
+    Skipped(SkipReason),
+    /// Dry-run: la acción se habría aplicado
+    Planned,
+    /// Aplicada, sin verificación disponible
+    Applied,
+    /// Guardada en el perfil; surte efecto al reiniciar la salida, así que no se verifica
+    Deferred,
+    /// Aplicada y la anomalía desapareció
+    Resolved,
+    /// Aplicada pero la anomalía sigue presente
+    StillPresent,
+    Failed(String),
+}
+
+/// Reporte de remediación de una anomalía
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RemediationReport {
+    pub anomaly_type: AnomalyType,
+    pub action: Option<RemediationAction>,
+    pub outcome: RemediationOutcome,
+    pub attempt: u32,
+    /// Cambios aplicados (o planificados en dry-run)
+    pub changes: Vec<String>,
+}
+
+/// Cada cuánto se consulta el replay buffer mientras se detiene
+const REPLAY_BUFFER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Máximo que se espera a que OBS termine de detener el replay buffer
+const REPLAY_BUFFER_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Motor que aplica acciones sobre OBS para anomalías `auto_fixable`
+pub struct RemediationEngine {
+    obs_port: Arc<dyn OBSPort>,
+    registry: RemediationRegistry,
+    policy: RemediationPolicy,
+    confirmation_handler: Option<Arc<dyn ConfirmationHandler>>,
+    verifier: Option<(Arc<dyn ContextProvider>, Arc<AnomalyDetector>)>,
+    /// Intentos por tipo de anomalía y momento del último
+    attempts: Mutex<HashMap<AnomalyType, (u32, Instant)>>,
+}
+
+impl RemediationEngine {
+    pub fn new(obs_port: Arc<dyn OBSPort>) -> Self {
+        Self {
+            obs_port,
+            registry: RemediationRegistry::with_default_actions(),
+            policy: RemediationPolicy::default(),
+            confirmation_handler: None,
+            verifier: None,
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_registry(mut self, registry: RemediationRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: RemediationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_confirmation_handler(mut self, handler: Arc<dyn ConfirmationHandler>) -> Self {
+        self.confirmation_handler = Some(handler);
+        self
+    }
+
+    /// Verifica tras cada acción que la anomalía ya no aparece
+    pub fn with_verifier(
+        mut self,
+        provider: Arc<dyn ContextProvider>,
+        detector: Arc<AnomalyDetector>,
+    ) -> Self {
+        self.verifier = Some((provider, detector));
+        self
+    }
+
+    /// Intentos recientes para un tipo de anomalía (dentro de `attempt_window`)
+    pub fn attempts(&self, anomaly_type: AnomalyType) -> u32 {
+        self.attempts
+            .lock()
+            .unwrap()
+            .get(&anomaly_type)
+            .filter(|(_, last)| last.elapsed() < self.policy.attempt_window)
+            .map_or(0, |(count, _)| *count)
+    }
+
+    fn record_attempt(&self, anomaly_type: AnomalyType) -> u32 {
+        let now = Instant::now();
+        let mut attempts = self.attempts.lock().unwrap();
+        let (count, last) = attempts.entry(anomaly_type).or_insert((0, now));
+        if now.duration_since(*last) >= self.policy.attempt_window {
+            *count = 0;
+        }
+        *count += 1;
+        *last = now;
+        *count
+    }
+
+    fn needs_confirmation(&self, anomaly: &Anomaly, action: &RemediationAction) -> bool {
+        match self.policy.confirmation {
+            ConfirmationPolicy::Never => false,
+            ConfirmationPolicy::Disruptive => {
+                action.is_disruptive() || anomaly.severity == Severity::Critical
+            }
+            ConfirmationPolicy::Always => true,
+        }
+    }
+
+    /// Intenta remediar una anomalía
+    pub async fn remediate(&self, anomaly: &Anomaly) -> RemediationReport {
+        let mut report = RemediationReport {
+            anomaly_type: anomaly.anomaly_type,
+            action: None,
+            outcome: RemediationOutcome::Skipped(SkipReason::NotAutoFixable),
+            attempt: self.attempts(anomaly.anomaly_type),
+            changes: Vec::new(),
+        };
+
+        if !anomaly.auto_fixable {
+            return report;
+        }
+
+        let Some(action) = self.registry.action_for(anomaly.anomaly_type).cloned() else {
+            report.outcome = RemediationOutcome::Skipped(SkipReason::NoActionRegistered);
+            return report;
+        };
+        report.action = Some(action.clone());
+
+        if report.attempt >= self.policy.max_attempts {
+            warn!(
+                "Max remediation attempts ({}) reached for {:?}",
+                self.policy.max_attempts, anomaly.anomaly_type
+            );
+            report.outcome = RemediationOutcome::Skipped(SkipReason::MaxAttemptsReached);
+            return report;
+        }
+
+        if self.needs_confirmation(anomaly, &action) {
+            let confirmed = self
+                .confirmation_handler
+                .as_ref()
+                .is_some_and(|handler| handler.confirm(anomaly, &action));
+            if !confirmed {
+                report.outcome = RemediationOutcome::Skipped(SkipReason::NotConfirmed);
+                return report;
+            }
+        }
+
+        if self.policy.dry_run {
+            info!("[dry-run] {:?}: {}", anomaly.anomaly_type, action.describe());
+            report.changes.push(action.describe());
+            report.outcome = RemediationOutcome::Planned;
+            return report;
+        }
+
+        report.attempt = self.record_attempt(anomaly.anomaly_type);
+
+        info!(
+            "Remediating {:?} (attempt {}/{}): {}",
+            anomaly.anomaly_type,
+            report.attempt,
+            self.policy.max_attempts,
+            action.describe()
+        );
+
+        match self.apply(anomaly, &action).await {
+            Ok(changes) => report.changes = changes,
+            Err(e) => {
+                warn!("Remediation of {:?} failed: {}", anomaly.anomaly_type, e);
+                report.outcome = RemediationOutcome::Failed(e.to_string());
+                return report;
+            }
+        }
+
+        if action.takes_effect_on_restart() {
+            report.outcome = RemediationOutcome::Deferred;
+            return report;
+        }
+
+        report.outcome = match self.verify(anomaly).await {
+            Ok(Some(true)) => {
+                // Resuelta: un nuevo episodio vuelve a tener todos los intentos
+                self.attempts.lock().unwrap().remove(&anomaly.anomaly_type);
+                RemediationOutcome::Resolved
+            }
+            Ok(Some(false)) => RemediationOutcome::StillPresent,
+            Ok(None) => RemediationOutcome::Applied,
+            Err(e) => {
+                warn!("Could not verify remediation of {:?}: {}", anomaly.anomaly_type, e);
+                RemediationOutcome::Applied
+            }
+        };
+
+        report
+    }
+
+    /// Remedia una lista de anomalías en orden
+    pub async fn remediate_all(&self, anomalies: &[Anomaly]) -> Vec<RemediationReport> {
+        let mut reports = Vec::with_capacity(anomalies.len());
+        for anomaly in anomalies {
+            reports.push(self.remediate(anomaly).await);
+        }
+        reports
+    }
+
+    async fn apply(&self, anomaly: &Anomaly, action: &RemediationAction) -> Result<Vec<String>> {
+        match action {
+            RemediationAction::LowerBitrate { factor, min_kbps } => {
+                let mut settings = self.obs_port.get_encoder_settings().await?;
+                let previous = settings.bitrate_kbps;
+                let target = ((previous as f64 * factor) as u32).max(*min_kbps);
+                if target >= previous {
+                    anyhow::bail!("Bitrate already at minimum ({} kbps)", previous);
+                }
+                settings.bitrate_kbps = target;
+                self.obs_port.set_encoder_settings(&settings).await?;
+                Ok(vec![format!("Bitrate {} -> {} kbps (from the next stream start)", previous, target)])
+            }
+            RemediationAction::FasterPreset => {
+                let mut settings = self.obs_port.get_encoder_settings().await?;
+                let previous = settings.preset.clone();
+                settings.preset = settings
+                    .faster_preset()
+                    .with_context(|| format!("No faster preset than '{}'", previous))?;
+                self.obs_port.set_encoder_settings(&settings).await?;
+                Ok(vec![format!("Preset {} -> {} (from the next stream start)", previous, settings.preset)])
+            }
+            RemediationAction::HideMissingSources => {
+                if anomaly.sources.is_empty() {
+                    anyhow::bail!("Anomaly does not name any source");
+                }
+                let mut changes = Vec::new();
+                for source in &anomaly.sources {
+                    self.obs_port.set_source_visible(&source.scene, &source.source, false).await?;
+                    changes.push(format!("Hid '{}' in scene '{}'", source.source, source.scene));
+                }
+                Ok(changes)
+            }
+            RemediationAction::SwitchScene { scene } => {
+                let previous = self.obs_port.get_current_scene().await?;
+                self.obs_port.set_current_scene(scene).await?;
+                Ok(vec![format!("Scene '{}' -> '{}'", previous, scene)])
+            }
+            RemediationAction::RestartReplayBuffer => {
+                if !self.obs_port.get_replay_buffer_active().await? {
+                    anyhow::bail!("Replay buffer is not running");
+                }
+                self.obs_port.stop_replay_buffer().await?;
+                self.wait_replay_buffer_stopped().await?;
+                self.obs_port.start_replay_buffer().await?;
+                Ok(vec!["Replay buffer restarted".to_string()])
+            }
+        }
+    }
+
+    /// OBS detiene el replay buffer en segundo plano; arrancarlo antes de que termine falla
+    async fn wait_replay_buffer_stopped(&self) -> Result<()> {
+        let deadline = Instant::now() + REPLAY_BUFFER_STOP_TIMEOUT;
+        while self.obs_port.get_replay_buffer_active().await? {
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Replay buffer did not stop within {}s",
+                    REPLAY_BUFFER_STOP_TIMEOUT.as_secs()
+                );
+            }
+            tokio::time::sleep(REPLAY_BUFFER_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    /// `Some(true)` si la anomalía desapareció, `None` si no hay verificador
+    async fn verify(&self, anomaly: &Anomaly) -> Result<Option<bool>> {
+        let Some((provider, detector)) = &self.verifier else {
+            return Ok(None);
+        };
+
+        tokio::time::sleep(self.policy.verify_delay).await;
+
+        let context = provider.collect().await?;
+        let still_present = detector
+            .scan(&context)
+            .iter()
+            .any(|a| a.anomaly_type == anomaly.anomaly_type);
+
+        debug!(
+            "Verification for {:?}: {}",
+            anomaly.anomaly_type,
+            if still_present { "still present" } else { "cleared" }
+        );
+        Ok(Some(!still_present))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::ports::*;
+    use crate::domain::models::*;
+    use crate::domain::services::anomaly_detector::{DroppedFramesRule, SystemContext};
+    use async_trait::async_trait;
+
+    #[derive(Default)]
+    struct RecordingOBSPort {
+        encoder: Mutex<Option<EncoderSettings>>,
+        calls: Mutex<Vec<String>>,
+        /// Replay buffer activo y consultas que aún lo verán activo tras pedir que pare
+        replay: Mutex<(bool, u32)>,
+    }
+
+    impl RecordingOBSPort {
+        fn with_encoder(preset: &str, bitrate_kbps: u32) -> Self {
+            let port = Self::default();
+            *port.encoder.lock().unwrap() = Some(EncoderSettings {
+                encoder: "x264".to_string(),
+                preset: preset.to_string(),
+                bitrate_kbps,
+            });
+            port
+        }
+
+        fn with_replay_buffer(stop_polls: u32) -> Self {
+            let port = Self::default();
+            *port.replay.lock().unwrap() = (true, stop_polls);
+            port
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[async_trait]
+    impl OBSPort for RecordingOBSPort {
+        async fn connect(&self) -> Result<()> { Ok(()) }
+        async fn disconnect(&self) -> Result<()> { Ok(()) }
+        async fn get_scenes(&self) -> Result<Vec<Scene>> { Ok(vec![]) }
+        async fn get_current_scene(&self) -> Result<String> { Ok("Live".to_string()) }
+        async fn validate_scene(&self, _: &str) -> Result<ValidationReport> {
+            anyhow::bail!("unused")
+        }
+        async fn get_stats(&self) -> Result<OBSStats> { anyhow::bail!("unused") }
+        async fn get_video_settings(&self) -> Result<VideoSettings> { anyhow::bail!("unused") }
+        async fn set_video_settings(&self, _: &VideoSettings) -> Result<()> { Ok(()) }
+        async fn take_screenshot(&self, _: &str) -> Result<Vec<u8>> { Ok(vec![]) }
+        async fn set_current_scene(&self, scene_name: &str) -> Result<()> {
+            self.record(format!("scene:{}", scene_name));
+            Ok(())
+        }
+        async fn set_source_visible(&self, scene_name: &str, source_name: &str, visible: bool) -> Result<()> {
+            self.record(format!("visible:{}:{}:{}", scene_name, source_name, visible));
+            Ok(())
+        }
+        async fn get_encoder_settings(&self) -> Result<EncoderSettings> {
+            self.encoder.lock().unwrap().clone().context("no encoder")
+        }
+        async fn set_encoder_settings(&self, settings: &EncoderSettings) -> Result<()> {
+            self.record(format!("encoder:{}:{}", settings.preset, settings.bitrate_kbps));
+            *self.encoder.lock().unwrap() = Some(settings.clone());
+            Ok(())
+        }
+        async fn get_replay_buffer_active(&self) -> Result<bool> {
+            let mut replay = self.replay.lock().unwrap();
+            if replay.1 > 0 && self.calls().last().is_some_and(|c| c == "replay:stop") {
+                replay.1 -= 1;
+                return Ok(true);
+            }
+            Ok(replay.0)
+        }
+        async fn start_replay_buffer(&self) -> Result<()> {
+            anyhow::ensure!(!self.get_replay_buffer_active().await?, "still stopping");
+            self.record("replay:start".to_string());
+            self.replay.lock().unwrap().0 = true;
+            Ok(())
+        }
+        async fn stop_replay_buffer(&self) -> Result<()> {
+            self.record("replay:stop".to_string());
+            self.replay.lock().unwrap().0 = false;
+            Ok(())
+        }
+        async fn get_recording_settings(&self) -> Result<RecordingSettings> { anyhow::bail!("unused") }
+        async fn get_stream_settings(&self) -> Result<StreamSettings> { anyhow::bail!("unused") }
+    }
+
+    struct FixedContext(f64);
+
+    #[async_trait]
+    impl ContextProvider for FixedContext {
+        async fn collect(&self) -> Result<SystemContext> {
+            Ok(SystemContext {
+                cpu_temp: 50.0,
+                gpu_temp: 60.0,
+                cpu_usage: 30.0,
+                cpu_frequency: None,
+                memory_used_percent: 50.0,
+                disk_free_gb: 100.0,
+                obs_dropped_frames_percent: self.0,
+                obs_cpu_usage: 10.0,
+                missing_sources: vec![],
+                audio_peak_db: None,
+                network_bitrate: None,
+                recording_minutes_left: None,
+                obs_process: None,
+                network: None,
+                power: None,
+                timestamp: chrono::Utc::now(),
+            })
+        }
+    }
+
+    struct Answer(bool);
+
+    impl ConfirmationHandler for Answer {
+        fn confirm(&self, _: &Anomaly, _: &RemediationAction) -> bool {
+            self.0
+        }
+    }
+
+    fn dropped_frames() -> Anomaly {
+        Anomaly::new(AnomalyType::DroppedFrames, Severity::Warning, "Dropping 5.0% of frames")
+            .auto_fixable(true)
+    }
+
+    fn policy() -> RemediationPolicy {
+        RemediationPolicy {
+            verify_delay: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    fn verifier(dropped_percent: f64) -> (Arc<dyn ContextProvider>, Arc<AnomalyDetector>) {
+        let detector = AnomalyDetector::new().with_rule(Arc::new(DroppedFramesRule {
+            threshold_percent: 1.0,
+        }));
+        (Arc::new(FixedContext(dropped_percent)), Arc::new(detector))
+    }
+
+    /// Cambia a "BRB" ante frames perdidos (acción inmediata y verificable)
+    fn switch_on_dropped_frames() -> RemediationRegistry {
+        RemediationRegistry::new().with_action(
+            AnomalyType::DroppedFrames,
+            RemediationAction::SwitchScene { scene: "BRB".to_string() },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_lower_bitrate_is_deferred() {
+        let obs = Arc::new(RecordingOBSPort::with_encoder("veryfast", 6000));
+        let (provider, detector) = verifier(5.0);
+        let engine = RemediationEngine::new(obs.clone())
+            .with_policy(policy())
+            .with_verifier(provider, detector);
+
+        let report = engine.remediate(&dropped_frames()).await;
+
+        // El stream en curso sigue con el bitrate anterior: no se verifica
+        assert_eq!(report.outcome, RemediationOutcome::Deferred);
+        assert_eq!(report.changes, vec!["Bitrate 6000 -> 4800 kbps (from the next stream start)"]);
+        assert_eq!(obs.calls(), vec!["encoder:veryfast:4800"]);
+        assert_eq!(engine.attempts(AnomalyType::DroppedFrames), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resolved_resets_attempts() {
+        let obs = Arc::new(RecordingOBSPort::default());
+        let (provider, detector) = verifier(0.0);
+        let engine = RemediationEngine::new(obs.clone())
+            .with_registry(switch_on_dropped_frames())
+            .with_policy(RemediationPolicy {
+                confirmation: ConfirmationPolicy::Never,
+                ..policy()
+            })
+            .with_verifier(provider, detector);
+
+        let report = engine.remediate(&dropped_frames()).await;
+
+        assert_eq!(report.outcome, RemediationOutcome::Resolved);
+        assert_eq!(report.attempt, 1);
+        assert_eq!(engine.attempts(AnomalyType::DroppedFrames), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_touch_obs() {
+        let obs = Arc::new(RecordingOBSPort::with_encoder("veryfast", 6000));
+        let engine = RemediationEngine::new(obs.clone()).with_policy(RemediationPolicy {
+            dry_run: true,
+            ..policy()
+        });
+
+        let report = engine.remediate(&dropped_frames()).await;
+
+        assert_eq!(report.outcome, RemediationOutcome::Planned);
+        assert!(obs.calls().is_empty());
+        assert_eq!(engine.attempts(AnomalyType::DroppedFrames), 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_attempts_and_still_present() {
+        let obs = Arc::new(RecordingOBSPort::default());
+        let (provider, detector) = verifier(5.0);
+        let engine = RemediationEngine::new(obs.clone())
+            .with_registry(switch_on_dropped_frames())
+            .with_policy(RemediationPolicy {
+                max_attempts: 2,
+                confirmation: ConfirmationPolicy::Never,
+                ..policy()
+            })
+            .with_verifier(provider, detector);
+
+        let anomaly = dropped_frames();
+        assert_eq!(engine.remediate(&anomaly).await.outcome, RemediationOutcome::StillPresent);
+        assert_eq!(engine.remediate(&anomaly).await.outcome, RemediationOutcome::StillPresent);
+        assert_eq!(
+            engine.remediate(&anomaly).await.outcome,
+            RemediationOutcome::Skipped(SkipReason::MaxAttemptsReached)
+        );
+        assert_eq!(obs.calls(), vec!["scene:BRB", "scene:BRB"]);
+    }
+
+    #[tokio::test]
+    async fn test_attempts_expire_after_window() {
+        let obs = Arc::new(RecordingOBSPort::with_encoder("veryfast", 6000));
+        let engine = RemediationEngine::new(obs.clone()).with_policy(RemediationPolicy {
+            max_attempts: 1,
+            attempt_window: Duration::ZERO,
+            ..policy()
+        });
+
+        assert_eq!(engine.remediate(&dropped_frames()).await.outcome, RemediationOutcome::Deferred);
+        assert_eq!(engine.remediate(&dropped_frames()).await.outcome, RemediationOutcome::Deferred);
+        assert_eq!(obs.calls(), vec!["encoder:veryfast:4800", "encoder:veryfast:3840"]);
+    }
+
+    #[tokio::test]
+    async fn test_disruptive_action_requires_confirmation() {
+        let obs = Arc::new(RecordingOBSPort::default());
+        let anomaly = Anomaly::new(AnomalyType::MissingSource, Severity::Critical, "Missing 2 source(s)")
+            .with_sources(vec![SourceRef::new("Live", "webcam"), SourceRef::new("Live", "mic: USB")])
+            .auto_fixable(true);
+
+        let engine = RemediationEngine::new(obs.clone()).with_policy(policy());
+        assert_eq!(
+            engine.remediate(&anomaly).await.outcome,
+            RemediationOutcome::Skipped(SkipReason::NotConfirmed)
+        );
+
+        let engine = engine.with_confirmation_handler(Arc::new(Answer(true)));
+        let report = engine.remediate(&anomaly).await;
+        assert_eq!(report.outcome, RemediationOutcome::Applied);
+        assert_eq!(
+            obs.calls(),
+            vec!["visible:Live:webcam:false", "visible:Live:mic: USB:false"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skips_non_fixable_and_unregistered() {
+        let obs = Arc::new(RecordingOBSPort::default());
+        let engine = RemediationEngine::new(obs.clone()).with_policy(policy());
+
+        let report = engine
+            .remediate(&Anomaly::new(AnomalyType::DiskSpaceLow, Severity::Critical, "full"))
+            .await;
+        assert_eq!(report.outcome, RemediationOutcome::Skipped(SkipReason::NotAutoFixable));
+
+        let report = engine
+            .remediate(&Anomaly::new(AnomalyType::AudioClipping, Severity::Warning, "clip").auto_fixable(true))
+            .await;
+        assert_eq!(report.outcome, RemediationOutcome::Skipped(SkipReason::NoActionRegistered));
+        assert!(obs.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_scene_and_replay_buffer() {
+        let obs = Arc::new(RecordingOBSPort::with_replay_buffer(2));
+        let engine = RemediationEngine::new(obs.clone())
+            .with_registry(RemediationRegistry::with_default_actions().with_fallback_scene("BRB"))
+            .with_policy(RemediationPolicy {
+                confirmation: ConfirmationPolicy::Never,
+                ..policy()
+            });
+
+        engine
+            .remediate(&Anomaly::new(AnomalyType::PluginCrash, Severity::Critical, "crash").auto_fixable(true))
+            .await;
+        let report = engine
+            .remediate(&Anomaly::new(AnomalyType::MemoryLeak, Severity::Warning, "leak").auto_fixable(true))
+            .await;
+
+        // Espera a que OBS termine de pararlo antes de volver a arrancarlo
+        assert_eq!(report.outcome, RemediationOutcome::Applied);
+        assert_eq!(obs.calls(), vec!["scene:BRB", "replay:stop", "replay:start"]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_buffer_not_running() {
+        let obs = Arc::new(RecordingOBSPort::default());
+        let engine = RemediationEngine::new(obs.clone()).with_policy(policy());
+
+        let report = engine
+            .remediate(&Anomaly::new(AnomalyType::MemoryLeak, Severity::Warning, "leak").auto_fixable(true))
+            .await;
+
+        assert_eq!(report.outcome, RemediationOutcome::Failed("Replay buffer is not running".to_string()));
+        assert!(obs.calls().is_empty());
+    }
+}
@@ -0,0 +1,326 @@
+use crate::domain::models::{AnomalyType, Severity};
+use crate::domain::services::anomaly_detector::{AnomalyDetector, RuleConfig, SystemContext};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tracing::debug;
+
+/// Anomalía esperada en un paso del escenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedAnomaly {
+    #[serde(rename = "type")]
+    pub anomaly_type: AnomalyType,
+    /// Si se omite, cualquier severidad es válida
+    #[serde(default)]
+    pub severity: Option<Severity>,
+}
+
+impl ExpectedAnomaly {
+    fn matches(&self, anomaly_type: AnomalyType, severity: Severity) -> bool {
+        self.anomaly_type == anomaly_type && (self.severity.is_none() || self.severity == Some(severity))
+    }
+}
+
+/// Paso del escenario: contexto en un instante virtual y anomalías esperadas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioStep {
+    /// Segundos desde el inicio del escenario
+    pub at: f64,
+    /// Campos del `SystemContext` que cambian respecto al paso anterior
+    #[serde(default)]
+    pub context: Map<String, Value>,
+    /// Anomalías que deben detectarse exactamente en este paso
+    #[serde(default)]
+    pub expect: Vec<ExpectedAnomaly>,
+}
+
+/// Secuencia temporal de contextos para probar reglas offline
+///
+/// Las claves desconocidas se rechazan: una errata no debe cambiar en
+/// silencio lo que prueba el escenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Umbrales de las reglas (por defecto los de `AnomalyDetector::with_default_rules`)
+    #[serde(default)]
+    pub rules: RuleConfig,
+    pub steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Invalid TOML scenario")
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        serde_json::from_str(content).context("Invalid JSON scenario")
+    }
+
+    /// Carga un escenario; el formato se elige por extensión (.json o .toml)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario {}", path.display()))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_toml(&content),
+        }
+    }
+}
+
+/// Reloj virtual que avanza según los pasos del escenario
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualClock {
+    start: DateTime<Utc>,
+    elapsed_secs: f64,
+}
+
+impl VirtualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            elapsed_secs: 0.0,
+        }
+    }
+
+    /// Avanza hasta `at` segundos desde el inicio (nunca hacia atrás)
+    pub fn advance_to(&mut self, at: f64) -> Result<()> {
+        if at < self.elapsed_secs {
+            anyhow::bail!(
+                "Scenario steps must be in chronological order ({}s after {}s)",
+                at,
+                self.elapsed_secs
+            );
+        }
+        self.elapsed_secs = at;
+        Ok(())
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.start + Duration::milliseconds((self.elapsed_secs * 1000.0) as i64)
+    }
+}
+
+/// Resultado de un paso del escenario
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub index: usize,
+    pub at: f64,
+    pub detected: Vec<(AnomalyType, Severity)>,
+    pub missing: Vec<ExpectedAnomaly>,
+    pub unexpected: Vec<(AnomalyType, Severity)>,
+}
+
+impl StepResult {
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Resultado completo de un escenario
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub steps: Vec<StepResult>,
+}
+
+impl ScenarioReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(StepResult::passed)
+    }
+
+    pub fn failed_steps(&self) -> usize {
+        self.steps.iter().filter(|s| !s.passed()).count()
+    }
+}
+
+/// Ejecuta escenarios contra un `AnomalyDetector`
+pub struct ScenarioRunner {
+    detector: AnomalyDetector,
+    start: DateTime<Utc>,
+}
+
+impl ScenarioRunner {
+    pub fn new(detector: AnomalyDetector) -> Self {
+        Self {
+            detector,
+            start: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    /// Runner con las reglas definidas por el propio escenario
+    pub fn for_scenario(scenario: &Scenario) -> Self {
+        Self::new(AnomalyDetector::from_config(&scenario.rules))
+    }
+
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn run(&self, scenario: &Scenario) -> Result<ScenarioReport> {
+        let mut clock = VirtualClock::new(self.start);
+        let mut context = serde_json::to_value(SystemContext::default())?;
+        let mut steps = Vec::with_capacity(scenario.steps.len());
+
+        for (index, step) in scenario.steps.iter().enumerate() {
+            clock.advance_to(step.at)?;
+
+            // Cada paso hereda el contexto del anterior
+            let fields = context.as_object_mut().context("Context is not an object")?;
+            for (key, value) in &step.context {
+                if !fields.contains_key(key) {
+                    anyhow::bail!("Step {}: unknown context field '{}'", index, key);
+                }
+                fields.insert(key.clone(), value.clone());
+            }
+
+            let mut sample: SystemContext = serde_json::from_value(context.clone())
+                .with_context(|| format!("Step {}: invalid context", index))?;
+            sample.timestamp = clock.now();
+
+            let detected: Vec<(AnomalyType, Severity)> = self
+                .detector
+                .scan(&sample)
+                .iter()
+                .map(|a| (a.anomaly_type, a.severity))
+                .collect();
+
+            let mut unexpected = detected.clone();
+            let mut missing = Vec::new();
+            for expected in &step.expect {
+                match unexpected
+                    .iter()
+                    .position(|(t, s)| expected.matches(*t, *s))
+                {
+                    Some(pos) => {
+                        unexpected.remove(pos);
+                    }
+                    None => missing.push(expected.clone()),
+                }
+            }
+
+            debug!(
+                "Scenario '{}' step {} at {}s: {} detected",
+                scenario.name,
+                index,
+                step.at,
+                detected.len()
+            );
+
+            steps.push(StepResult {
+                index,
+                at: step.at,
+                detected,
+                missing,
+                unexpected,
+            });
+        }
+
+        Ok(ScenarioReport {
+            name: scenario.name.clone(),
+            steps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THERMAL_RAMP: &str = include_str!("../../../scenarios/thermal_ramp.toml");
+
+    #[test]
+    fn test_bundled_scenario_passes() {
+        let scenario = Scenario::from_toml(THERMAL_RAMP).unwrap();
+        let report = ScenarioRunner::for_scenario(&scenario).run(&scenario).unwrap();
+
+        assert_eq!(report.steps.len(), scenario.steps.len());
+        assert!(report.passed(), "{:#?}", report);
+    }
+
+    #[test]
+    fn test_failing_expectations_are_reported() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "name": "wrong expectations",
+                "steps": [
+                    { "at": 0, "context": { "cpu_temp": 80.0 }, "expect": [] },
+                    { "at": 10, "context": { "cpu_temp": 50.0 },
+                      "expect": [{ "type": "HighCPUTemp", "severity": "Critical" }] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = ScenarioRunner::for_scenario(&scenario).run(&scenario).unwrap();
+
+        assert!(!report.passed());
+        assert_eq!(report.failed_steps(), 2);
+        assert_eq!(report.steps[0].unexpected, vec![(AnomalyType::HighCPUTemp, Severity::Warning)]);
+        assert_eq!(report.steps[1].missing.len(), 1);
+    }
+
+    #[test]
+    fn test_custom_rule_config() {
+        let scenario = Scenario::from_toml(
+            r#"
+            name = "strict disk"
+            [rules]
+            disk_free_gb = 50.0
+
+            [[steps]]
+            at = 0
+            context = { disk_free_gb = 40.0 }
+            expect = [{ type = "DiskSpaceLow" }]
+            "#,
+        )
+        .unwrap();
+
+        let report = ScenarioRunner::for_scenario(&scenario).run(&scenario).unwrap();
+        assert!(report.passed(), "{:#?}", report);
+    }
+
+    #[test]
+    fn test_rejects_bad_steps() {
+        let out_of_order = Scenario::from_toml(
+            "name = 'x'\n[[steps]]\nat = 10\n[[steps]]\nat = 5\n",
+        )
+        .unwrap();
+        assert!(ScenarioRunner::for_scenario(&out_of_order).run(&out_of_order).is_err());
+
+        let unknown_field = Scenario::from_toml(
+            "name = 'x'\n[[steps]]\nat = 0\ncontext = { cpu_tmp = 90.0 }\n",
+        )
+        .unwrap();
+        assert!(ScenarioRunner::for_scenario(&unknown_field).run(&unknown_field).is_err());
+    }
+
+    #[test]
+    fn test_rejects_typoed_keys() {
+        let step_key = Scenario::from_toml("name = 'x'\n[[steps]]\nat = 0\nexpects = [{ type = 'HighCPUTemp' }]\n");
+        assert!(step_key.is_err());
+
+        let rule_key = Scenario::from_toml("name = 'x'\n[rules]\ncpu_temp_critcal = 70.0\n[[steps]]\nat = 0\n");
+        assert!(format!("{:#}", rule_key.unwrap_err()).contains("cpu_temp_critcal"));
+
+        let expectation_key = r#"{"name":"x","steps":[{"at":0,"expect":[{"typ":"DiskSpaceLow"}]}]}"#;
+        assert!(Scenario::from_json(expectation_key).is_err());
+    }
+
+    #[test]
+    fn test_virtual_clock() {
+        let start = Utc.timestamp_opt(1_000, 0).unwrap();
+        let mut clock = VirtualClock::new(start);
+        clock.advance_to(1.5).unwrap();
+        assert_eq!(clock.now(), start + Duration::milliseconds(1500));
+        assert!(clock.advance_to(1.0).is_err());
+    }
+}
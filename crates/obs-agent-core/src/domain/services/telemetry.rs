@@ -0,0 +1,259 @@
+use crate::domain::models::{AnomalyType, Metric, MetricSample, OBSStats, OutputStatus, Severity};
+use crate::domain::services::anomaly_detector::{AnomalyDetector, SystemContext};
+use crate::domain::services::health_check::HealthReport;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Muestra de telemetría grabada durante el monitoreo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TelemetryRecord {
+    pub timestamp: DateTime<Utc>,
+    pub context: SystemContext,
+    #[serde(default)]
+    pub obs_stats: Option<OBSStats>,
+    #[serde(default)]
+    pub ram_available_gb: Option<f64>,
+    /// Escena de programa en el momento de la muestra
+    #[serde(default)]
+    pub scene: Option<String>,
+    #[serde(default)]
+    pub outputs: Vec<OutputStatus>,
+}
+
+impl TelemetryRecord {
+    /// Registro sin datos de OBS (p.ej. monitoreo solo de hardware)
+    pub fn from_context(context: SystemContext) -> Self {
+        Self {
+            timestamp: context.timestamp,
+            context,
+            obs_stats: None,
+            ram_available_gb: None,
+            scene: None,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Valores numéricos de la muestra: métricas del sistema, OBS y cada salida
+    /// (etiquetada con `output`)
+    pub fn metric_samples(&self) -> Vec<MetricSample> {
+        let mut samples: Vec<MetricSample> = Metric::values(&self.context)
+            .into_iter()
+            .map(|(metric, value)| MetricSample::new(metric.name(), value))
+            .collect();
+
+        if let Some(stats) = &self.obs_stats {
+            samples.extend([
+                MetricSample::new("obs_active_fps", stats.active_fps),
+                MetricSample::new("obs_render_frames", stats.render_total_frames as f64),
+                MetricSample::new("obs_render_skipped_frames", stats.render_skipped_frames as f64),
+                MetricSample::new("obs_encoding_frames", stats.output_total_frames as f64),
+                MetricSample::new("obs_encoding_skipped_frames", stats.output_skipped_frames as f64),
+            ]);
+        }
+
+        for output in &self.outputs {
+            let sample = |name: &str, value: f64| MetricSample::new(name, value).with_tag("output", &output.name);
+            samples.extend([
+                sample("obs_output_active", output.active as u8 as f64),
+                sample("obs_output_reconnecting", output.reconnecting as u8 as f64),
+                sample("obs_output_bytes", output.bytes as f64),
+                sample("obs_output_frames", output.total_frames as f64),
+                sample("obs_output_dropped_frames", output.skipped_frames as f64),
+            ]);
+            if let Some(congestion) = output.congestion {
+                samples.push(sample("obs_output_congestion_ratio", congestion));
+            }
+        }
+
+        samples
+    }
+}
+
+/// Velocidad de reproducción de una grabación
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Respeta los intervalos originales entre muestras
+    RealTime,
+    /// Divide los intervalos originales por el factor
+    Accelerated(f64),
+    /// Sin esperas entre muestras
+    Unlimited,
+}
+
+impl ReplaySpeed {
+    fn delay(&self, gap: chrono::Duration) -> Option<Duration> {
+        let gap = gap.to_std().ok()?;
+        match self {
+            Self::RealTime => Some(gap),
+            Self::Accelerated(factor) if *factor > 0.0 => Some(gap.div_f64(*factor)),
+            _ => None,
+        }
+    }
+}
+
+/// Resultado de evaluar una muestra durante la reproducción
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    pub index: usize,
+    pub record: TelemetryRecord,
+    pub report: HealthReport,
+    /// Tipos de anomalía que aparecieron respecto a la muestra anterior
+    pub raised: Vec<AnomalyType>,
+    /// Tipos de anomalía que desaparecieron respecto a la muestra anterior
+    pub cleared: Vec<AnomalyType>,
+}
+
+/// Resumen de una reproducción completa
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplaySummary {
+    pub samples: usize,
+    pub unhealthy_samples: usize,
+    pub anomalies_by_type: BTreeMap<AnomalyType, usize>,
+    pub first_critical: Option<DateTime<Utc>>,
+}
+
+/// Reproduce grabaciones a través del `AnomalyDetector` y `HealthReport`
+pub struct TelemetryReplayer {
+    detector: AnomalyDetector,
+    speed: ReplaySpeed,
+}
+
+impl TelemetryReplayer {
+    pub fn new(detector: AnomalyDetector) -> Self {
+        Self {
+            detector,
+            speed: ReplaySpeed::Unlimited,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Reproduce las muestras en orden, invocando `on_step` para cada una
+    pub async fn replay<F>(&self, records: &[TelemetryRecord], mut on_step: F) -> ReplaySummary
+    where
+        F: FnMut(&ReplayStep),
+    {
+        info!("Replaying {} telemetry samples ({:?})", records.len(), self.speed);
+
+        let mut summary = ReplaySummary::default();
+        let mut previous: HashSet<AnomalyType> = HashSet::new();
+        let mut last_timestamp: Option<DateTime<Utc>> = None;
+
+        for (index, record) in records.iter().enumerate() {
+            if let Some(last) = last_timestamp {
+                if let Some(delay) = self.speed.delay(record.timestamp - last) {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            last_timestamp = Some(record.timestamp);
+
+            let mut context = record.context.clone();
+            context.timestamp = record.timestamp;
+
+            let anomalies = self.detector.scan(&context);
+            let current: HashSet<AnomalyType> = anomalies.iter().map(|a| a.anomaly_type).collect();
+
+            let mut raised: Vec<AnomalyType> = current.difference(&previous).copied().collect();
+            let mut cleared: Vec<AnomalyType> = previous.difference(&current).copied().collect();
+            raised.sort();
+            cleared.sort();
+
+            for anomaly in &anomalies {
+                *summary.anomalies_by_type.entry(anomaly.anomaly_type).or_insert(0) += 1;
+                if anomaly.severity == Severity::Critical && summary.first_critical.is_none() {
+                    summary.first_critical = Some(record.timestamp);
+                }
+            }
+
+            // Sin dato de RAM asumimos que grabar era posible
+            let report = HealthReport::from_anomalies(
+                anomalies,
+                record.ram_available_gb.unwrap_or(f64::MAX),
+                record.timestamp,
+            );
+            if !report.is_healthy {
+                summary.unhealthy_samples += 1;
+            }
+            summary.samples += 1;
+
+            debug!("Replay sample {}: {} raised, {} cleared", index, raised.len(), cleared.len());
+
+            on_step(&ReplayStep {
+                index,
+                record: record.clone(),
+                report,
+                raised,
+                cleared,
+            });
+
+            previous = current;
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(secs: i64, cpu_temp: f32) -> TelemetryRecord {
+        TelemetryRecord::from_context(SystemContext {
+            cpu_temp,
+            timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_replay_transitions_and_summary() {
+        let records = vec![record(0, 60.0), record(5, 80.0), record(10, 90.0), record(15, 60.0)];
+        let replayer = TelemetryReplayer::new(AnomalyDetector::with_default_rules());
+
+        let mut transitions = Vec::new();
+        let summary = replayer
+            .replay(&records, |step| {
+                transitions.push((step.raised.clone(), step.cleared.clone()));
+            })
+            .await;
+
+        assert_eq!(summary.samples, 4);
+        assert_eq!(summary.unhealthy_samples, 2);
+        assert_eq!(summary.anomalies_by_type.get(&AnomalyType::HighCPUTemp), Some(&2));
+        assert_eq!(summary.first_critical, Some(Utc.timestamp_opt(10, 0).unwrap()));
+        assert_eq!(transitions[1].0, vec![AnomalyType::HighCPUTemp]);
+        assert!(transitions[2].0.is_empty());
+        assert_eq!(transitions[3].1, vec![AnomalyType::HighCPUTemp]);
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_replay_waits_scaled_gaps() {
+        let records = vec![record(0, 50.0), record(1, 50.0)];
+        let replayer = TelemetryReplayer::new(AnomalyDetector::with_default_rules())
+            .with_speed(ReplaySpeed::Accelerated(20.0));
+
+        let start = std::time::Instant::now();
+        replayer.replay(&records, |_| {}).await;
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let line = serde_json::to_string(&record(42, 70.0)).unwrap();
+        let parsed: TelemetryRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.timestamp, Utc.timestamp_opt(42, 0).unwrap());
+        assert_eq!(parsed.context.cpu_temp, 70.0);
+        assert!(parsed.obs_stats.is_none());
+    }
+}
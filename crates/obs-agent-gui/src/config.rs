@@ -39,6 +39,10 @@ pub struct PortableConfig {
 
     /// API endpoint del servidor de suscripciones
     pub subscription_api_url: String,
+
+    /// Servidor para la prueba de ancho de banda (HOST:PORT; vacío = servidor local)
+    #[serde(default)]
+    pub bandwidth_test_endpoint: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -113,6 +117,7 @@ impl Default for PortableConfig {
             subscription_tier: SubscriptionTier::Free,
             credits_available: 100,
             subscription_api_url: "https://api.obsagent.io".to_string(),
+            bandwidth_test_endpoint: None,
         }
     }
 }
@@ -2,8 +2,9 @@ mod config;
 mod ui;
 mod obs_launcher;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use eframe::egui;
+use obs_agent_infra::SimulationScenario;
 
 fn main() -> Result<()> {
     // Setup logging
@@ -11,12 +12,18 @@ fn main() -> Result<()> {
         .with_env_filter("obs_agent_gui=debug,obs_agent_core=info,obs_agent_infra=info")
         .init();
 
-    // Auto-launch OBS si no está corriendo
-    match obs_launcher::ensure_obs_running() {
-        Ok(_) => println!("✅ OBS está listo"),
-        Err(e) => {
-            eprintln!("⚠️  Advertencia: {}", e);
-            eprintln!("💡 Por favor, inicia OBS Studio manualmente");
+    let simulate = simulation_from_args()?;
+
+    // Auto-launch OBS si no está corriendo (innecesario en simulación)
+    if let Some(scenario) = simulate {
+        println!("🧪 Modo simulación: {}", scenario);
+    } else {
+        match obs_launcher::ensure_obs_running() {
+            Ok(_) => println!("✅ OBS está listo"),
+            Err(e) => {
+                eprintln!("⚠️  Advertencia: {}", e);
+                eprintln!("💡 Por favor, inicia OBS Studio manualmente");
+            }
         }
     }
 
@@ -31,6 +38,21 @@ fn main() -> Result<()> {
     eframe::run_native(
         "OBS Agent - AI Streaming Assistant",
         options,
-        Box::new(|cc| Ok(Box::new(ui::OBSAgentApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(ui::OBSAgentApp::new(cc, simulate)))),
     ).map_err(|e| anyhow::anyhow!("Failed to run GUI: {}", e))
 }
+
+/// Lee `--simulate <escenario>` (o `--simulate=<escenario>`) de la línea de comandos
+fn simulation_from_args() -> Result<Option<SimulationScenario>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(name) = arg.strip_prefix("--simulate=") {
+            return Ok(Some(name.parse()?));
+        }
+        if arg == "--simulate" {
+            let name = args.next().context("--simulate requires a scenario name")?;
+            return Ok(Some(name.parse()?));
+        }
+    }
+    Ok(None)
+}
@@ -1,10 +1,16 @@
 use crate::config::{PortableConfig, SubscriptionTier};
 use eframe::egui;
-use obs_agent_core::application::ports::{MonitorPort, OBSPort};
-use obs_agent_core::domain::services::{AnomalyDetector, HealthCheckService, SystemContext};
-use obs_agent_infra::{MonitorAdapter, OBSAdapter};
+use obs_agent_core::application::ports::{MetricsStore, MonitorPort, NetworkPort, OBSPort, TelemetrySink};
+use obs_agent_core::domain::models::{BandwidthTest, Metric, RetentionPolicy, TimeRange};
+use obs_agent_core::domain::services::{AnomalyDetector, HealthCheckService, SystemContext, TelemetryRecord};
+use obs_agent_infra::{
+    run_bandwidth_test, simulated_ports, MonitorAdapter, MonitorSampler, NetworkAdapter, OBSAdapter,
+    SamplerHandle, SimulatedNetworkAdapter, Simulation, SimulationScenario, SqliteMetricsStore,
+};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tab {
@@ -34,11 +40,25 @@ pub struct OBSAgentApp {
     config: PortableConfig,
     runtime: Arc<Runtime>,
     current_tab: Tab,
+    simulation: Option<Arc<Simulation>>,
+    /// Puertos compartidos por todas las pestañas (en simulación conservan su estado)
+    obs: Arc<dyn OBSPort>,
+    network: Arc<dyn NetworkPort>,
+    /// Detector compartido: las reglas con estado necesitan ver todas las muestras
+    detector: Arc<AnomalyDetector>,
+    /// Muestreo en segundo plano compartido por todas las pestañas
+    sampler: SamplerHandle,
+    /// Histórico en SQLite (None si no se pudo abrir)
+    metrics_store: Option<Arc<SqliteMetricsStore>>,
 
     // Estado
     connection_status: String,
     hardware_info: Option<String>,
+    bandwidth_test: Option<BandwidthTest>,
+    /// Prueba de subida en curso (no bloquea la interfaz)
+    bandwidth_task: Option<JoinHandle<anyhow::Result<BandwidthTest>>>,
     health_report: Option<String>,
+    history: Option<String>,
     anomalies: Vec<String>,
 
     // UI state
@@ -65,17 +85,50 @@ pub struct OBSAgentApp {
 }
 
 impl OBSAgentApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(_cc: &eframe::CreationContext<'_>, simulate: Option<SimulationScenario>) -> Self {
         let config = PortableConfig::load().unwrap_or_default();
         let runtime = Arc::new(Runtime::new().expect("Failed to create Tokio runtime"));
+        let simulation = simulate.map(|scenario| Arc::new(Simulation::new(scenario)));
+
+        let (obs, monitor): (Arc<dyn OBSPort>, Arc<dyn MonitorPort>) = match &simulation {
+            Some(simulation) => simulated_ports(Arc::clone(simulation)),
+            None => (Self::obs_adapter(&config), Arc::new(MonitorAdapter::new())),
+        };
+        let network: Arc<dyn NetworkPort> = match &simulation {
+            Some(simulation) => Arc::new(SimulatedNetworkAdapter::new(Arc::clone(simulation))),
+            None => Arc::new(NetworkAdapter::new()),
+        };
+        let sampler = {
+            let _guard = runtime.enter();
+            MonitorSampler::new(monitor).spawn()
+        };
+        let metrics_store = match runtime.block_on(SqliteMetricsStore::open(SqliteMetricsStore::default_path())) {
+            Ok(store) => {
+                let _ = runtime.block_on(store.apply_retention(&RetentionPolicy::default(), chrono::Utc::now()));
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                tracing::warn!("Metrics history disabled: {:#}", e);
+                None
+            }
+        };
 
         Self {
             config,
             runtime,
             current_tab: Tab::Home,
+            simulation,
+            obs,
+            network,
+            detector: Arc::new(AnomalyDetector::with_default_rules()),
+            sampler,
+            metrics_store,
             connection_status: "Not connected".to_string(),
             hardware_info: None,
+            bandwidth_test: None,
+            bandwidth_task: None,
             health_report: None,
+            history: None,
             anomalies: Vec::new(),
             show_config_saved: false,
             error_message: None,
@@ -104,6 +157,26 @@ impl OBSAgentApp {
         ui.label(&self.connection_status);
         ui.add_space(10.0);
 
+        match self.sampler.latest() {
+            Some(snapshot) => {
+                let temp = |t: Option<f32>| t.map_or("--".to_string(), |t| format!("{:.0}°C", t));
+                ui.label(format!(
+                    "📈 En vivo: CPU {:.0}% {} | GPU {} | RAM {}",
+                    snapshot.cpu_usage,
+                    temp(snapshot.cpu_temp),
+                    temp(snapshot.gpu_temp),
+                    snapshot
+                        .memory
+                        .as_ref()
+                        .map_or("--".to_string(), |m| format!("{:.0}%", m.used_percent)),
+                ));
+            }
+            None => {
+                ui.label("📈 En vivo: esperando primera muestra...");
+            }
+        }
+        ui.add_space(10.0);
+
         if ui.button("🔌 Probar Conexión OBS").clicked() {
             self.test_obs_connection();
         }
@@ -216,6 +289,10 @@ impl OBSAgentApp {
         if ui.button("💾 Guardar Configuración").clicked() {
             match self.config.save() {
                 Ok(_) => {
+                    // El host, puerto o contraseña de OBS pueden haber cambiado
+                    if self.simulation.is_none() {
+                        self.obs = Self::obs_adapter(&self.config);
+                    }
                     self.show_config_saved = true;
                     self.error_message = None;
                 }
@@ -243,8 +320,30 @@ impl OBSAgentApp {
         ui.heading("🖥️  Información de Hardware");
         ui.add_space(20.0);
 
-        if ui.button("🔄 Detectar Hardware").clicked() {
-            self.detect_hardware();
+        ui.horizontal(|ui| {
+            if ui.button("🔄 Detectar Hardware").clicked() {
+                self.detect_hardware();
+            }
+
+            ui.separator();
+            ui.label("Servidor:");
+            let mut endpoint = self.config.bandwidth_test_endpoint.clone().unwrap_or_default();
+            if ui
+                .add(egui::TextEdit::singleline(&mut endpoint).hint_text("vacío = servidor local de prueba"))
+                .changed()
+            {
+                self.config.bandwidth_test_endpoint = Some(endpoint).filter(|e| !e.trim().is_empty());
+            }
+            if self.bandwidth_task.is_some() {
+                ui.spinner();
+                ui.label("Midiendo subida...");
+            } else if ui.button("📶 Probar Ancho de Banda").clicked() {
+                self.run_bandwidth_test();
+            }
+        });
+        if self.bandwidth_task.is_some() {
+            self.poll_bandwidth_test();
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
         }
 
         ui.add_space(10.0);
@@ -275,6 +374,21 @@ impl OBSAgentApp {
         } else {
             ui.label("Presiona 'Ejecutar Health Check' para validar el sistema");
         }
+
+        ui.add_space(20.0);
+        ui.separator();
+
+        if self.metrics_store.is_some() {
+            if ui.button("📈 Ver Historial (24 h)").clicked() {
+                self.load_history();
+            }
+            if let Some(history) = &self.history {
+                ui.add_space(10.0);
+                ui.monospace(history);
+            }
+        } else {
+            ui.label("Historial no disponible");
+        }
     }
 
     fn render_anomalies(&mut self, ui: &mut egui::Ui) {
@@ -622,14 +736,26 @@ impl OBSAgentApp {
         });
     }
 
+    /// Puertos reales o simulados según `--simulate`
+    fn obs_adapter(config: &PortableConfig) -> Arc<dyn OBSPort> {
+        Arc::new(OBSAdapter::new(&config.obs_host, config.obs_port, config.obs_password.clone()))
+    }
+
+    fn ports(&self) -> (Arc<dyn OBSPort>, Arc<dyn MonitorPort>) {
+        (Arc::clone(&self.obs), self.sampler.monitor())
+    }
+
+    fn network(&self) -> Arc<dyn NetworkPort> {
+        Arc::clone(&self.network)
+    }
+
     fn test_obs_connection(&mut self) {
-        let config = self.config.clone();
         let runtime = Arc::clone(&self.runtime);
 
         self.connection_status = "Conectando...".to_string();
 
         // Spawn async task
-        let adapter = OBSAdapter::new(&config.obs_host, config.obs_port, config.obs_password);
+        let (adapter, _) = self.ports();
 
         match runtime.block_on(adapter.connect()) {
             Ok(_) => {
@@ -642,29 +768,84 @@ impl OBSAgentApp {
         }
     }
 
+    fn run_bandwidth_test(&mut self) {
+        let network = self.network();
+        let endpoint = self.config.bandwidth_test_endpoint.clone();
+
+        self.bandwidth_task = Some(self.runtime.spawn(async move {
+            run_bandwidth_test(network.as_ref(), endpoint.as_deref(), Duration::from_secs(5)).await
+        }));
+    }
+
+    /// Recoge el resultado de la prueba de subida cuando termina
+    fn poll_bandwidth_test(&mut self) {
+        if !self.bandwidth_task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        let Some(task) = self.bandwidth_task.take() else {
+            return;
+        };
+
+        match self.runtime.block_on(task) {
+            Ok(Ok(test)) => {
+                self.bandwidth_test = Some(test);
+                self.detect_hardware();
+            }
+            Ok(Err(e)) => {
+                self.error_message = Some(format!("Error en la prueba de ancho de banda: {}", e));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("La prueba de ancho de banda falló: {}", e));
+            }
+        }
+    }
+
     fn detect_hardware(&mut self) {
-        let monitor = MonitorAdapter::new();
+        let (_, monitor) = self.ports();
 
         match monitor.detect_hardware() {
-            Ok(hw) => {
+            Ok(mut hw) => {
+                if let Some(test) = &self.bandwidth_test {
+                    hw.limit_to_upload(test.sustained_upload_kbps());
+                }
+
+                let upload = self.bandwidth_test.as_ref().map(|test| {
+                    format!(
+                        "\n\n📶 SUBIDA:\n  Servidor: {}\n  Throughput: {:.0} kbps (±{:.0})\n  Rango: {:.0} - {:.0} kbps\n  Sostenida: {} kbps\n  Bitrate seguro: {} kbps",
+                        test.endpoint, test.throughput_kbps, test.stddev_kbps,
+                        test.min_kbps, test.max_kbps,
+                        test.sustained_upload_kbps(), test.safe_bitrate_kbps()
+                    )
+                });
                 let info = format!(
                     "OS: {} {}\nHostname: {}\n\n\
                     💻 CPU:\n  Nombre: {}\n  Cores: {} físicos / {} lógicos\n  Frecuencia: {:.0} MHz\n\n\
-                    🎮 GPU:\n  {}\n\n\
-                    💾 RAM:\n  Total: {:.1} GB\n  Disponible: {:.1} GB\n  Uso: {:.1}%\n\n\
-                    📊 RECOMENDACIONES:\n  Encoder: {:?}\n  Preset: {}\n  Resolución: {}x{}\n  FPS: {}\n  Bitrate: {} kbps",
+                    🎮 GPUs:\n{}\n\n\
+                    💾 RAM:\n  Total: {:.1} GB\n  Disponible: {:.1} GB\n  Uso: {:.1}%{}\n\n\
+                    📊 RECOMENDACIONES:\n  Encoder: {:?}\n  Preset: {}\n  Resolución: {}x{}\n  FPS: {}\n  Bitrate: {} kbps{}",
                     hw.os, hw.os_version, hw.hostname,
                     hw.cpu.brand, hw.cpu.cores_physical, hw.cpu.cores_logical, hw.cpu.frequency_mhz,
-                    if let Some(gpu) = &hw.gpu {
-                        format!("Nombre: {}\n  Vendor: {:?}\n  NVENC: {}\n  AMF: {}\n  QSV: {}",
-                            gpu.name, gpu.vendor, gpu.supports_nvenc, gpu.supports_amf, gpu.supports_qsv)
+                    if hw.gpus.is_empty() {
+                        "  No detectado".to_string()
                     } else {
-                        "No detectado".to_string()
+                        hw.gpus.iter().enumerate().map(|(i, gpu)| {
+                            format!("  #{} {}{}{}\n    Vendor: {:?}\n    NVENC: {}\n    AMF: {}\n    QSV: {}\n    VAAPI: {}{}",
+                                i, gpu.name,
+                                if hw.render_gpu == Some(i) { " [render]" } else { "" },
+                                if hw.encode_gpu == Some(i) { " [encode]" } else { "" },
+                                gpu.vendor, gpu.supports_nvenc, gpu.supports_amf, gpu.supports_qsv, gpu.supports_vaapi,
+                                gpu.temperature_c.map(|t| format!("\n    Temperatura: {:.0}°C", t)).unwrap_or_default())
+                        }).collect::<Vec<_>>().join("\n")
                     },
                     hw.ram.total_gb, hw.ram.available_gb, hw.ram.used_percent,
+                    upload.unwrap_or_default(),
                     hw.recommended_encoder, hw.recommended_preset,
                     hw.recommended_resolution.0, hw.recommended_resolution.1,
-                    hw.recommended_fps, hw.recommended_bitrate
+                    hw.recommended_fps, hw.recommended_bitrate,
+                    self.bandwidth_test
+                        .as_ref()
+                        .map(|test| format!(" (la subida permite hasta {} kbps)", test.safe_bitrate_kbps()))
+                        .unwrap_or_default()
                 );
 
                 self.hardware_info = Some(info);
@@ -677,13 +858,13 @@ impl OBSAgentApp {
     }
 
     fn run_health_check(&mut self) {
-        let config = self.config.clone();
         let runtime = Arc::clone(&self.runtime);
 
-        let obs = Arc::new(OBSAdapter::new(&config.obs_host, config.obs_port, config.obs_password)) as Arc<dyn OBSPort>;
-        let monitor = Arc::new(MonitorAdapter::new()) as Arc<dyn MonitorPort>;
+        let (obs, monitor) = self.ports();
 
-        let service = HealthCheckService::new(obs, monitor);
+        let service = HealthCheckService::new(obs, monitor)
+            .with_network(self.network())
+            .with_detector(Arc::clone(&self.detector));
 
         match runtime.block_on(service.check()) {
             Ok(report) => {
@@ -712,6 +893,16 @@ impl OBSAgentApp {
 
                 self.health_report = Some(info);
                 self.error_message = None;
+
+                if let Some(store) = &self.metrics_store {
+                    let stored = runtime.block_on(async {
+                        store.record_anomalies(&report.anomalies).await?;
+                        store.record_health(&report).await
+                    });
+                    if let Err(e) = stored {
+                        self.error_message = Some(format!("Error guardando historial: {}", e));
+                    }
+                }
             }
             Err(e) => {
                 self.error_message = Some(format!("Error en health check: {}", e));
@@ -720,13 +911,13 @@ impl OBSAgentApp {
     }
 
     fn scan_anomalies(&mut self) {
-        let monitor = MonitorAdapter::new();
-        let detector = AnomalyDetector::with_default_rules();
+        let (_, monitor) = self.ports();
 
         let context = SystemContext {
-            cpu_temp: monitor.get_cpu_temp().unwrap_or(0.0),
-            gpu_temp: monitor.get_gpu_temp().unwrap_or(0.0),
+            cpu_temp: monitor.get_cpu_temp().ok().flatten().unwrap_or(0.0),
+            gpu_temp: monitor.get_gpu_temp().ok().flatten().unwrap_or(0.0),
             cpu_usage: monitor.get_cpu_usage().unwrap_or(0.0),
+            cpu_frequency: monitor.get_cpu_frequency().ok().flatten(),
             memory_used_percent: monitor.get_memory_info().map(|m| m.used_percent).unwrap_or(0.0),
             disk_free_gb: monitor.get_disk_space().map(|d| d.free_gb).unwrap_or(0.0),
             obs_dropped_frames_percent: 0.0,
@@ -734,9 +925,26 @@ impl OBSAgentApp {
             missing_sources: vec![],
             audio_peak_db: None,
             network_bitrate: None,
+            recording_minutes_left: None,
+            obs_process: monitor.get_obs_process().ok().flatten(),
+            network: None,
+            power: monitor.get_power_status().ok().flatten(),
+            timestamp: chrono::Utc::now(),
         };
 
-        let anomalies = detector.scan(&context);
+        let anomalies = self.detector.scan(&context);
+
+        if let Some(store) = &self.metrics_store {
+            let record = TelemetryRecord::from_context(context);
+            let stored = self.runtime.block_on(async {
+                store.record(&record).await?;
+                store.record_anomalies(&anomalies).await
+            });
+            if let Err(e) = stored {
+                self.error_message = Some(format!("Error guardando historial: {}", e));
+                return;
+            }
+        }
 
         if anomalies.is_empty() {
             self.anomalies = vec!["✅ No se detectaron anomalías".to_string()];
@@ -755,6 +963,71 @@ impl OBSAgentApp {
 
         self.error_message = None;
     }
+
+    fn load_history(&mut self) {
+        let Some(store) = self.metrics_store.clone() else {
+            return;
+        };
+        let range = TimeRange::last(Duration::from_secs(24 * 3600));
+
+        let result = self.runtime.block_on(async {
+            let mut lines = Vec::new();
+            for metric in [
+                Metric::CpuTempCelsius,
+                Metric::GpuTempCelsius,
+                Metric::CpuUsagePercent,
+                Metric::MemoryUsedPercent,
+                Metric::ObsDroppedFramesPercent,
+            ] {
+                // Intervalos del tamaño del rango: como mucho dos puntos que se combinan abajo
+                let summary = store.query_metric(metric, range, Some(Duration::from_secs(24 * 3600 + 1))).await?;
+                let samples: u64 = summary.iter().map(|p| p.samples).sum();
+                if samples == 0 {
+                    lines.push(format!("{:<28} sin datos", metric.name()));
+                    continue;
+                }
+                let avg = summary.iter().map(|p| p.avg * p.samples as f64).sum::<f64>() / samples as f64;
+                let min = summary.iter().map(|p| p.min).fold(f64::INFINITY, f64::min);
+                let max = summary.iter().map(|p| p.max).fold(f64::NEG_INFINITY, f64::max);
+                lines.push(format!(
+                    "{:<28} media {:.1} | mín {:.1} | máx {:.1} ({} muestras)",
+                    metric.name(),
+                    avg,
+                    min,
+                    max,
+                    samples
+                ));
+            }
+
+            let anomalies = store.query_anomalies(range).await?;
+            let reports = store.query_health(range).await?;
+            lines.push(String::new());
+            lines.push(format!(
+                "Anomalías: {} | Health checks: {} ({} con problemas)",
+                anomalies.len(),
+                reports.len(),
+                reports.iter().filter(|r| !r.is_healthy).count()
+            ));
+            for anomaly in anomalies.iter().rev().take(10) {
+                lines.push(format!(
+                    "  {} [{:?}] {:?}: {}",
+                    anomaly.timestamp.with_timezone(&chrono::Local).format("%d/%m %H:%M"),
+                    anomaly.severity,
+                    anomaly.anomaly_type,
+                    anomaly.details
+                ));
+            }
+            anyhow::Ok(lines.join("\n"))
+        });
+
+        match result {
+            Ok(history) => {
+                self.history = Some(history);
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(format!("Error leyendo historial: {}", e)),
+        }
+    }
 }
 
 impl eframe::App for OBSAgentApp {
@@ -775,7 +1048,10 @@ impl eframe::App for OBSAgentApp {
                 ui.add_space(10.0);
 
                 match self.current_tab {
-                    Tab::Home => self.render_home(ui),
+                    Tab::Home => {
+                        self.render_home(ui);
+                        ctx.request_repaint_after(Duration::from_secs(1));
+                    }
                     Tab::Config => self.render_config(ui),
                     Tab::Hardware => self.render_hardware(ui),
                     Tab::Health => self.render_health(ui),
@@ -790,6 +1066,12 @@ impl eframe::App for OBSAgentApp {
         egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.label("OBS Agent v0.1.0 - Portable Mode");
+                if let Some(simulation) = &self.simulation {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!("🧪 Simulación: {}", simulation.scenario()),
+                    );
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.hyperlink_to("Documentación", "https://github.com/iberi22/obs-studio-agent");
                 });
@@ -0,0 +1,33 @@
+-- Histórico de métricas: muestras crudas (resolution_secs = 0) y agregados
+CREATE TABLE IF NOT EXISTS metric_samples (
+    timestamp_ms    INTEGER NOT NULL,
+    metric          TEXT    NOT NULL,
+    value           REAL    NOT NULL,
+    min_value       REAL    NOT NULL,
+    max_value       REAL    NOT NULL,
+    sample_count    INTEGER NOT NULL DEFAULT 1,
+    resolution_secs INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_samples_metric_time ON metric_samples (metric, timestamp_ms);
+CREATE INDEX IF NOT EXISTS idx_metric_samples_resolution_time ON metric_samples (resolution_secs, timestamp_ms);
+
+CREATE TABLE IF NOT EXISTS anomalies (
+    id           TEXT    PRIMARY KEY,
+    timestamp_ms INTEGER NOT NULL,
+    anomaly_type TEXT    NOT NULL,
+    severity     TEXT    NOT NULL,
+    data         TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anomalies_time ON anomalies (timestamp_ms);
+
+CREATE TABLE IF NOT EXISTS health_reports (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp_ms  INTEGER NOT NULL,
+    is_healthy    INTEGER NOT NULL,
+    anomaly_count INTEGER NOT NULL,
+    data          TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_health_reports_time ON health_reports (timestamp_ms);
@@ -0,0 +1,28 @@
+-- Tokens de la API: solo se guarda el SHA-256 del secreto
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id            TEXT    PRIMARY KEY,
+    name          TEXT    NOT NULL,
+    scope         TEXT    NOT NULL,
+    token_hash    TEXT    NOT NULL UNIQUE,
+    created_ms    INTEGER NOT NULL,
+    last_used_ms  INTEGER,
+    revoked_ms    INTEGER
+);
+
+-- Un nombre solo puede estar en uso por un token activo
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_tokens_active_name ON api_tokens (name) WHERE revoked_ms IS NULL;
+
+-- Quién ejecutó (o intentó ejecutar) cada acción que modifica algo
+CREATE TABLE IF NOT EXISTS audit_log (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp_ms INTEGER NOT NULL,
+    token_id     TEXT,
+    token_name   TEXT,
+    method       TEXT    NOT NULL,
+    path         TEXT    NOT NULL,
+    status       INTEGER NOT NULL,
+    details      TEXT,
+    remote_addr  TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_time ON audit_log (timestamp_ms);
@@ -205,7 +205,7 @@ impl AIPort for AIAdapter {
         let prompt = format!(
             "Analyze this hardware configuration for OBS streaming:\n\
              CPU: {} ({} cores)\n\
-             GPU: {:?}\n\
+             GPUs: {:?}\n\
              RAM: {:.1} GB\n\
              \n\
              Recommended encoder: {:?}\n\
@@ -223,7 +223,7 @@ impl AIPort for AIAdapter {
              Format as JSON with keys: is_optimal, issues, recommendations, required_plugins",
             hardware.cpu.brand,
             hardware.cpu.cores_physical,
-            hardware.gpu,
+            hardware.gpus,
             hardware.ram.total_gb,
             hardware.recommended_encoder,
             hardware.recommended_preset,
@@ -279,14 +279,14 @@ impl AIPort for AIAdapter {
         let prompt = format!(
             "Create optimal OBS settings for this hardware:\n\
              CPU: {} ({} cores)\n\
-             GPU: {:?}\n\
+             GPUs: {:?}\n\
              RAM: {:.1} GB\n\
              \n\
              Provide optimal encoder, preset, bitrate, and resolution. \
              Format as JSON.",
             hardware.cpu.brand,
             hardware.cpu.cores_physical,
-            hardware.gpu,
+            hardware.gpus,
             hardware.ram.total_gb
         );
 
@@ -0,0 +1,264 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use obs_agent_core::application::ports::TokenStore;
+use obs_agent_core::domain::models::{ApiToken, AuditEntry, Scope, TimeRange};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use super::sqlite::{from_millis, SqliteDatabase};
+use std::path::{Path, PathBuf};
+
+/// Prefijo de los secretos (facilita detectarlos si se filtran en logs o repos)
+const TOKEN_PREFIX: &str = "obsa_";
+/// `last_used` se actualiza como mucho una vez por este intervalo
+const LAST_USED_RESOLUTION_MS: i64 = 60_000;
+
+type TokenRow = (String, String, String, i64, Option<i64>, Option<i64>);
+type AuditRow = (i64, Option<String>, Option<String>, String, String, i64, Option<String>, Option<String>);
+
+/// Tokens de la API y auditoría en SQLite
+pub struct SqliteTokenStore {
+    db: SqliteDatabase,
+}
+
+impl SqliteTokenStore {
+    /// Abre (o crea) la base de datos y aplica las migraciones pendientes
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let db = SqliteDatabase::open(path, "token store", &sqlx::migrate!("./migrations/auth")).await?;
+        Ok(Self { db })
+    }
+
+    /// Ubicación por defecto (directorio de datos del usuario)
+    pub fn default_path() -> PathBuf {
+        SqliteDatabase::default_path("auth.db")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.db.path
+    }
+
+    /// Secreto aleatorio de 256 bits
+    fn generate_secret() -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        format!("{}{}", TOKEN_PREFIX, hex(&bytes))
+    }
+
+    fn hash(secret: &str) -> String {
+        hex(&Sha256::digest(secret.trim().as_bytes()))
+    }
+
+    fn token_from_row((id, name, scope, created, last_used, revoked): TokenRow) -> Result<ApiToken> {
+        Ok(ApiToken {
+            id,
+            name,
+            scope: scope.parse()?,
+            created_at: from_millis(created),
+            last_used_at: last_used.map(from_millis),
+            revoked_at: revoked.map(from_millis),
+        })
+    }
+}
+
+#[async_trait]
+impl TokenStore for SqliteTokenStore {
+    async fn create_token(&self, name: &str, scope: Scope) -> Result<(ApiToken, String)> {
+        let name = name.trim();
+        anyhow::ensure!(!name.is_empty(), "Token name must not be empty");
+
+        let secret = Self::generate_secret();
+        let token = ApiToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            scope,
+            created_at: Utc::now(),
+            last_used_at: None,
+            revoked_at: None,
+        };
+
+        sqlx::query("INSERT INTO api_tokens (id, name, scope, token_hash, created_ms) VALUES (?, ?, ?, ?, ?)")
+            .bind(&token.id)
+            .bind(&token.name)
+            .bind(scope.name())
+            .bind(Self::hash(&secret))
+            .bind(token.created_at.timestamp_millis())
+            .execute(&self.db.pool)
+            .await
+            .map_err(|e| match &e {
+                sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                    anyhow::anyhow!("An active token named '{}' already exists", name)
+                }
+                _ => anyhow::Error::new(e).context("Failed to store token"),
+            })?;
+
+        Ok((token, secret))
+    }
+
+    async fn list_tokens(&self) -> Result<Vec<ApiToken>> {
+        let rows: Vec<TokenRow> = sqlx::query_as(
+            "SELECT id, name, scope, created_ms, last_used_ms, revoked_ms FROM api_tokens ORDER BY created_ms",
+        )
+        .fetch_all(&self.db.pool)
+        .await
+        .context("Failed to list tokens")?;
+
+        rows.into_iter().map(Self::token_from_row).collect()
+    }
+
+    async fn revoke_token(&self, name_or_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE api_tokens SET revoked_ms = ? WHERE (name = ? OR id = ?) AND revoked_ms IS NULL",
+        )
+        .bind(Utc::now().timestamp_millis())
+        .bind(name_or_id)
+        .bind(name_or_id)
+        .execute(&self.db.pool)
+        .await
+        .context("Failed to revoke token")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn authenticate(&self, secret: &str) -> Result<Option<ApiToken>> {
+        if !secret.trim().starts_with(TOKEN_PREFIX) {
+            return Ok(None);
+        }
+
+        let row: Option<TokenRow> = sqlx::query_as(
+            "SELECT id, name, scope, created_ms, last_used_ms, revoked_ms
+             FROM api_tokens WHERE token_hash = ? AND revoked_ms IS NULL",
+        )
+        .bind(Self::hash(secret))
+        .fetch_optional(&self.db.pool)
+        .await
+        .context("Failed to look up token")?;
+        let Some(token) = row.map(Self::token_from_row).transpose()? else {
+            return Ok(None);
+        };
+
+        let now = Utc::now().timestamp_millis();
+        if !matches!(token.last_used_at, Some(used) if now - used.timestamp_millis() < LAST_USED_RESOLUTION_MS) {
+            sqlx::query("UPDATE api_tokens SET last_used_ms = ? WHERE id = ?")
+                .bind(now)
+                .bind(&token.id)
+                .execute(&self.db.pool)
+                .await
+                .context("Failed to update token usage")?;
+        }
+
+        Ok(Some(token))
+    }
+
+    async fn record_audit(&self, entry: &AuditEntry) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO audit_log (timestamp_ms, token_id, token_name, method, path, status, details, remote_addr)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(entry.timestamp.timestamp_millis())
+        .bind(&entry.token_id)
+        .bind(&entry.token_name)
+        .bind(&entry.method)
+        .bind(&entry.path)
+        .bind(entry.status as i64)
+        .bind(&entry.details)
+        .bind(&entry.remote_addr)
+        .execute(&self.db.pool)
+        .await
+        .context("Failed to store audit entry")?;
+        Ok(())
+    }
+
+    async fn query_audit(&self, range: TimeRange, limit: usize) -> Result<Vec<AuditEntry>> {
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            "SELECT timestamp_ms, token_id, token_name, method, path, status, details, remote_addr
+             FROM audit_log WHERE timestamp_ms >= ? AND timestamp_ms <= ?
+             ORDER BY timestamp_ms DESC, id DESC LIMIT ?",
+        )
+        .bind(range.from.timestamp_millis())
+        .bind(range.to.timestamp_millis())
+        .bind(limit as i64)
+        .fetch_all(&self.db.pool)
+        .await
+        .context("Failed to query audit log")?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(timestamp, token_id, token_name, method, path, status, details, remote_addr)| AuditEntry {
+                    timestamp: from_millis(timestamp),
+                    token_id,
+                    token_name,
+                    method,
+                    path,
+                    status: status as u16,
+                    details,
+                    remote_addr,
+                },
+            )
+            .collect())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tokens_are_hashed_and_revocable() {
+        let path = std::env::temp_dir().join(format!("obs-agent-auth-{}.db", uuid::Uuid::new_v4()));
+        let store = SqliteTokenStore::open(&path).await.unwrap();
+
+        let (token, secret) = store.create_token("stream-deck", Scope::Operator).await.unwrap();
+        assert!(secret.starts_with(TOKEN_PREFIX));
+        assert!(store.create_token("stream-deck", Scope::Read).await.is_err());
+
+        // Solo se guarda el hash
+        let stored: Vec<(String,)> = sqlx::query_as("SELECT token_hash FROM api_tokens")
+            .fetch_all(&store.db.pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, vec![(SqliteTokenStore::hash(&secret),)]);
+        assert!(!stored[0].0.contains(&secret[TOKEN_PREFIX.len()..]));
+
+        let authenticated = store.authenticate(&secret).await.unwrap().unwrap();
+        assert_eq!((authenticated.id.as_str(), authenticated.scope), (token.id.as_str(), Scope::Operator));
+        assert!(store.authenticate("obsa_wrong").await.unwrap().is_none());
+
+        assert!(store.revoke_token("stream-deck").await.unwrap());
+        assert!(!store.revoke_token("stream-deck").await.unwrap());
+        assert!(store.authenticate(&secret).await.unwrap().is_none());
+        // El nombre queda libre tras revocar
+        store.create_token("stream-deck", Scope::Read).await.unwrap();
+        assert_eq!(store.list_tokens().await.unwrap().len(), 2);
+
+        store
+            .record_audit(&AuditEntry {
+                timestamp: Utc::now(),
+                token_id: Some(token.id.clone()),
+                token_name: Some(token.name.clone()),
+                method: "PUT".to_string(),
+                path: "/api/v1/scenes/current".to_string(),
+                status: 204,
+                details: Some("scene: BRB".to_string()),
+                remote_addr: None,
+            })
+            .await
+            .unwrap();
+        let now = Utc::now();
+        let audit = store
+            .query_audit(TimeRange::new(now - chrono::Duration::minutes(1), now + chrono::Duration::seconds(1)), 10)
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].details.as_deref(), Some("scene: BRB"));
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use anyhow::{Context, Result};
+use obs_agent_core::application::ports::NetworkPort;
+use obs_agent_core::domain::models::BandwidthTest;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tokio::task::JoinHandle;
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+
+/// Tamaño de cada escritura
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Buffer de envío pequeño para que el ritmo de escritura siga al de la red
+const SEND_BUFFER: u32 = 256 * 1024;
+/// Tiempo extra para que el servidor drene y confirme los bytes recibidos
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Bytes que el sink acepta por conexión (~1 minuto a 500 Mbps)
+pub const DEFAULT_SINK_MAX_BYTES: u64 = 4_000_000_000;
+/// Conexiones simultáneas que atiende el sink
+pub const DEFAULT_SINK_MAX_CONNECTIONS: usize = 4;
+
+/// Prueba de subida: envía datos a un servidor durante una ventana de tiempo
+///
+/// El servidor (ver [`BandwidthSink`]) descarta los datos y, al cerrar el
+/// cliente su escritura, responde con el total recibido (u64 big-endian).
+/// Con cualquier otro servidor TCP, o si la confirmación supera lo enviado,
+/// se usa lo enviado por el cliente.
+pub struct BandwidthProbe {
+    duration: Duration,
+    interval: Duration,
+    /// Intervalos iniciales descartados (llenado de buffers, slow start)
+    warmup_intervals: usize,
+}
+
+impl BandwidthProbe {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            interval: Duration::from_millis(250),
+            warmup_intervals: 1,
+        }
+    }
+
+    pub async fn run(&self, endpoint: &str) -> Result<BandwidthTest> {
+        let addr = tokio::net::lookup_host(endpoint)
+            .await
+            .with_context(|| format!("Failed to resolve {}", endpoint))?
+            .next()
+            .with_context(|| format!("No address found for {}", endpoint))?;
+
+        let socket = if addr.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+        socket.set_send_buffer_size(SEND_BUFFER)?;
+        let stream = socket
+            .connect(addr)
+            .await
+            .with_context(|| format!("Failed to connect to bandwidth server {}", endpoint))?;
+
+        info!("Testing upload to {} for {:?}", endpoint, self.duration);
+        let chunk = vec![0x5a_u8; CHUNK_SIZE];
+        let start = Instant::now();
+        let mut interval_start = start;
+        let mut interval_bytes = 0u64;
+        let mut bytes_sent = 0u64;
+        let mut intervals = Vec::new();
+
+        // El servidor confirma al cerrar nosotros la escritura, o antes si corta
+        // la prueba al llegar a su límite de bytes
+        let (mut reader, mut writer) = stream.into_split();
+        let mut ack = tokio::spawn(async move { reader.read_u64().await });
+
+        while start.elapsed() < self.duration && !ack.is_finished() {
+            writer.write_all(&chunk).await.context("Bandwidth test connection dropped")?;
+            bytes_sent += CHUNK_SIZE as u64;
+            interval_bytes += CHUNK_SIZE as u64;
+
+            let elapsed = interval_start.elapsed();
+            if elapsed >= self.interval {
+                intervals.push(interval_bytes as f64 * 8.0 / 1000.0 / elapsed.as_secs_f64());
+                interval_start = Instant::now();
+                interval_bytes = 0;
+                // Deja correr la lectura de la confirmación en runtimes de un hilo
+                tokio::task::yield_now().await;
+            }
+        }
+        if ack.is_finished() {
+            info!("{} stopped the test early at its byte limit", endpoint);
+        }
+
+        // Esperar la confirmación del servidor: mide lo que realmente llegó
+        writer.shutdown().await.ok();
+        let acknowledged = tokio::time::timeout(ACK_TIMEOUT, &mut ack)
+            .await
+            .ok()
+            .and_then(|ack| ack.ok())
+            .and_then(|ack| ack.ok())
+            .filter(|ack| {
+                let plausible = *ack <= bytes_sent;
+                if !plausible {
+                    warn!("{} acknowledged {} bytes but only {} were sent; ignoring it", endpoint, ack, bytes_sent);
+                }
+                plausible
+            });
+        ack.abort();
+        let duration_secs = start.elapsed().as_secs_f64();
+        let delivered = acknowledged.unwrap_or(bytes_sent);
+
+        let steady = intervals.get(self.warmup_intervals..).filter(|rest| !rest.is_empty()).unwrap_or(&intervals);
+        let test = BandwidthTest {
+            endpoint: endpoint.to_string(),
+            duration_secs,
+            bytes_sent,
+            bytes_acknowledged: acknowledged,
+            throughput_kbps: delivered as f64 * 8.0 / 1000.0 / duration_secs,
+            ..Default::default()
+        }
+        .with_intervals(steady);
+
+        debug!(
+            "Bandwidth test to {}: {:.0} kbps (±{:.0}), {} bytes sent, {:?} acknowledged",
+            endpoint, test.throughput_kbps, test.stddev_kbps, bytes_sent, acknowledged
+        );
+        Ok(test)
+    }
+}
+
+/// Servidor que descarta lo recibido y confirma el total (para pruebas de subida)
+pub struct BandwidthSink {
+    listener: TcpListener,
+    rate_limit_kbps: Option<u32>,
+    max_bytes: u64,
+    max_connections: usize,
+}
+
+impl BandwidthSink {
+    pub async fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind bandwidth sink on {}", addr))?;
+        Ok(Self {
+            listener,
+            rate_limit_kbps: None,
+            max_bytes: DEFAULT_SINK_MAX_BYTES,
+            max_connections: DEFAULT_SINK_MAX_CONNECTIONS,
+        })
+    }
+
+    /// Bytes aceptados por conexión; al llegar al límite se confirma y se cierra
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self
+    }
+
+    /// Conexiones simultáneas; las que sobran se cierran sin leer
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Limita la velocidad de lectura (simula un enlace lento)
+    pub fn with_rate_limit_kbps(mut self, kbps: u32) -> Self {
+        self.rate_limit_kbps = Some(kbps);
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().context("Bandwidth sink has no local address")
+    }
+
+    /// Atiende conexiones hasta que se cancele la tarea
+    pub async fn serve(self) -> Result<()> {
+        info!("Bandwidth sink listening on {}", self.local_addr()?);
+        let slots = Arc::new(Semaphore::new(self.max_connections));
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let Ok(permit) = Arc::clone(&slots).try_acquire_owned() else {
+                warn!("Rejecting bandwidth client {}: {} tests already running", peer, self.max_connections);
+                continue;
+            };
+            let rate_limit_kbps = self.rate_limit_kbps;
+            let max_bytes = self.max_bytes;
+            tokio::spawn(async move {
+                let _permit = permit;
+                match Self::drain(stream, rate_limit_kbps, max_bytes).await {
+                    Ok(bytes) => info!("Received {} bytes from {}", bytes, peer),
+                    Err(e) => debug!("Bandwidth client {} failed: {}", peer, e),
+                }
+            });
+        }
+    }
+
+    /// Lanza el servidor en segundo plano
+    pub fn spawn(self) -> JoinHandle<Result<()>> {
+        tokio::spawn(self.serve())
+    }
+
+    async fn drain(mut stream: TcpStream, rate_limit_kbps: Option<u32>, max_bytes: u64) -> Result<u64> {
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut total = 0u64;
+        let start = Instant::now();
+
+        loop {
+            let read = stream.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            total += read as u64;
+            if total >= max_bytes {
+                debug!("Bandwidth client reached the {} byte limit", max_bytes);
+                break;
+            }
+
+            if let Some(kbps) = rate_limit_kbps {
+                let expected = Duration::from_secs_f64(total as f64 * 8.0 / 1000.0 / kbps as f64);
+                if let Some(wait) = expected.checked_sub(start.elapsed()) {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+
+        stream.write_u64(total).await?;
+        if total >= max_bytes {
+            // Descartar lo que siga llegando hasta que el cliente vea la confirmación y cierre
+            let _ = tokio::time::timeout(ACK_TIMEOUT, async {
+                while matches!(stream.read(&mut buffer).await, Ok(read) if read > 0) {}
+            })
+            .await;
+        }
+        stream.shutdown().await.ok();
+        Ok(total)
+    }
+}
+
+/// Prueba de subida contra `endpoint` o, si no se indica, contra un
+/// [`BandwidthSink`] local temporal (útil para verificar la instalación)
+pub async fn run_bandwidth_test(
+    network: &dyn NetworkPort,
+    endpoint: Option<&str>,
+    duration: Duration,
+) -> Result<BandwidthTest> {
+    if let Some(endpoint) = endpoint {
+        return network.bandwidth_test(endpoint, duration).await;
+    }
+
+    let sink = BandwidthSink::bind("127.0.0.1:0").await?;
+    let addr = sink.local_addr()?;
+    let server = sink.spawn();
+    let result = network.bandwidth_test(&addr.to_string(), duration).await;
+    server.abort();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_probe_against_local_sink() {
+        // Sin límite de bytes: en loopback se superaría en una fracción de segundo
+        let sink = BandwidthSink::bind("127.0.0.1:0").await.unwrap().with_max_bytes(u64::MAX);
+        let addr = sink.local_addr().unwrap();
+        let server = sink.spawn();
+
+        let test = BandwidthProbe::new(Duration::from_millis(600))
+            .run(&addr.to_string())
+            .await
+            .unwrap();
+        server.abort();
+
+        assert_eq!(test.bytes_acknowledged, Some(test.bytes_sent));
+        assert!(test.throughput_kbps > 0.0);
+        assert!(test.min_kbps <= test.max_kbps);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_sink_caps_throughput() {
+        let sink = BandwidthSink::bind("127.0.0.1:0").await.unwrap().with_rate_limit_kbps(20_000);
+        let addr = sink.local_addr().unwrap();
+        let server = sink.spawn();
+
+        let test = BandwidthProbe::new(Duration::from_secs(1))
+            .run(&addr.to_string())
+            .await
+            .unwrap();
+        server.abort();
+
+        // Lo confirmado por el servidor refleja el enlace limitado, no los buffers locales
+        assert!(test.throughput_kbps < 26_000.0, "{:?}", test);
+        assert!(test.throughput_kbps > 12_000.0, "{:?}", test);
+        assert!(test.safe_bitrate_kbps() < 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_sink_byte_limit_ends_test_early() {
+        let sink = BandwidthSink::bind("127.0.0.1:0").await.unwrap().with_max_bytes(1_000_000);
+        let addr = sink.local_addr().unwrap();
+        let server = sink.spawn();
+
+        let test = BandwidthProbe::new(Duration::from_secs(3))
+            .run(&addr.to_string())
+            .await
+            .unwrap();
+        server.abort();
+
+        let acknowledged = test.bytes_acknowledged.unwrap();
+        assert!((1_000_000..=test.bytes_sent).contains(&acknowledged), "{:?}", test);
+        assert!(test.duration_secs < 2.0, "{:?}", test);
+    }
+
+    #[tokio::test]
+    async fn test_inflated_ack_is_ignored() {
+        // Servidor que confirma más bytes de los que pudo recibir
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; CHUNK_SIZE];
+            while stream.read(&mut buffer).await.unwrap_or(0) > 0 {}
+            stream.write_u64(u64::MAX).await.unwrap();
+        });
+
+        let test = BandwidthProbe::new(Duration::from_millis(300))
+            .run(&addr.to_string())
+            .await
+            .unwrap();
+        server.abort();
+
+        assert_eq!(test.bytes_acknowledged, None);
+        let sent_kbps = test.bytes_sent as f64 * 8.0 / 1000.0 / test.duration_secs;
+        assert!((test.throughput_kbps - sent_kbps).abs() < 1.0, "{:?}", test);
+    }
+
+    #[tokio::test]
+    async fn test_sink_caps_connections() {
+        let sink = BandwidthSink::bind("127.0.0.1:0").await.unwrap().with_max_connections(1);
+        let addr = sink.local_addr().unwrap();
+        let server = sink.spawn();
+
+        // La primera conexión ocupa el único hueco; la segunda se cierra sin leer
+        let _first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        let mut buffer = [0u8; 8];
+        let read = tokio::time::timeout(Duration::from_secs(2), second.read(&mut buffer)).await.unwrap();
+        server.abort();
+
+        assert!(matches!(read, Ok(0) | Err(_)));
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use obs_agent_core::application::ports::{
+    AgentRegistration, FleetCommand, FleetPort, Heartbeat, HeartbeatAck, RegistrationAck,
+};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+use tracing::debug;
+
+/// Cliente del servidor central (`obs-agent-server`) para el agente de flota
+///
+/// `server` es la URL base del servidor, p.ej. `http://central:8080`; el
+/// token necesita alcance `operator` para registrarse y enviar latidos.
+pub struct FleetClient {
+    client: Client,
+    server: String,
+    token: Option<String>,
+}
+
+impl FleetClient {
+    pub fn new(server: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            server: server.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Token de la API (cabecera `Authorization: Bearer ...`)
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        let request = self.client.post(format!("{}/api/v1/fleet{}", self.server, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Error con el mensaje del servidor si la respuesta no es 2xx
+async fn check(response: Response, what: &str) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    let message = body["message"].as_str().unwrap_or_default();
+    anyhow::bail!("{} failed: {} {}", what, status, message)
+}
+
+#[async_trait]
+impl FleetPort for FleetClient {
+    async fn register(&self, registration: &AgentRegistration) -> Result<RegistrationAck> {
+        let response = self
+            .post("/register")
+            .json(registration)
+            .send()
+            .await
+            .with_context(|| format!("Could not reach the fleet server at {}", self.server))?;
+        let ack = check(response, "Fleet registration").await?.json().await?;
+        Ok(ack)
+    }
+
+    async fn heartbeat(&self, host_id: &str, heartbeat: &Heartbeat) -> Result<Option<Vec<FleetCommand>>> {
+        let response = self
+            .post(&format!("/hosts/{}/heartbeat", host_id))
+            .json(heartbeat)
+            .send()
+            .await
+            .with_context(|| format!("Could not reach the fleet server at {}", self.server))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let ack: HeartbeatAck = check(response, "Fleet heartbeat").await?.json().await?;
+        debug!("Heartbeat sent, {} command(s) received", ack.commands.len());
+        Ok(Some(ack.commands))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::application::ports::CommandKind;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Servidor HTTP mínimo: responde en orden y devuelve la cabecera de cada petición
+    async fn serve(responses: Vec<(u16, String)>) -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (heads_tx, heads) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 8192];
+                loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, rest)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| l.to_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                            .and_then(|l| l.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if rest.len() >= length || read == 0 {
+                            heads_tx.send(head.to_string()).unwrap();
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{}/", addr), heads)
+    }
+
+    fn heartbeat() -> Heartbeat {
+        Heartbeat {
+            timestamp: chrono::Utc::now(),
+            sample: None,
+            health: None,
+            error: Some("OBS is not running".to_string()),
+            results: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_commands_and_unknown_host() {
+        let command = FleetCommand {
+            id: uuid::Uuid::new_v4(),
+            kind: CommandKind::SwitchScene { scene: "BRB".to_string() },
+            issued_at: chrono::Utc::now(),
+            issued_by: "admin".to_string(),
+        };
+        let ack = serde_json::to_string(&HeartbeatAck { commands: vec![command.clone()] }).unwrap();
+        let (server, mut heads) = serve(vec![
+            (200, ack),
+            (404, r#"{"error":"not_found","message":"Host 'studio-1' is not registered"}"#.to_string()),
+            (403, r#"{"error":"forbidden","message":"Token 'wall' has scope 'read'"}"#.to_string()),
+        ])
+        .await;
+        let client = FleetClient::new(server).with_token("obsa_secret");
+
+        let commands = client.heartbeat("studio-1", &heartbeat()).await.unwrap().unwrap();
+        assert_eq!(commands[0].kind, command.kind);
+        let head = heads.recv().await.unwrap();
+        assert!(head.starts_with("POST /api/v1/fleet/hosts/studio-1/heartbeat "));
+        assert!(head.to_lowercase().contains("authorization: bearer obsa_secret"));
+
+        assert!(client.heartbeat("studio-1", &heartbeat()).await.unwrap().is_none());
+        let error = client.heartbeat("studio-1", &heartbeat()).await.unwrap_err();
+        assert!(error.to_string().contains("scope 'read'"), "{}", error);
+    }
+}
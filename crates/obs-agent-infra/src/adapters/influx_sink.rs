@@ -0,0 +1,235 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use obs_agent_core::application::ports::TelemetrySink;
+use obs_agent_core::domain::services::TelemetryRecord;
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Measurement de todas las líneas enviadas
+const MEASUREMENT: &str = "obs_agent";
+
+/// Envía muestras a InfluxDB (o Telegraf, VictoriaMetrics...) en line protocol
+///
+/// `write_url` es el endpoint de escritura completo, p.ej.
+/// `http://influx:8086/api/v2/write?org=acme&bucket=obs&precision=ns` (v2) o
+/// `http://influx:8086/write?db=obs` (v1). Las líneas se acumulan hasta
+/// `batch_size` o hasta el siguiente `flush`.
+pub struct InfluxLineSink {
+    client: Client,
+    write_url: String,
+    token: Option<String>,
+    tags: BTreeMap<String, String>,
+    batch_size: usize,
+    buffer: Mutex<Vec<String>>,
+}
+
+impl InfluxLineSink {
+    pub fn new(write_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            write_url: write_url.into(),
+            token: None,
+            tags: BTreeMap::new(),
+            batch_size: 500,
+            buffer: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Token de la API (cabecera `Authorization: Token ...`)
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Etiquetas añadidas a cada línea (host, sala, equipo...)
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Líneas acumuladas antes de enviar sin esperar al `flush`
+    pub fn with_batch_size(mut self, lines: usize) -> Self {
+        self.batch_size = lines.max(1);
+        self
+    }
+
+    /// Líneas de una muestra: una por combinación de etiquetas, con un campo por métrica
+    pub fn lines(&self, record: &TelemetryRecord) -> Vec<String> {
+        let mut series: BTreeMap<BTreeMap<&str, &str>, Vec<String>> = BTreeMap::new();
+        let samples = record.metric_samples();
+        for sample in &samples {
+            let mut tags: BTreeMap<&str, &str> = self.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            tags.extend(sample.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+            if sample.value.is_finite() {
+                series.entry(tags).or_default().push(format!("{}={}", escape(&sample.name), sample.value));
+            }
+        }
+
+        let timestamp = record.timestamp.timestamp_nanos_opt().unwrap_or_default();
+        series
+            .into_iter()
+            .map(|(tags, fields)| {
+                let tags: String = tags
+                    .iter()
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(key, value)| format!(",{}={}", escape(key), escape(value)))
+                    .collect();
+                format!("{}{} {} {}", MEASUREMENT, tags, fields.join(","), timestamp)
+            })
+            .collect()
+    }
+
+    async fn send(&self, lines: Vec<String>) -> Result<()> {
+        let mut request = self.client.post(&self.write_url).body(lines.join("\n"));
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("Token {}", token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to push metrics to {}", self.write_url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("InfluxDB rejected {} line(s): {} {}", lines.len(), status, body.trim());
+        }
+
+        debug!("Pushed {} line(s) to {}", lines.len(), self.write_url);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for InfluxLineSink {
+    async fn record(&self, record: &TelemetryRecord) -> Result<()> {
+        let lines = self.lines(record);
+        let full = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.extend(lines);
+            buffer.len() >= self.batch_size
+        };
+        if full {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let batch = std::mem::take(&mut *self.buffer.lock().await);
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let result = self.send(batch.clone()).await;
+        if result.is_err() {
+            // Se reintenta en el siguiente flush, sin crecer sin límite
+            let mut buffer = self.buffer.lock().await;
+            if buffer.len() + batch.len() <= self.batch_size * 10 {
+                buffer.splice(0..0, batch);
+            } else {
+                warn!("Dropping {} metric line(s) after failed push", batch.len());
+            }
+        }
+        result
+    }
+}
+
+/// Escapa comas, espacios y signos igual en claves y valores de etiquetas
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::models::OutputStatus;
+    use obs_agent_core::domain::services::SystemContext;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn record() -> TelemetryRecord {
+        TelemetryRecord {
+            outputs: vec![OutputStatus {
+                name: "stream".to_string(),
+                active: true,
+                bytes: 1024,
+                ..Default::default()
+            }],
+            ..TelemetryRecord::from_context(SystemContext {
+                cpu_usage: 40.0,
+                timestamp: chrono::DateTime::from_timestamp(1_800_000_000, 0).unwrap(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_line_protocol() {
+        let sink = InfluxLineSink::new("http://localhost:8086/write?db=obs")
+            .with_tags([("host".to_string(), "studio pc".to_string())]);
+        let lines = sink.lines(&record());
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("obs_agent,host=studio\\ pc cpu_usage_percent=40,"));
+        assert!(lines[0].ends_with(" 1800000000000000000"));
+        assert!(lines[1].starts_with("obs_agent,host=studio\\ pc,output=stream obs_output_active=1,"));
+        assert!(lines[1].contains("obs_output_bytes=1024"));
+    }
+
+    #[tokio::test]
+    async fn test_batches_and_pushes_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (bodies_tx, mut bodies) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // Cabecera + cuerpo completo según Content-Length
+                loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| l.to_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                            .and_then(|l| l.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length || read == 0 {
+                            bodies_tx.send((head.to_string(), body.to_string())).unwrap();
+                            break;
+                        }
+                    }
+                }
+                stream.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n").await.unwrap();
+            }
+        });
+
+        let sink = InfluxLineSink::new(format!("http://{}/api/v2/write?bucket=obs", addr))
+            .with_token("secret")
+            .with_batch_size(3);
+
+        // 2 líneas por muestra: la segunda muestra supera el lote y se envía
+        sink.record(&record()).await.unwrap();
+        assert!(bodies.try_recv().is_err());
+        sink.record(&record()).await.unwrap();
+        let (head, body) = bodies.recv().await.unwrap();
+        assert!(head.starts_with("POST /api/v2/write?bucket=obs "));
+        assert!(head.contains("authorization: Token secret") || head.contains("Authorization: Token secret"));
+        assert_eq!(body.lines().count(), 4);
+
+        sink.flush().await.unwrap();
+        assert!(bodies.try_recv().is_err());
+        sink.record(&record()).await.unwrap();
+        sink.flush().await.unwrap();
+        assert_eq!(bodies.recv().await.unwrap().1.lines().count(), 2);
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use obs_agent_core::application::ports::{MetricsStore, TelemetrySink};
+use obs_agent_core::domain::models::{Anomaly, Metric, MetricPoint, RetentionPolicy, RetentionSummary, TimeRange};
+use obs_agent_core::domain::services::{HealthReport, TelemetryRecord};
+use super::sqlite::{from_millis, SqliteDatabase};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Histórico de métricas, anomalías y reportes de salud en SQLite
+pub struct SqliteMetricsStore {
+    db: SqliteDatabase,
+}
+
+impl SqliteMetricsStore {
+    /// Abre (o crea) la base de datos y aplica las migraciones pendientes
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let db = SqliteDatabase::open(path, "metrics store", &sqlx::migrate!("./migrations")).await?;
+        Ok(Self { db })
+    }
+
+    /// Ubicación por defecto (directorio de datos del usuario)
+    pub fn default_path() -> PathBuf {
+        SqliteDatabase::default_path("metrics.db")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.db.path
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for SqliteMetricsStore {
+    async fn record(&self, record: &TelemetryRecord) -> Result<()> {
+        let timestamp = record.timestamp.timestamp_millis();
+        let values = Metric::values(&record.context);
+
+        let mut tx = self.db.pool.begin().await?;
+        for (metric, value) in &values {
+            sqlx::query(
+                "INSERT INTO metric_samples (timestamp_ms, metric, value, min_value, max_value) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(timestamp)
+            .bind(metric.name())
+            .bind(value)
+            .bind(value)
+            .bind(value)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to store metric sample")?;
+        }
+        tx.commit().await?;
+
+        debug!("Stored {} metrics at {}", values.len(), record.timestamp);
+        Ok(())
+    }
+
+    /// Cada escritura se confirma en su transacción
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetricsStore for SqliteMetricsStore {
+    async fn record_anomalies(&self, anomalies: &[Anomaly]) -> Result<()> {
+        let mut tx = self.db.pool.begin().await?;
+        for anomaly in anomalies {
+            sqlx::query(
+                "INSERT OR REPLACE INTO anomalies (id, timestamp_ms, anomaly_type, severity, data) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(anomaly.id.to_string())
+            .bind(anomaly.timestamp.timestamp_millis())
+            .bind(format!("{:?}", anomaly.anomaly_type))
+            .bind(format!("{:?}", anomaly.severity))
+            .bind(serde_json::to_string(anomaly)?)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to store anomaly")?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn record_health(&self, report: &HealthReport) -> Result<()> {
+        sqlx::query("INSERT INTO health_reports (timestamp_ms, is_healthy, anomaly_count, data) VALUES (?, ?, ?, ?)")
+            .bind(report.timestamp.timestamp_millis())
+            .bind(report.is_healthy)
+            .bind(report.anomalies.len() as i64)
+            .bind(serde_json::to_string(report)?)
+            .execute(&self.db.pool)
+            .await
+            .context("Failed to store health report")?;
+        Ok(())
+    }
+
+    async fn query_metric(&self, metric: Metric, range: TimeRange, step: Option<Duration>) -> Result<Vec<MetricPoint>> {
+        let from = range.from.timestamp_millis();
+        let to = range.to.timestamp_millis();
+
+        let rows: Vec<(i64, f64, f64, f64, i64)> = match step.map(|s| (s.as_millis() as i64).max(1)) {
+            // Media ponderada por muestras: los agregados cuentan lo que resumen
+            Some(step_ms) => sqlx::query_as(
+                "SELECT (timestamp_ms / ?1) * ?1 AS bucket,
+                        SUM(value * sample_count) / SUM(sample_count),
+                        MIN(min_value), MAX(max_value), SUM(sample_count)
+                 FROM metric_samples
+                 WHERE metric = ?2 AND timestamp_ms >= ?3 AND timestamp_ms < ?4
+                 GROUP BY bucket ORDER BY bucket",
+            )
+            .bind(step_ms)
+            .bind(metric.name())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.db.pool)
+            .await,
+            None => sqlx::query_as(
+                "SELECT timestamp_ms, value, min_value, max_value, sample_count
+                 FROM metric_samples
+                 WHERE metric = ? AND timestamp_ms >= ? AND timestamp_ms < ?
+                 ORDER BY timestamp_ms",
+            )
+            .bind(metric.name())
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.db.pool)
+            .await,
+        }
+        .with_context(|| format!("Failed to query {}", metric))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(timestamp, avg, min, max, samples)| MetricPoint {
+                timestamp: from_millis(timestamp),
+                avg,
+                min,
+                max,
+                samples: samples as u64,
+            })
+            .collect())
+    }
+
+    async fn query_anomalies(&self, range: TimeRange) -> Result<Vec<Anomaly>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM anomalies WHERE timestamp_ms >= ? AND timestamp_ms < ? ORDER BY timestamp_ms",
+        )
+        .bind(range.from.timestamp_millis())
+        .bind(range.to.timestamp_millis())
+        .fetch_all(&self.db.pool)
+        .await
+        .context("Failed to query anomalies")?;
+
+        rows.iter()
+            .map(|(data,)| serde_json::from_str(data).context("Invalid stored anomaly"))
+            .collect()
+    }
+
+    async fn query_health(&self, range: TimeRange) -> Result<Vec<HealthReport>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM health_reports WHERE timestamp_ms >= ? AND timestamp_ms < ? ORDER BY timestamp_ms",
+        )
+        .bind(range.from.timestamp_millis())
+        .bind(range.to.timestamp_millis())
+        .fetch_all(&self.db.pool)
+        .await
+        .context("Failed to query health reports")?;
+
+        rows.iter()
+            .map(|(data,)| serde_json::from_str(data).context("Invalid stored health report"))
+            .collect()
+    }
+
+    async fn apply_retention(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<RetentionSummary> {
+        let now = now.timestamp_millis();
+        let interval_ms = (policy.rollup_interval.as_millis() as i64).max(1);
+        // Alineado al intervalo: un bucket nunca queda repartido entre dos pasadas
+        let raw_cutoff = (now - policy.raw_retention.as_millis() as i64).div_euclid(interval_ms) * interval_ms;
+        let rollup_cutoff = now - policy.rollup_retention.as_millis() as i64;
+
+        let mut tx = self.db.pool.begin().await?;
+        let rollups_created = sqlx::query(
+            "INSERT INTO metric_samples (timestamp_ms, metric, value, min_value, max_value, sample_count, resolution_secs)
+             SELECT (timestamp_ms / ?1) * ?1 AS bucket, metric,
+                    SUM(value * sample_count) / SUM(sample_count),
+                    MIN(min_value), MAX(max_value), SUM(sample_count), ?2
+             FROM metric_samples
+             WHERE resolution_secs = 0 AND timestamp_ms < ?3
+             GROUP BY bucket, metric",
+        )
+        .bind(interval_ms)
+        .bind(policy.rollup_interval.as_secs().max(1) as i64)
+        .bind(raw_cutoff)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to roll up metrics")?
+        .rows_affected();
+
+        let samples_rolled_up = sqlx::query("DELETE FROM metric_samples WHERE resolution_secs = 0 AND timestamp_ms < ?")
+            .bind(raw_cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        let mut rows_deleted = 0;
+        for table in ["metric_samples", "anomalies", "health_reports"] {
+            rows_deleted += sqlx::query(&format!("DELETE FROM {} WHERE timestamp_ms < ?", table))
+                .bind(rollup_cutoff)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to expire {}", table))?
+                .rows_affected();
+        }
+        tx.commit().await?;
+
+        let summary = RetentionSummary {
+            samples_rolled_up,
+            rollups_created,
+            rows_deleted,
+        };
+        if summary != RetentionSummary::default() {
+            info!("Metrics retention: {:?}", summary);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::models::{AnomalyType, Severity};
+    use obs_agent_core::domain::services::SystemContext;
+
+    async fn temp_store() -> (SqliteMetricsStore, PathBuf) {
+        let path = std::env::temp_dir().join(format!("obs-agent-{}.db", uuid::Uuid::new_v4()));
+        (SqliteMetricsStore::open(&path).await.unwrap(), path)
+    }
+
+    fn remove(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    fn sample(at: DateTime<Utc>, cpu_temp: f32) -> TelemetryRecord {
+        TelemetryRecord::from_context(SystemContext {
+            cpu_temp,
+            timestamp: at,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_store_and_query_history() {
+        let (store, path) = temp_store().await;
+        let start = from_millis(1_800_000_000_000);
+
+        // 4 minutos, una muestra cada 15 s: 60, 61, ... 75 °C
+        for i in 0..16 {
+            let at = start + chrono::Duration::seconds(i * 15);
+            store.record(&sample(at, 60.0 + i as f32)).await.unwrap();
+        }
+        let anomaly = Anomaly {
+            timestamp: start + chrono::Duration::seconds(200),
+            ..Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "CPU temperature 73°C")
+        };
+        store.record_anomalies(std::slice::from_ref(&anomaly)).await.unwrap();
+        store
+            .record_health(&HealthReport::from_anomalies(vec![anomaly.clone()], 8.0, anomaly.timestamp))
+            .await
+            .unwrap();
+
+        let range = TimeRange::new(start, start + chrono::Duration::minutes(10));
+        let raw = store.query_metric(Metric::CpuTempCelsius, range, None).await.unwrap();
+        assert_eq!(raw.len(), 16);
+        assert_eq!(raw[15].avg, 75.0);
+        // Sin sensor de GPU no se guarda nada
+        assert!(store.query_metric(Metric::GpuTempCelsius, range, None).await.unwrap().is_empty());
+
+        let per_minute = store
+            .query_metric(Metric::CpuTempCelsius, range, Some(Duration::from_secs(60)))
+            .await
+            .unwrap();
+        assert_eq!(per_minute.len(), 4);
+        assert_eq!((per_minute[0].min, per_minute[0].avg, per_minute[0].max), (60.0, 61.5, 63.0));
+        assert_eq!(per_minute[0].samples, 4);
+
+        let anomalies = store.query_anomalies(range).await.unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].id, anomaly.id);
+        let reports = store.query_health(range).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].anomalies[0].id, anomaly.id);
+
+        remove(&path);
+    }
+
+    #[tokio::test]
+    async fn test_retention_rolls_up_then_expires() {
+        let (store, path) = temp_store().await;
+        let start = from_millis(1_800_000_000_000);
+        for i in 0..8 {
+            let at = start + chrono::Duration::seconds(i * 15);
+            store.record(&sample(at, 50.0 + i as f32)).await.unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            raw_retention: Duration::from_secs(3600),
+            rollup_interval: Duration::from_secs(60),
+            rollup_retention: Duration::from_secs(24 * 3600),
+        };
+        let range = TimeRange::new(start, start + chrono::Duration::minutes(2));
+
+        // Dentro de la retención cruda no cambia nada
+        let summary = store.apply_retention(&policy, start + chrono::Duration::minutes(30)).await.unwrap();
+        assert_eq!(summary, RetentionSummary::default());
+
+        let summary = store.apply_retention(&policy, start + chrono::Duration::hours(2)).await.unwrap();
+        let samples_per_record = Metric::values(&sample(start, 50.0).context).len() as u64;
+        assert_eq!(summary.samples_rolled_up, 8 * samples_per_record);
+        assert_eq!(summary.rollups_created, 2 * samples_per_record);
+
+        // Los agregados conservan media, extremos y número de muestras
+        let points = store.query_metric(Metric::CpuTempCelsius, range, None).await.unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].min, points[0].avg, points[0].max, points[0].samples), (50.0, 51.5, 53.0, 4));
+        let whole = store
+            .query_metric(Metric::CpuTempCelsius, range, Some(Duration::from_secs(3600)))
+            .await
+            .unwrap();
+        assert_eq!((whole[0].avg, whole[0].samples), (53.5, 8));
+
+        let summary = store.apply_retention(&policy, start + chrono::Duration::days(2)).await.unwrap();
+        assert_eq!(summary.rows_deleted, 2 * samples_per_record);
+        assert!(store.query_metric(Metric::CpuTempCelsius, range, None).await.unwrap().is_empty());
+
+        remove(&path);
+    }
+}
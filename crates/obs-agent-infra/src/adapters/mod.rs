@@ -1,9 +1,38 @@
 pub mod obs_adapter;
 pub mod ai_adapter;
+pub mod auth_store;
+pub mod bandwidth;
+pub mod fleet_client;
+pub mod influx_sink;
+pub mod metrics_store;
 pub mod monitor_adapter;
+pub mod network_adapter;
 pub mod plugin_adapter;
+pub mod telemetry_recorder;
+pub mod simulation_adapter;
+mod sqlite;
+pub mod sysfs;
+pub mod nvidia_smi;
+pub mod openmetrics;
+pub mod process_monitor;
+pub mod sampler;
+pub mod statsd_sink;
 
 pub use obs_adapter::*;
 pub use ai_adapter::*;
+pub use auth_store::*;
+pub use bandwidth::*;
+pub use fleet_client::*;
+pub use influx_sink::*;
+pub use metrics_store::*;
 pub use monitor_adapter::*;
+pub use network_adapter::*;
 pub use plugin_adapter::*;
+pub use telemetry_recorder::*;
+pub use simulation_adapter::*;
+pub use sysfs::*;
+pub use nvidia_smi::*;
+pub use openmetrics::*;
+pub use process_monitor::*;
+pub use sampler::*;
+pub use statsd_sink::*;
@@ -1,53 +1,200 @@
 use anyhow::{Context, Result};
 use obs_agent_core::application::ports::MonitorPort;
 use obs_agent_core::domain::models::{
-    CPUInfo, EncoderType, GPUInfo, GPUVendor, HardwareInfo, RAMInfo,
+    CoreTemperature, CPUInfo, CpuFrequencyStats, EncoderType, GPUInfo, HardwareInfo, ObsProcessInfo,
+    PowerProfile, PowerStatus, RAMInfo,
 };
-use sysinfo::System;
+use super::nvidia_smi::{CommandRunner, NvidiaSmi, NvidiaSmiGpu, SystemCommandRunner};
+use super::process_monitor::ObsProcessMonitor;
+use super::sysfs::{DrmGpu, Sysfs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{System, MINIMUM_CPU_UPDATE_INTERVAL};
 use tracing::{debug, info};
 
+/// Cada cuánto se vuelve a preguntar el perfil a `powerprofilesctl`
+const POWER_PROFILE_TTL: Duration = Duration::from_secs(60);
+
 /// Adapter para monitorear hardware del sistema
 pub struct MonitorAdapter {
-    system: System,
+    system: Mutex<System>,
+    /// Último refresco de CPU y memoria
+    last_refresh: Mutex<Instant>,
+    /// Ya hubo un refresco completo tras el de creación
+    primed: AtomicBool,
+    sysfs: Sysfs,
+    nvidia_smi: NvidiaSmi,
+    commands: Arc<dyn CommandRunner>,
+    obs_process: ObsProcessMonitor,
+    /// Última respuesta de `powerprofilesctl` (también si no está instalado)
+    power_profile: Mutex<Option<(Instant, Option<PowerProfile>)>>,
 }
 
 impl MonitorAdapter {
+    /// No bloquea: los contadores de CPU se ceban en la primera lectura (ver `refresh`)
     pub fn new() -> Self {
-        let system = System::new_all();
-        Self { system }
+        Self {
+            system: Mutex::new(System::new_all()),
+            last_refresh: Mutex::new(Instant::now()),
+            primed: AtomicBool::new(false),
+            sysfs: Sysfs::default(),
+            nvidia_smi: NvidiaSmi::default(),
+            commands: Arc::new(SystemCommandRunner),
+            obs_process: ObsProcessMonitor::default(),
+            power_profile: Mutex::new(None),
+        }
+    }
+
+    /// Cambia la raíz de sysfs (por defecto `/sys`)
+    pub fn with_sysfs_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.sysfs = Sysfs::new(root);
+        self
+    }
+
+    /// Cambia el directorio de reportes de crash de OBS
+    pub fn with_obs_crash_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.obs_process = self.obs_process.with_crash_dir(dir);
+        self
     }
 
-    #[allow(dead_code)]
-    fn refresh(&mut self) {
-        self.system.refresh_all();
+    /// Refresca uso de CPU y memoria.
+    ///
+    /// sysinfo calcula el uso de CPU entre dos refrescos, así que se ignoran los
+    /// que llegan antes de `MINIMUM_CPU_UPDATE_INTERVAL` (se reutiliza el último).
+    /// La primera lectura tras crear el adapter completa ese intervalo una sola
+    /// vez para no devolver el uso de arranque.
+    pub fn refresh(&self) {
+        let mut last = self.last_refresh.lock().unwrap();
+        let elapsed = last.elapsed();
+        if elapsed < MINIMUM_CPU_UPDATE_INTERVAL {
+            if self.primed.load(Ordering::Acquire) {
+                return;
+            }
+            std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL - elapsed);
+        }
+
+        let mut system = self.system.lock().unwrap();
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        *last = Instant::now();
+        self.primed.store(true, Ordering::Release);
+    }
+
+    /// Cambia el ejecutor de comandos externos (p.ej. `nvidia-smi`)
+    pub fn with_command_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.nvidia_smi = NvidiaSmi::with_runner(Arc::clone(&runner));
+        self.commands = runner;
+        self
     }
 
-    fn detect_gpu_vendor(name: &str) -> GPUVendor {
-        let name_lower = name.to_lowercase();
-        if name_lower.contains("nvidia") || name_lower.contains("geforce") || name_lower.contains("rtx") {
-            GPUVendor::NVIDIA
-        } else if name_lower.contains("amd") || name_lower.contains("radeon") {
-            GPUVendor::AMD
-        } else if name_lower.contains("intel") {
-            GPUVendor::Intel
+    /// Perfil de power-profiles-daemon, consultado como mucho una vez por `POWER_PROFILE_TTL`
+    fn daemon_power_profile(&self) -> Option<PowerProfile> {
+        let mut cached = self.power_profile.lock().unwrap();
+        if let Some((at, profile)) = *cached {
+            if at.elapsed() < POWER_PROFILE_TTL {
+                return profile;
+            }
+        }
+
+        let profile = self
+            .commands
+            .run("powerprofilesctl", &["get"])
+            .map_err(|e| debug!("powerprofilesctl not available: {}", e))
+            .ok()
+            .and_then(|name| PowerProfile::parse(&name));
+        *cached = Some((Instant::now(), profile));
+        profile
+    }
+
+    /// Todas las GPUs detectadas (sysfs en Linux, completado con `nvidia-smi`)
+    pub fn detect_gpus(&self) -> Vec<GPUInfo> {
+        let drm = self.sysfs.gpus();
+        Self::merge_gpus(&drm, &self.query_nvidia_smi(&drm))
+    }
+
+    /// GPUs detectadas y el índice de la que usa OBS para renderizar
+    /// (la de arranque, que es el adaptador por defecto; si no, la primera)
+    fn gpu_inventory(&self) -> (Vec<GPUInfo>, Option<usize>) {
+        let drm = self.sysfs.gpus();
+        let gpus = Self::merge_gpus(&drm, &self.query_nvidia_smi(&drm));
+        let render_gpu = if gpus.is_empty() {
+            None
         } else {
-            GPUVendor::Unknown
+            Some(drm.iter().position(|gpu| gpu.boot_vga).unwrap_or(0))
+        };
+        (gpus, render_gpu)
+    }
+
+    /// `nvidia-smi` solo se lanza si hay una GPU con el driver propietario
+    /// (que no expone hwmon) o si no hay sysfs (p.ej. Windows)
+    fn query_nvidia_smi(&self, drm: &[DrmGpu]) -> Vec<NvidiaSmiGpu> {
+        let needed = drm.is_empty() || drm.iter().any(|gpu| gpu.driver.as_deref() == Some("nvidia"));
+        if !needed {
+            return vec![];
         }
+
+        self.nvidia_smi.query().unwrap_or_else(|e| {
+            debug!("nvidia-smi not available: {}", e);
+            vec![]
+        })
     }
 
-    fn detect_encoder(gpu_vendor: GPUVendor, cpu_cores: usize) -> EncoderType {
-        match gpu_vendor {
-            GPUVendor::NVIDIA => EncoderType::NVENC,
-            GPUVendor::AMD => EncoderType::AMF,
-            GPUVendor::Intel if cpu_cores >= 4 => EncoderType::QSV,
-            _ => EncoderType::X264,
+    fn merge_gpus(drm: &[DrmGpu], smi: &[NvidiaSmiGpu]) -> Vec<GPUInfo> {
+        if drm.is_empty() {
+            return smi.iter().map(NvidiaSmiGpu::to_gpu_info).collect();
         }
+
+        drm.iter()
+            .map(|gpu| {
+                gpu.pci_slot
+                    .as_deref()
+                    .and_then(|slot| smi.iter().find(|s| s.matches_pci_slot(slot)))
+                    .map(NvidiaSmiGpu::to_gpu_info)
+                    .unwrap_or_else(|| gpu.to_gpu_info())
+            })
+            .collect()
     }
 
-    fn recommend_preset(gpu_vendor: GPUVendor, cpu_cores: usize) -> String {
-        match gpu_vendor {
-            GPUVendor::NVIDIA => "p5".to_string(), // NVENC preset
-            GPUVendor::AMD => "balanced".to_string(),
+    #[cfg(feature = "nvidia")]
+    fn nvml_gpu_temp() -> Result<f32> {
+        use nvml_wrapper::Nvml;
+        let nvml = Nvml::init().context("Failed to initialize NVML")?;
+        let mut hottest = None;
+        for index in 0..nvml.device_count().context("Failed to count GPU devices")? {
+            let device = nvml.device_by_index(index).context("Failed to get GPU device")?;
+            let temp = device.temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+                .context("Failed to get GPU temperature")?;
+            hottest = Some(hottest.map_or(temp, |t: u32| t.max(temp)));
+        }
+        hottest.map(|t| t as f32).context("No NVIDIA GPU found")
+    }
+
+    fn disk_info(disk: &sysinfo::Disk) -> super::DiskInfo {
+        let total_gb = disk.total_space() as f64 / 1_073_741_824.0;
+        let free_gb = disk.available_space() as f64 / 1_073_741_824.0;
+        let used_percent = ((total_gb - free_gb) / total_gb) * 100.0;
+
+        super::DiskInfo {
+            total_gb,
+            free_gb,
+            used_percent,
+            mount_point: Some(disk.mount_point().display().to_string()),
+        }
+    }
+
+    /// Montaje más específico que contiene `path`
+    fn mount_containing<'a>(path: &Path, mounts: impl Iterator<Item = &'a Path>) -> Option<&'a Path> {
+        mounts
+            .filter(|mount| path.starts_with(mount))
+            .max_by_key(|mount| mount.components().count())
+    }
+
+    fn recommend_preset(encoder: EncoderType, cpu_cores: usize) -> String {
+        match encoder {
+            EncoderType::NVENC => "p5".to_string(), // NVENC preset
+            EncoderType::AMF => "balanced".to_string(),
             _ if cpu_cores >= 8 => "medium".to_string(),
             _ => "veryfast".to_string(),
         }
@@ -89,39 +236,90 @@ impl Default for MonitorAdapter {
 }
 
 impl MonitorPort for MonitorAdapter {
-    fn get_cpu_temp(&self) -> Result<f32> {
-        // sysinfo no proporciona temperaturas directamente
-        // Necesitaríamos usar una librería específica de plataforma
-        debug!("CPU temperature monitoring not yet implemented");
-        Ok(0.0)
+    fn get_cpu_temp(&self) -> Result<Option<f32>> {
+        if !cfg!(target_os = "linux") {
+            // sysinfo no proporciona temperaturas directamente
+            debug!("CPU temperature monitoring is only implemented on Linux");
+            return Ok(None);
+        }
+
+        let temp = self.sysfs.cpu_temperatures().representative();
+        if temp.is_none() {
+            debug!("No CPU temperature sensor found under {}", self.sysfs.root().display());
+        }
+        Ok(temp)
+    }
+
+    fn get_cpu_core_temps(&self) -> Result<Vec<CoreTemperature>> {
+        Ok(self.sysfs.cpu_temperatures().cores)
+    }
+
+    fn get_cpu_frequency(&self) -> Result<Option<CpuFrequencyStats>> {
+        let mut cores = self.sysfs.cpu_frequencies();
+        if cores.is_empty() {
+            return Ok(None);
+        }
+
+        // sysinfo lista las CPUs lógicas en el mismo orden que cpufreq (cpu0, cpu1...)
+        self.refresh();
+        let system = self.system.lock().unwrap();
+        for core in &mut cores {
+            core.usage_percent = system.cpus().get(core.core).map(|cpu| cpu.cpu_usage());
+        }
+        Ok(Some(CpuFrequencyStats { cores }))
     }
 
-    fn get_gpu_temp(&self) -> Result<f32> {
+    fn get_gpu_temp(&self) -> Result<Option<f32>> {
         #[cfg(feature = "nvidia")]
-        {
-            use nvml_wrapper::Nvml;
-            let nvml = Nvml::init().context("Failed to initialize NVML")?;
-            let device = nvml.device_by_index(0).context("Failed to get GPU device")?;
-            let temp = device.temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
-                .context("Failed to get GPU temperature")?;
-            return Ok(temp as f32);
+        match Self::nvml_gpu_temp() {
+            Ok(temp) => return Ok(Some(temp)),
+            Err(e) => debug!("NVML unavailable, falling back to sysfs/nvidia-smi: {}", e),
         }
 
-        #[cfg(not(feature = "nvidia"))]
+        // amdgpu, i915 y xe exponen la temperatura vía hwmon; NVIDIA vía nvidia-smi
+        match self
+            .detect_gpus()
+            .iter()
+            .filter_map(|gpu| gpu.temperature_c)
+            .max_by(|a, b| a.total_cmp(b))
         {
-            debug!("GPU temperature monitoring requires 'nvidia' feature");
-            Ok(0.0)
+            Some(temp) => Ok(Some(temp)),
+            None => {
+                debug!("No GPU temperature sensor found");
+                Ok(None)
+            }
         }
     }
 
+    fn get_gpus(&self) -> Result<Vec<GPUInfo>> {
+        Ok(self.detect_gpus())
+    }
+
     fn get_cpu_usage(&self) -> Result<f32> {
-        let usage = self.system.global_cpu_info().cpu_usage();
+        self.refresh();
+        let usage = self.system.lock().unwrap().global_cpu_info().cpu_usage();
         Ok(usage)
     }
 
+    fn get_power_status(&self) -> Result<Option<PowerStatus>> {
+        if !cfg!(target_os = "linux") {
+            debug!("Power source monitoring is only implemented on Linux");
+            return Ok(None);
+        }
+
+        let mut power = self.sysfs.power_status();
+        if power.profile.is_none() {
+            // Sin platform_profile, power-profiles-daemon gestiona el perfil por su cuenta
+            power.profile = self.daemon_power_profile();
+        }
+        Ok(Some(power))
+    }
+
     fn get_memory_info(&self) -> Result<RAMInfo> {
-        let total_gb = self.system.total_memory() as f64 / 1_073_741_824.0;
-        let available_gb = self.system.available_memory() as f64 / 1_073_741_824.0;
+        self.refresh();
+        let system = self.system.lock().unwrap();
+        let total_gb = system.total_memory() as f64 / 1_073_741_824.0;
+        let available_gb = system.available_memory() as f64 / 1_073_741_824.0;
         let used_percent = ((total_gb - available_gb) / total_gb) * 100.0;
 
         Ok(RAMInfo {
@@ -136,69 +334,61 @@ impl MonitorPort for MonitorAdapter {
         let disks = sysinfo::Disks::new_with_refreshed_list();
 
         if let Some(disk) = disks.iter().next() {
-            let total_gb = disk.total_space() as f64 / 1_073_741_824.0;
-            let free_gb = disk.available_space() as f64 / 1_073_741_824.0;
-            let used_percent = ((total_gb - free_gb) / total_gb) * 100.0;
-
-            Ok(super::DiskInfo {
-                total_gb,
-                free_gb,
-                used_percent,
-            })
+            Ok(Self::disk_info(disk))
         } else {
             anyhow::bail!("No disks found");
         }
     }
 
+    fn get_disk_space_for(&self, path: &Path) -> Result<super::DiskInfo> {
+        // Resolver symlinks para dar con el montaje real
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+
+        let mount = Self::mount_containing(&path, disks.iter().map(|d| d.mount_point()))
+            .with_context(|| format!("No disk found containing {}", path.display()))?;
+        let disk = disks
+            .iter()
+            .find(|d| d.mount_point() == mount)
+            .context("Disk disappeared while resolving mount")?;
+
+        debug!("{} is on {}", path.display(), mount.display());
+        Ok(Self::disk_info(disk))
+    }
+
+    fn get_obs_process(&self) -> Result<Option<ObsProcessInfo>> {
+        Ok(Some(self.obs_process.sample()))
+    }
+
     fn detect_hardware(&self) -> Result<HardwareInfo> {
         info!("Detecting hardware...");
 
         // CPU Info
-        let cpu = self.system.cpus().first().context("No CPU found")?;
-        let cpu_info = CPUInfo {
-            name: cpu.name().to_string(),
-            brand: cpu.brand().to_string(),
-            cores_physical: self.system.physical_core_count().unwrap_or(1),
-            cores_logical: self.system.cpus().len(),
-            frequency_mhz: cpu.frequency() as f64,
-            arch: std::env::consts::ARCH.to_string(),
+        let cpu_info = {
+            let system = self.system.lock().unwrap();
+            let cpu = system.cpus().first().context("No CPU found")?;
+            CPUInfo {
+                name: cpu.name().to_string(),
+                brand: cpu.brand().to_string(),
+                cores_physical: system.physical_core_count().unwrap_or(1),
+                cores_logical: system.cpus().len(),
+                frequency_mhz: cpu.frequency() as f64,
+                arch: std::env::consts::ARCH.to_string(),
+            }
         };
 
-        // GPU Info (básico - necesitaría vulkano o wgpu para detección completa)
-        let gpu_name = if cfg!(target_os = "windows") {
-            // En Windows podríamos usar WMI
-            "Unknown GPU".to_string()
-        } else {
-            "Unknown GPU".to_string()
-        };
-
-        let gpu_vendor = Self::detect_gpu_vendor(&gpu_name);
-        let gpu_info = Some(GPUInfo {
-            name: gpu_name,
-            vendor: gpu_vendor,
-            memory_total_mb: 0,
-            memory_free_mb: 0,
-            driver_version: "Unknown".to_string(),
-            supports_nvenc: gpu_vendor == GPUVendor::NVIDIA,
-            supports_nvenc_hevc: gpu_vendor == GPUVendor::NVIDIA,
-            supports_amf: gpu_vendor == GPUVendor::AMD,
-            supports_qsv: gpu_vendor == GPUVendor::Intel,
-        });
+        // GPU Info: sysfs en Linux, nvidia-smi en cualquier plataforma
+        let (gpus, render_gpu) = self.gpu_inventory();
 
         // RAM Info
-        let total_gb = self.system.total_memory() as f64 / 1_073_741_824.0;
-        let available_gb = self.system.available_memory() as f64 / 1_073_741_824.0;
-        let ram_info = RAMInfo {
-            total_gb,
-            available_gb,
-            used_percent: ((total_gb - available_gb) / total_gb) * 100.0,
-        };
+        let ram_info = self.get_memory_info()?;
 
         // Recomendaciones
-        let has_hw_encoder = gpu_vendor != GPUVendor::Unknown;
-        let recommended_encoder = Self::detect_encoder(gpu_vendor, cpu_info.cores_physical);
-        let recommended_preset = Self::recommend_preset(gpu_vendor, cpu_info.cores_physical);
-        let recommended_resolution = Self::recommend_resolution(total_gb, has_hw_encoder);
+        let (recommended_encoder, encode_gpu) =
+            EncoderType::best_for(&gpus, render_gpu, cpu_info.cores_physical);
+        let has_hw_encoder = recommended_encoder != EncoderType::X264;
+        let recommended_preset = Self::recommend_preset(recommended_encoder, cpu_info.cores_physical);
+        let recommended_resolution = Self::recommend_resolution(ram_info.total_gb, has_hw_encoder);
         let recommended_fps = Self::recommend_fps(cpu_info.cores_physical, has_hw_encoder);
         let recommended_bitrate = Self::recommend_bitrate(recommended_resolution, recommended_fps);
 
@@ -207,17 +397,20 @@ impl MonitorPort for MonitorAdapter {
             os_version: System::os_version().unwrap_or_else(|| "Unknown".to_string()),
             hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
             cpu: cpu_info,
-            gpu: gpu_info,
+            gpus,
+            render_gpu,
+            encode_gpu,
             ram: ram_info,
             recommended_encoder,
             recommended_preset,
             recommended_resolution,
             recommended_fps,
             recommended_bitrate,
+            measured_upload_kbps: None,
         };
 
-        info!("Hardware detection complete: {} cores, {:.1} GB RAM",
-            hardware.cpu.cores_physical, hardware.ram.total_gb);
+        info!("Hardware detection complete: {} cores, {:.1} GB RAM, {} GPU(s)",
+            hardware.cpu.cores_physical, hardware.ram.total_gb, hardware.gpus.len());
 
         Ok(hardware)
     }
@@ -229,6 +422,7 @@ pub use obs_agent_core::application::ports::DiskInfo;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use obs_agent_core::domain::models::GPUVendor;
 
     #[test]
     fn test_hardware_detection() {
@@ -238,4 +432,71 @@ mod tests {
         assert!(hardware.cpu.cores_physical > 0);
         assert!(hardware.ram.total_gb > 0.0);
     }
+
+    #[test]
+    fn test_mount_containing_recording_path() {
+        let mounts = [Path::new("/"), Path::new("/home"), Path::new("/mnt/videos")];
+        let mount = |path: &str| MonitorAdapter::mount_containing(Path::new(path), mounts.iter().copied());
+
+        assert_eq!(mount("/mnt/videos/obs/2026-10-18.mkv"), Some(Path::new("/mnt/videos")));
+        assert_eq!(mount("/home/streamer/Videos"), Some(Path::new("/home")));
+        assert_eq!(mount("/var/tmp"), Some(Path::new("/")));
+        // "/mnt/videos2" no está dentro de "/mnt/videos"
+        assert_eq!(mount("/mnt/videos2"), Some(Path::new("/")));
+    }
+
+    struct FixtureRunner;
+
+    impl CommandRunner for FixtureRunner {
+        fn run(&self, _: &str, _: &[&str]) -> Result<String> {
+            Ok(include_str!("../../tests/fixtures/nvidia-smi-rtx3080.xml").to_string())
+        }
+    }
+
+    #[test]
+    fn test_nvidia_smi_fallback_for_proprietary_driver() {
+        let root = std::env::temp_dir().join(format!("obs-agent-sysfs-{}", uuid::Uuid::new_v4()));
+        for (card, vendor, uevent) in [
+            ("card0", "0x8086", "DRIVER=i915\nPCI_SLOT_NAME=0000:00:02.0"),
+            ("card1", "0x10de", "DRIVER=nvidia\nPCI_SLOT_NAME=0000:01:00.0"),
+        ] {
+            let device = root.join("class/drm").join(card).join("device");
+            std::fs::create_dir_all(&device).unwrap();
+            std::fs::write(device.join("vendor"), vendor).unwrap();
+            std::fs::write(device.join("uevent"), uevent).unwrap();
+        }
+
+        let adapter = MonitorAdapter::new()
+            .with_sysfs_root(&root)
+            .with_command_runner(Arc::new(FixtureRunner));
+        let gpus = adapter.detect_gpus();
+        let temp = adapter.get_gpu_temp().unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].vendor, GPUVendor::Intel);
+        assert_eq!(gpus[1].name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(gpus[1].encoder_sessions, Some(2));
+        assert_eq!(temp, Some(67.0));
+    }
+
+    #[derive(Default)]
+    struct CountingRunner(std::sync::atomic::AtomicU32);
+
+    impl CommandRunner for CountingRunner {
+        fn run(&self, _: &str, _: &[&str]) -> Result<String> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok("power-saver\n".to_string())
+        }
+    }
+
+    #[test]
+    fn test_power_profile_daemon_is_cached() {
+        let runner = Arc::new(CountingRunner::default());
+        let adapter = MonitorAdapter::new().with_command_runner(Arc::clone(&runner) as Arc<dyn CommandRunner>);
+
+        assert_eq!(adapter.daemon_power_profile(), Some(PowerProfile::PowerSaver));
+        assert_eq!(adapter.daemon_power_profile(), Some(PowerProfile::PowerSaver));
+        assert_eq!(runner.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
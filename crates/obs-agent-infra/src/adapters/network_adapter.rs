@@ -0,0 +1,258 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use obs_agent_core::application::ports::NetworkPort;
+use obs_agent_core::domain::models::{
+    BandwidthTest, IngestEndpoint, InterfaceStats, LatencyStats, NetworkStats,
+};
+use super::bandwidth::BandwidthProbe;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::Networks;
+use tokio::net::TcpStream;
+use tracing::debug;
+
+/// Intervalo por defecto entre mediciones de latencia al servidor de ingesta
+const LATENCY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Adapter para monitorear interfaces de red y la latencia al servidor de ingesta
+pub struct NetworkAdapter {
+    networks: Mutex<Networks>,
+    last_refresh: Mutex<Instant>,
+    /// Capacidad de subida configurada o medida con la última prueba de subida
+    upload_capacity: Mutex<Option<u32>>,
+    /// Última medición de latencia; se reutiliza durante `latency_interval`
+    latency: Mutex<Option<(Instant, LatencyStats)>>,
+    latency_interval: Duration,
+    probe_attempts: u32,
+    connect_timeout: Duration,
+}
+
+impl NetworkAdapter {
+    pub fn new() -> Self {
+        Self {
+            networks: Mutex::new(Networks::new_with_refreshed_list()),
+            last_refresh: Mutex::new(Instant::now()),
+            upload_capacity: Mutex::new(None),
+            latency: Mutex::new(None),
+            latency_interval: LATENCY_INTERVAL,
+            probe_attempts: 5,
+            connect_timeout: Duration::from_secs(2),
+        }
+    }
+
+    /// Cambia el número de conexiones de prueba y su timeout
+    pub fn with_probe(mut self, attempts: u32, connect_timeout: Duration) -> Self {
+        self.probe_attempts = attempts.max(1);
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Fija la capacidad de subida conocida (kbps) hasta la próxima prueba de subida
+    pub fn with_upload_capacity(self, kbps: u32) -> Self {
+        *self.upload_capacity.lock().unwrap() = Some(kbps).filter(|kbps| *kbps > 0);
+        self
+    }
+
+    /// Cambia cada cuánto se vuelve a medir la latencia al servidor de ingesta
+    pub fn with_latency_interval(mut self, interval: Duration) -> Self {
+        self.latency_interval = interval;
+        self
+    }
+
+    /// Tráfico de cada interfaz (sin loopback) desde la muestra anterior
+    pub fn interface_stats(&self) -> Vec<InterfaceStats> {
+        let mut networks = self.networks.lock().unwrap();
+        networks.refresh();
+
+        let now = Instant::now();
+        let elapsed = {
+            let mut last = self.last_refresh.lock().unwrap();
+            let elapsed = now.duration_since(*last).as_secs_f64();
+            *last = now;
+            elapsed
+        };
+        let kbps = |bytes: u64| if elapsed > 0.0 { bytes as f64 * 8.0 / 1000.0 / elapsed } else { 0.0 };
+
+        let mut interfaces: Vec<InterfaceStats> = networks
+            .list()
+            .iter()
+            .filter(|(name, _)| !is_loopback(name))
+            .map(|(name, data)| InterfaceStats {
+                name: name.clone(),
+                tx_kbps: kbps(data.transmitted()),
+                rx_kbps: kbps(data.received()),
+                tx_packets: data.packets_transmitted(),
+                rx_packets: data.packets_received(),
+                tx_errors: data.errors_on_transmitted(),
+                rx_errors: data.errors_on_received(),
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        interfaces
+    }
+
+    /// Mide la latencia de conexión TCP (handshake) al servidor de ingesta
+    pub async fn probe_latency(&self, endpoint: &IngestEndpoint) -> LatencyStats {
+        let mut samples = Vec::new();
+        let mut failures = 0;
+
+        for _ in 0..self.probe_attempts {
+            let start = Instant::now();
+            match tokio::time::timeout(
+                self.connect_timeout,
+                TcpStream::connect((endpoint.host.as_str(), endpoint.port)),
+            )
+            .await
+            {
+                Ok(Ok(_)) => samples.push(start.elapsed().as_secs_f64() * 1000.0),
+                Ok(Err(e)) => {
+                    debug!("Connection to {} failed: {}", endpoint, e);
+                    failures += 1;
+                }
+                Err(_) => {
+                    debug!("Connection to {} timed out", endpoint);
+                    failures += 1;
+                }
+            }
+        }
+
+        LatencyStats::from_samples(endpoint.to_string(), &samples, failures)
+    }
+
+    /// Latencia reciente al servidor de ingesta, midiéndola solo si la
+    /// anterior caducó o era de otro servidor
+    async fn cached_latency(&self, endpoint: &IngestEndpoint) -> LatencyStats {
+        let name = endpoint.to_string();
+        if let Some((at, latency)) = self.latency.lock().unwrap().as_ref() {
+            if latency.endpoint == name && at.elapsed() < self.latency_interval {
+                return latency.clone();
+            }
+        }
+
+        let latency = self.probe_latency(endpoint).await;
+        *self.latency.lock().unwrap() = Some((Instant::now(), latency.clone()));
+        latency
+    }
+}
+
+impl Default for NetworkAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NetworkPort for NetworkAdapter {
+    async fn sample(&self, ingest: Option<&IngestEndpoint>) -> Result<NetworkStats> {
+        let interfaces = self.interface_stats();
+        let latency = match ingest {
+            Some(endpoint) => Some(self.cached_latency(endpoint).await),
+            None => None,
+        };
+
+        let mut stats = NetworkStats {
+            interfaces,
+            latency,
+            estimated_upload_kbps: None,
+        };
+        let capacity = *self.upload_capacity.lock().unwrap();
+        stats.estimated_upload_kbps = capacity.and_then(|capacity| {
+            NetworkStats::estimate_upload_kbps(capacity, stats.latency.as_ref(), stats.tx_error_ratio())
+        });
+
+        debug!(
+            "Network: tx {:.0} kbps, rx {:.0} kbps, estimated upload {:?} kbps",
+            stats.tx_kbps(),
+            stats.rx_kbps(),
+            stats.estimated_upload_kbps
+        );
+        Ok(stats)
+    }
+
+    async fn bandwidth_test(&self, endpoint: &str, duration: Duration) -> Result<BandwidthTest> {
+        let test = BandwidthProbe::new(duration).run(endpoint).await?;
+        *self.upload_capacity.lock().unwrap() = Some(test.sustained_upload_kbps()).filter(|kbps| *kbps > 0);
+        Ok(test)
+    }
+}
+
+/// `lo` (Linux), `lo0` (macOS), "Loopback Pseudo-Interface 1" (Windows)
+fn is_loopback(name: &str) -> bool {
+    name == "lo" || name.starts_with("lo0") || name.to_lowercase().contains("loopback")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_latency_to_local_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((_socket, _)) = listener.accept().await {}
+        });
+
+        let adapter = NetworkAdapter::new().with_probe(4, Duration::from_secs(1));
+        let endpoint = IngestEndpoint::parse(&format!("rtmp://127.0.0.1:{}/live", port)).unwrap();
+        let stats = adapter.sample(Some(&endpoint)).await.unwrap();
+
+        let latency = stats.latency.unwrap();
+        assert_eq!(latency.endpoint, format!("127.0.0.1:{}", port));
+        assert_eq!(latency.attempts, 4);
+        assert_eq!(latency.failures, 0);
+        assert!(latency.min_ms <= latency.avg_ms && latency.avg_ms <= latency.max_ms);
+        assert!(stats.interfaces.iter().all(|i| !is_loopback(&i.name)));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_ingest_counts_failures() {
+        // Puerto libre: la conexión se rechaza
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let adapter = NetworkAdapter::new().with_probe(3, Duration::from_secs(1));
+        let endpoint = IngestEndpoint {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+        let latency = adapter.probe_latency(&endpoint).await;
+
+        assert_eq!(latency.failures, 3);
+        assert_eq!(latency.failure_ratio(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_latency_is_cached_between_samples() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            while let Ok((_socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let adapter = NetworkAdapter::new().with_probe(2, Duration::from_secs(1));
+        let endpoint = IngestEndpoint::parse(&format!("rtmp://127.0.0.1:{}/live", port)).unwrap();
+        adapter.sample(Some(&endpoint)).await.unwrap();
+        adapter.sample(Some(&endpoint)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_upload_estimate_needs_known_capacity() {
+        let unknown = NetworkAdapter::new().sample(None).await.unwrap();
+        assert_eq!(unknown.estimated_upload_kbps, None);
+
+        let configured = NetworkAdapter::new().with_upload_capacity(6000).sample(None).await.unwrap();
+        assert!(configured.estimated_upload_kbps.is_some_and(|kbps| (1800..=6000).contains(&kbps)));
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{Context, Result};
+use obs_agent_core::domain::models::{GPUInfo, GPUVendor};
+use std::process::Command;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Ejecuta comandos externos (inyectable para tests)
+pub trait CommandRunner: Send + Sync {
+    /// Ejecuta `program` con `args` y devuelve su stdout
+    fn run(&self, program: &str, args: &[&str]) -> Result<String>;
+}
+
+/// Runner que lanza procesos reales
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run {}", program))?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        String::from_utf8(output.stdout).with_context(|| format!("{} produced invalid UTF-8", program))
+    }
+}
+
+/// GPU reportada por `nvidia-smi -q -x`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NvidiaSmiGpu {
+    pub name: String,
+    /// Dirección PCI tal como la da nvidia-smi (p.ej. "00000000:01:00.0")
+    pub pci_bus_id: String,
+    /// Identificador PCI `vendor:device`
+    pub pci_id: Option<String>,
+    pub driver_version: String,
+    pub temperature_c: Option<f32>,
+    pub gpu_util_percent: Option<f32>,
+    /// `None` si la GPU no tiene NVENC
+    pub encoder_util_percent: Option<f32>,
+    pub encoder_sessions: Option<u32>,
+    pub vram_total_mb: Option<u64>,
+    pub vram_used_mb: Option<u64>,
+    pub vram_free_mb: Option<u64>,
+}
+
+impl NvidiaSmiGpu {
+    /// Compara con una dirección PCI de sysfs ("0000:01:00.0")
+    pub fn matches_pci_slot(&self, slot: &str) -> bool {
+        let bus = |id: &str| id.rsplitn(3, ':').take(2).collect::<Vec<_>>().join(":").to_lowercase();
+        bus(&self.pci_bus_id) == bus(slot)
+    }
+
+    pub fn to_gpu_info(&self) -> GPUInfo {
+        // nvidia-smi reporta encoder_util "N/A" en GPUs sin NVENC (p.ej. GT 1030)
+        let nvenc = self.encoder_util_percent.is_some();
+
+        GPUInfo {
+            name: self.name.clone(),
+            vendor: GPUVendor::NVIDIA,
+            memory_total_mb: self.vram_total_mb.unwrap_or(0),
+            memory_free_mb: self.vram_free_mb.unwrap_or(0),
+            driver_version: self.driver_version.clone(),
+            supports_nvenc: nvenc,
+            supports_nvenc_hevc: nvenc,
+            supports_amf: false,
+            supports_qsv: false,
+            supports_vaapi: false,
+            pci_id: self.pci_id.clone(),
+            temperature_c: self.temperature_c,
+            usage_percent: self.gpu_util_percent,
+            encoder_usage_percent: self.encoder_util_percent,
+            encoder_sessions: self.encoder_sessions,
+        }
+    }
+}
+
+/// Parsea la salida XML de `nvidia-smi -q -x`
+pub fn parse_nvidia_smi_xml(xml: &str) -> Result<Vec<NvidiaSmiGpu>> {
+    let doc = roxmltree::Document::parse_with_options(
+        xml,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )
+    .context("Invalid nvidia-smi XML")?;
+
+    let root = doc.root_element();
+    if root.tag_name().name() != "nvidia_smi_log" {
+        anyhow::bail!("Unexpected nvidia-smi root element <{}>", root.tag_name().name());
+    }
+
+    let driver_version = child_text(root, &["driver_version"]).unwrap_or_else(|| "Unknown".to_string());
+
+    let gpus = root
+        .children()
+        .filter(|n| n.has_tag_name("gpu"))
+        .map(|gpu| NvidiaSmiGpu {
+            name: child_text(gpu, &["product_name"]).unwrap_or_else(|| "NVIDIA GPU".to_string()),
+            pci_bus_id: gpu
+                .attribute("id")
+                .map(str::to_string)
+                .or_else(|| child_text(gpu, &["pci", "pci_bus_id"]))
+                .unwrap_or_default(),
+            pci_id: child_text(gpu, &["pci", "pci_device_id"]).and_then(|id| pci_id(&id)),
+            driver_version: driver_version.clone(),
+            temperature_c: child_number(gpu, &["temperature", "gpu_temp"]),
+            gpu_util_percent: child_number(gpu, &["utilization", "gpu_util"]),
+            encoder_util_percent: child_number(gpu, &["utilization", "encoder_util"]),
+            encoder_sessions: child_number(gpu, &["encoder_stats", "session_count"]).map(|n| n as u32),
+            vram_total_mb: child_number(gpu, &["fb_memory_usage", "total"]).map(|n| n as u64),
+            vram_used_mb: child_number(gpu, &["fb_memory_usage", "used"]).map(|n| n as u64),
+            vram_free_mb: child_number(gpu, &["fb_memory_usage", "free"]).map(|n| n as u64),
+        })
+        .collect();
+
+    Ok(gpus)
+}
+
+/// Texto del nodo en la ruta `path` (hijos directos)
+fn child_text(node: roxmltree::Node, path: &[&str]) -> Option<String> {
+    let mut current = node;
+    for tag in path {
+        current = current.children().find(|n| n.has_tag_name(*tag))?;
+    }
+    current.text().map(|t| t.trim().to_string())
+}
+
+/// Número al inicio del texto ("67 C", "31 %", "10240 MiB"); `None` para "N/A"
+fn child_number(node: roxmltree::Node, path: &[&str]) -> Option<f32> {
+    child_text(node, path)?.split_whitespace().next()?.parse().ok()
+}
+
+/// "220610DE" (device + vendor) -> "10de:2206"
+fn pci_id(device_id: &str) -> Option<String> {
+    let id = u32::from_str_radix(device_id.trim_start_matches("0x"), 16).ok()?;
+    Some(format!("{:04x}:{:04x}", id & 0xffff, id >> 16))
+}
+
+/// Consulta GPUs NVIDIA mediante `nvidia-smi` (respaldo sin NVML)
+#[derive(Clone)]
+pub struct NvidiaSmi {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl NvidiaSmi {
+    pub fn new() -> Self {
+        Self {
+            runner: Arc::new(SystemCommandRunner),
+        }
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    pub fn query(&self) -> Result<Vec<NvidiaSmiGpu>> {
+        let xml = self.runner.run("nvidia-smi", &["-q", "-x"])?;
+        let gpus = parse_nvidia_smi_xml(&xml)?;
+        debug!("nvidia-smi reported {} GPU(s)", gpus.len());
+        Ok(gpus)
+    }
+}
+
+impl Default for NvidiaSmi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RTX3080: &str = include_str!("../../tests/fixtures/nvidia-smi-rtx3080.xml");
+
+    struct FixtureRunner(&'static str);
+
+    impl CommandRunner for FixtureRunner {
+        fn run(&self, program: &str, args: &[&str]) -> Result<String> {
+            assert_eq!(program, "nvidia-smi");
+            assert_eq!(args, &["-q", "-x"]);
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_recorded_output() {
+        let gpus = NvidiaSmi::with_runner(Arc::new(FixtureRunner(RTX3080))).query().unwrap();
+        assert_eq!(gpus.len(), 2);
+
+        let rtx = &gpus[0];
+        assert_eq!(rtx.name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(rtx.driver_version, "550.120");
+        assert_eq!(rtx.pci_id.as_deref(), Some("10de:2206"));
+        assert_eq!(rtx.temperature_c, Some(67.0));
+        assert_eq!(rtx.gpu_util_percent, Some(23.0));
+        assert_eq!(rtx.encoder_util_percent, Some(31.0));
+        assert_eq!(rtx.encoder_sessions, Some(2));
+        assert_eq!(rtx.vram_total_mb, Some(10240));
+        assert_eq!(rtx.vram_free_mb, Some(8132));
+        assert!(rtx.matches_pci_slot("0000:01:00.0"));
+        assert!(!rtx.matches_pci_slot("0000:02:00.0"));
+
+        let info = rtx.to_gpu_info();
+        assert!(info.supports_nvenc);
+        assert_eq!(info.encoder_sessions, Some(2));
+
+        // Sin NVENC ni sensor
+        let gt = &gpus[1];
+        assert_eq!(gt.temperature_c, None);
+        assert!(!gt.to_gpu_info().supports_nvenc);
+    }
+
+    #[test]
+    fn test_rejects_unexpected_output() {
+        assert!(parse_nvidia_smi_xml("NVIDIA-SMI has failed").is_err());
+        assert!(parse_nvidia_smi_xml("<other/>").is_err());
+    }
+}
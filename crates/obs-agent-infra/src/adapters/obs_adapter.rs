@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use obs_agent_core::application::ports::{OBSPort, ValidationIssue, ValidationReport};
-use obs_agent_core::domain::models::{OBSStats, Scene, Severity, VideoSettings};
+use obs_agent_core::domain::models::{
+    EncoderSettings, OBSStats, OutputStatus, RecordingSettings, Scene, Severity, StreamSettings, VideoSettings,
+};
 use obws::Client;
+use std::net::IpAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
@@ -38,6 +41,85 @@ impl OBSAdapter {
         self.ensure_connected().await?;
         Ok(Arc::clone(&self.client))
     }
+
+    /// Clave del preset en el perfil según el encoder (modo de salida simple)
+    fn preset_parameter(encoder: &str) -> &'static str {
+        if encoder.contains("nvenc") {
+            "NVENCPreset2"
+        } else if encoder.contains("amd") {
+            "AMDPreset"
+        } else {
+            "Preset"
+        }
+    }
+
+    async fn profile_parameter(client: &Client, category: &str, name: &str) -> Result<String> {
+        let parameter = client.profiles().parameter(category, name)
+            .await
+            .with_context(|| format!("Failed to get profile parameter {}/{}", category, name))?;
+
+        parameter.value
+            .or(parameter.default_value)
+            .with_context(|| format!("Profile parameter {}/{} is not set", category, name))
+    }
+
+    /// Bitrate de grabación según el perfil, si se puede deducir
+    async fn recording_bitrate(client: &Client) -> Option<u32> {
+        let mode = Self::profile_parameter(client, "Output", "Mode").await.ok()?;
+        if mode == "Advanced" {
+            return Self::advanced_recording_bitrate(client).await;
+        }
+        if mode != "Simple" {
+            return None;
+        }
+
+        let quality = Self::profile_parameter(client, "SimpleOutput", "RecQuality").await.ok()?;
+        match quality.as_str() {
+            "Stream" => {
+                let video: u32 = Self::profile_parameter(client, "SimpleOutput", "VBitrate")
+                    .await
+                    .ok()?
+                    .parse()
+                    .ok()?;
+                let audio: u32 = Self::profile_parameter(client, "SimpleOutput", "ABitrate")
+                    .await
+                    .ok()
+                    .and_then(|a| a.parse().ok())
+                    .unwrap_or(160);
+                Some(video + audio)
+            }
+            // Calidades por CQP/CRF: estimaciones aproximadas para 1080p60
+            "Small" => Some(15_000),
+            "HQ" => Some(40_000),
+            "Lossless" => Some(250_000),
+            _ => None,
+        }
+    }
+
+    /// En modo Avanzado solo la salida FFmpeg guarda su bitrate en el perfil; la estándar
+    /// lo guarda en `recordEncoder.json`, que obs-websocket no expone
+    async fn advanced_recording_bitrate(client: &Client) -> Option<u32> {
+        let record_type = Self::profile_parameter(client, "AdvOut", "RecType").await.ok()?;
+        if record_type != "FFmpeg" {
+            return None;
+        }
+
+        let video: u32 = Self::profile_parameter(client, "AdvOut", "FFVBitrate").await.ok()?.parse().ok()?;
+        let audio: u32 = Self::profile_parameter(client, "AdvOut", "FFABitrate")
+            .await
+            .ok()
+            .and_then(|a| a.parse().ok())
+            .unwrap_or(160);
+        Some(video + audio)
+    }
+
+    async fn ensure_simple_output_mode(client: &Client) -> Result<()> {
+        let mode = Self::profile_parameter(client, "Output", "Mode").await?;
+        if mode != "Simple" {
+            anyhow::bail!("Encoder settings are only supported in Simple output mode (current: {})", mode);
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -54,6 +136,11 @@ impl OBSPort for OBSAdapter {
         Ok(())
     }
 
+    fn is_local(&self) -> bool {
+        let host = self.host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
+
     async fn disconnect(&self) -> Result<()> {
         info!("Disconnecting from OBS");
         *self.client.write().await = None;
@@ -197,12 +284,236 @@ impl OBSPort for OBSAdapter {
         // Screenshot API cambió en obws 0.11 - temporalmente deshabilitado
         anyhow::bail!("Screenshot functionality not available in obws 0.11 - needs update")
     }
+
+    async fn set_current_scene(&self, scene_name: &str) -> Result<()> {
+        info!("Switching to scene: {}", scene_name);
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        client.scenes().set_current_program_scene(scene_name)
+            .await
+            .context("Failed to set current scene")?;
+
+        Ok(())
+    }
+
+    async fn set_source_visible(&self, scene_name: &str, source_name: &str, visible: bool) -> Result<()> {
+        info!("Setting '{}' visible={} in scene '{}'", source_name, visible, scene_name);
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        let item_id = client.scene_items().id(obws::requests::scene_items::Id {
+            scene: scene_name,
+            source: source_name,
+            search_offset: None,
+        })
+        .await
+        .context("Failed to find scene item")?;
+
+        client.scene_items().set_enabled(obws::requests::scene_items::SetEnabled {
+            scene: scene_name,
+            item_id,
+            enabled: visible,
+        })
+        .await
+        .context("Failed to set scene item visibility")?;
+
+        Ok(())
+    }
+
+    async fn get_encoder_settings(&self) -> Result<EncoderSettings> {
+        debug!("Getting encoder settings");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        Self::ensure_simple_output_mode(client).await?;
+
+        let encoder = Self::profile_parameter(client, "SimpleOutput", "StreamEncoder").await?;
+        let preset = Self::profile_parameter(client, "SimpleOutput", Self::preset_parameter(&encoder)).await?;
+        let bitrate_kbps = Self::profile_parameter(client, "SimpleOutput", "VBitrate").await?
+            .parse()
+            .context("Invalid bitrate in profile")?;
+
+        Ok(EncoderSettings {
+            encoder,
+            preset,
+            bitrate_kbps,
+        })
+    }
+
+    async fn set_encoder_settings(&self, settings: &EncoderSettings) -> Result<()> {
+        info!("Setting encoder settings: {} preset={} bitrate={} kbps",
+            settings.encoder, settings.preset, settings.bitrate_kbps);
+
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        Self::ensure_simple_output_mode(client).await?;
+
+        let bitrate = settings.bitrate_kbps.to_string();
+        let parameters = [
+            ("StreamEncoder", settings.encoder.as_str()),
+            (Self::preset_parameter(&settings.encoder), settings.preset.as_str()),
+            ("VBitrate", bitrate.as_str()),
+        ];
+
+        for (name, value) in parameters {
+            client.profiles().set_parameter(obws::requests::profiles::SetParameter {
+                category: "SimpleOutput",
+                name,
+                value: Some(value),
+            })
+            .await
+            .with_context(|| format!("Failed to set profile parameter SimpleOutput/{}", name))?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_recording_settings(&self) -> Result<RecordingSettings> {
+        debug!("Getting recording settings");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        let directory = client.config().record_directory()
+            .await
+            .context("Failed to get recording directory")?;
+        let bitrate_kbps = Self::recording_bitrate(client).await;
+
+        debug!("Recording to {} at {:?} kbps", directory, bitrate_kbps);
+        Ok(RecordingSettings {
+            directory,
+            bitrate_kbps,
+        })
+    }
+
+    async fn get_stream_settings(&self) -> Result<StreamSettings> {
+        debug!("Getting stream service settings");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        let service = client.config().stream_service_settings::<serde_json::Value>()
+            .await
+            .context("Failed to get stream service settings")?;
+        let server = service.settings.get("server")
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+
+        Ok(StreamSettings {
+            service_type: service.r#type,
+            server,
+        })
+    }
+
+    async fn get_output_status(&self) -> Result<Vec<OutputStatus>> {
+        debug!("Getting output status");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        let stream = client.streaming().status()
+            .await
+            .context("Failed to get stream status")?;
+        let record = client.recording().status()
+            .await
+            .context("Failed to get record status")?;
+
+        Ok(vec![
+            OutputStatus {
+                name: "stream".to_string(),
+                active: stream.active,
+                reconnecting: stream.reconnecting,
+                congestion: Some(stream.congestion as f64),
+                bytes: stream.bytes,
+                skipped_frames: stream.skipped_frames as u64,
+                total_frames: stream.total_frames as u64,
+                duration_secs: stream.duration.as_seconds_f64(),
+            },
+            OutputStatus {
+                name: "record".to_string(),
+                active: record.active,
+                bytes: record.bytes,
+                duration_secs: record.duration.as_seconds_f64(),
+                ..Default::default()
+            },
+        ])
+    }
+
+    async fn set_output_active(&self, output: &str, active: bool) -> Result<()> {
+        info!("{} output '{}'", if active { "Starting" } else { "Stopping" }, output);
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        let result = match (output, active) {
+            ("stream", true) => client.streaming().start().await,
+            ("stream", false) => client.streaming().stop().await,
+            ("record", true) => client.recording().start().await,
+            ("record", false) => client.recording().stop().await.map(|_| ()),
+            _ => anyhow::bail!("Unknown output '{}' (stream, record)", output),
+        };
+        result.with_context(|| format!("Failed to {} {}", if active { "start" } else { "stop" }, output))
+    }
+
+    async fn get_replay_buffer_active(&self) -> Result<bool> {
+        debug!("Getting replay buffer status");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        client.replay_buffer().status()
+            .await
+            .context("Failed to get replay buffer status")
+    }
+
+    async fn start_replay_buffer(&self) -> Result<()> {
+        info!("Starting replay buffer");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        client.replay_buffer().start()
+            .await
+            .context("Failed to start replay buffer")?;
+
+        Ok(())
+    }
+
+    async fn stop_replay_buffer(&self) -> Result<()> {
+        info!("Stopping replay buffer");
+        let client_arc = self.get_client().await?;
+        let client = client_arc.read().await;
+        let client = client.as_ref().context("Not connected to OBS")?;
+
+        client.replay_buffer().stop()
+            .await
+            .context("Failed to stop replay buffer")?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_local() {
+        for host in ["localhost", "127.0.0.1", "::1", "[::1]"] {
+            assert!(OBSAdapter::new(host, 4455, None).is_local(), "{}", host);
+        }
+        for host in ["192.168.1.20", "obs-studio.lan"] {
+            assert!(!OBSAdapter::new(host, 4455, None).is_local(), "{}", host);
+        }
+    }
+
     #[tokio::test]
     #[ignore] // Requiere OBS corriendo
     async fn test_obs_connection() {
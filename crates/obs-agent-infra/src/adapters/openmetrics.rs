@@ -0,0 +1,399 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use obs_agent_core::domain::models::{Anomaly, AnomalyType, Metric, Severity};
+use obs_agent_core::domain::services::TelemetryRecord;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+pub const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Prefijo de todas las métricas exportadas
+const PREFIX: &str = "obs_agent";
+/// Tamaño máximo aceptado para la cabecera de una petición HTTP
+const MAX_REQUEST_HEADER: usize = 8 * 1024;
+/// Tiempo máximo para recibir la cabecera completa; un cliente lento no
+/// retiene la conexión indefinidamente
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct ExporterState {
+    record: Option<TelemetryRecord>,
+    anomalies: Vec<Anomaly>,
+    /// Último total de bytes de cada salida, para calcular su bitrate
+    output_bytes: HashMap<String, (DateTime<Utc>, u64)>,
+    output_bitrate_kbps: HashMap<String, f64>,
+}
+
+/// Expone la última muestra en formato OpenMetrics (Prometheus)
+///
+/// Los nombres son estables: `obs_agent_<métrica>` para las [`Metric`] del
+/// sistema y `obs_agent_obs_*` para OBS, con etiquetas `scene` y `output`.
+#[derive(Default)]
+pub struct OpenMetricsExporter {
+    state: RwLock<ExporterState>,
+}
+
+impl OpenMetricsExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publica una muestra y las anomalías abiertas en ese momento
+    pub fn update(&self, record: &TelemetryRecord, anomalies: &[Anomaly]) {
+        let mut state = self.state.write().unwrap();
+
+        for output in &record.outputs {
+            let previous = state.output_bytes.insert(output.name.clone(), (record.timestamp, output.bytes));
+            let bitrate = match previous {
+                // Un contador menor indica que la salida se reinició
+                Some((at, bytes)) if output.bytes >= bytes && record.timestamp > at => {
+                    let secs = (record.timestamp - at).num_milliseconds() as f64 / 1000.0;
+                    (output.bytes - bytes) as f64 * 8.0 / 1000.0 / secs
+                }
+                _ => 0.0,
+            };
+            state.output_bitrate_kbps.insert(output.name.clone(), if output.active { bitrate } else { 0.0 });
+        }
+
+        state.record = Some(record.clone());
+        state.anomalies = anomalies.to_vec();
+    }
+
+    /// Texto OpenMetrics de la última muestra (solo `up 0` antes de la primera)
+    pub fn render(&self) -> String {
+        let state = self.state.read().unwrap();
+        let mut out = MetricsWriter::default();
+
+        out.family("up", "gauge", "Whether the agent has published a sample");
+        out.sample("up", &[], if state.record.is_some() { 1.0 } else { 0.0 });
+
+        let Some(record) = &state.record else {
+            return out.finish();
+        };
+
+        out.family("last_sample_timestamp_seconds", "gauge", "Time of the latest sample");
+        out.sample("last_sample_timestamp_seconds", &[], record.timestamp.timestamp_millis() as f64 / 1000.0);
+
+        for (metric, value) in Metric::values(&record.context) {
+            out.family(metric.name(), "gauge", metric.description());
+            out.sample(metric.name(), &[], value);
+        }
+
+        let scene = record.scene.as_deref().unwrap_or("");
+        if !scene.is_empty() {
+            out.family("obs_scene", "info", "Current program scene");
+            out.sample("obs_scene_info", &[("scene", scene)], 1.0);
+        }
+
+        if let Some(stats) = &record.obs_stats {
+            out.family("obs_active_fps", "gauge", "Frames per second rendered by OBS");
+            out.sample("obs_active_fps", &[("scene", scene)], stats.active_fps);
+            out.family("obs_render_frames", "counter", "Frames rendered since OBS started");
+            out.sample("obs_render_frames_total", &[], stats.render_total_frames as f64);
+            out.family("obs_render_skipped_frames", "counter", "Frames skipped by the renderer (GPU lag)");
+            out.sample("obs_render_skipped_frames_total", &[], stats.render_skipped_frames as f64);
+            out.family("obs_encoding_frames", "counter", "Frames handed to the encoders since OBS started");
+            out.sample("obs_encoding_frames_total", &[], stats.output_total_frames as f64);
+            out.family("obs_encoding_skipped_frames", "counter", "Frames skipped by the encoders (encoding lag)");
+            out.sample("obs_encoding_skipped_frames_total", &[], stats.output_skipped_frames as f64);
+        }
+
+        if !record.outputs.is_empty() {
+            let outputs = || record.outputs.iter().map(|output| (output, [("output", output.name.as_str())]));
+
+            out.family("obs_output_active", "gauge", "Whether the output is running");
+            for (output, labels) in outputs() {
+                out.sample("obs_output_active", &labels, output.active as u8 as f64);
+            }
+            out.family("obs_output_reconnecting", "gauge", "Whether the output is reconnecting");
+            for (output, labels) in outputs() {
+                out.sample("obs_output_reconnecting", &labels, output.reconnecting as u8 as f64);
+            }
+            out.family("obs_output_congestion_ratio", "gauge", "Network congestion reported by the output (0-1)");
+            for (output, labels) in outputs() {
+                if let Some(congestion) = output.congestion {
+                    out.sample("obs_output_congestion_ratio", &labels, congestion);
+                }
+            }
+            out.family("obs_output_bitrate_kbps", "gauge", "Output bitrate since the previous sample");
+            for (output, labels) in outputs() {
+                let bitrate = state.output_bitrate_kbps.get(&output.name).copied().unwrap_or(0.0);
+                out.sample("obs_output_bitrate_kbps", &labels, bitrate);
+            }
+            out.family("obs_output_bytes", "counter", "Bytes sent or written since the output started");
+            for (output, labels) in outputs() {
+                out.sample("obs_output_bytes_total", &labels, output.bytes as f64);
+            }
+            out.family("obs_output_frames", "counter", "Frames delivered by the output");
+            for (output, labels) in outputs() {
+                out.sample("obs_output_frames_total", &labels, output.total_frames as f64);
+            }
+            out.family("obs_output_dropped_frames", "counter", "Frames dropped by the output (network)");
+            for (output, labels) in outputs() {
+                out.sample("obs_output_dropped_frames_total", &labels, output.skipped_frames as f64);
+            }
+        }
+
+        let mut open: BTreeMap<(AnomalyType, Severity), u32> = BTreeMap::new();
+        for anomaly in &state.anomalies {
+            *open.entry((anomaly.anomaly_type, anomaly.severity)).or_insert(0) += 1;
+        }
+        out.family("anomalies_open", "gauge", "Anomalies detected in the latest sample");
+        for ((anomaly_type, severity), count) in open {
+            let anomaly_type = format!("{:?}", anomaly_type);
+            let severity = format!("{:?}", severity).to_lowercase();
+            out.sample("anomalies_open", &[("type", &anomaly_type), ("severity", &severity)], count as f64);
+        }
+
+        out.finish()
+    }
+}
+
+/// Construye la exposición con el prefijo común
+#[derive(Default)]
+struct MetricsWriter {
+    text: String,
+}
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# TYPE {}_{} {}", PREFIX, name, kind);
+        let _ = writeln!(self.text, "# HELP {}_{} {}", PREFIX, name, help);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = write!(self.text, "{}_{}", PREFIX, name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", format_value(value));
+    }
+
+    fn finish(mut self) -> String {
+        self.text.push_str("# EOF\n");
+        self.text
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Servidor HTTP mínimo que sirve `GET /metrics`
+pub struct MetricsServer {
+    listener: TcpListener,
+    exporter: Arc<OpenMetricsExporter>,
+    read_timeout: Duration,
+}
+
+impl MetricsServer {
+    pub async fn bind(addr: &str, exporter: Arc<OpenMetricsExporter>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind metrics endpoint on {}", addr))?;
+        Ok(Self {
+            listener,
+            exporter,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+        })
+    }
+
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().context("Metrics endpoint has no local address")
+    }
+
+    /// Atiende peticiones hasta que se cancele la tarea
+    pub async fn serve(self) -> Result<()> {
+        info!("Serving OpenMetrics on http://{}/metrics", self.local_addr()?);
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let exporter = Arc::clone(&self.exporter);
+            let read_timeout = self.read_timeout;
+            tokio::spawn(async move {
+                if let Err(e) = Self::respond(stream, &exporter, read_timeout).await {
+                    debug!("Metrics request from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    /// Lanza el servidor en segundo plano
+    pub fn spawn(self) -> JoinHandle<Result<()>> {
+        tokio::spawn(self.serve())
+    }
+
+    async fn respond(mut stream: TcpStream, exporter: &OpenMetricsExporter, read_timeout: Duration) -> Result<()> {
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        let read_headers = async {
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await?;
+                if read == 0 || request.len() + read > MAX_REQUEST_HEADER {
+                    anyhow::bail!("Incomplete or oversized request");
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            Ok(())
+        };
+        tokio::time::timeout(read_timeout, read_headers)
+            .await
+            .map_err(|_| anyhow::anyhow!("Request headers not received within {:?}", read_timeout))??;
+
+        let request = String::from_utf8_lossy(&request);
+        let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+        let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+        let path = path.split('?').next().unwrap_or_default();
+
+        let (status, content_type, body) = match (method, path) {
+            ("GET", "/metrics") => ("200 OK", OPENMETRICS_CONTENT_TYPE, exporter.render()),
+            (_, "/metrics") => ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string()),
+            _ => ("404 Not Found", "text/plain", "Not found; try /metrics\n".to_string()),
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await.ok();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::models::{OBSStats, OutputStatus};
+    use obs_agent_core::domain::services::SystemContext;
+
+    fn record(at_secs: i64, bytes: u64) -> TelemetryRecord {
+        TelemetryRecord {
+            scene: Some("Main \"Cam\"".to_string()),
+            obs_stats: Some(OBSStats {
+                cpu_usage: 12.0,
+                memory_usage: 800.0,
+                active_fps: 60.0,
+                render_skipped_frames: 3,
+                render_total_frames: 6000,
+                output_skipped_frames: 0,
+                output_total_frames: 6000,
+            }),
+            outputs: vec![OutputStatus {
+                name: "stream".to_string(),
+                active: true,
+                congestion: Some(0.25),
+                bytes,
+                total_frames: 6000,
+                skipped_frames: 12,
+                ..Default::default()
+            }],
+            ..TelemetryRecord::from_context(SystemContext {
+                cpu_temp: 70.5,
+                timestamp: DateTime::from_timestamp(1_800_000_000 + at_secs, 0).unwrap(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_render_openmetrics() {
+        let exporter = OpenMetricsExporter::new();
+        let empty = exporter.render();
+        assert!(empty.contains("obs_agent_up 0\n"));
+        assert!(empty.ends_with("# EOF\n"));
+
+        exporter.update(&record(0, 1_000_000), &[]);
+        let anomaly = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "hot");
+        exporter.update(&record(2, 2_500_000), &[anomaly.clone(), anomaly]);
+        let text = exporter.render();
+
+        assert!(text.ends_with("# EOF\n"));
+        assert!(text.contains("obs_agent_up 1\n"));
+        assert!(text.contains("obs_agent_cpu_temp_celsius 70.5\n"));
+        assert!(text.contains("obs_agent_obs_scene_info{scene=\"Main \\\"Cam\\\"\"} 1\n"));
+        assert!(text.contains("# TYPE obs_agent_obs_render_skipped_frames counter\n"));
+        assert!(text.contains("obs_agent_obs_render_skipped_frames_total 3\n"));
+        assert!(text.contains("obs_agent_obs_output_congestion_ratio{output=\"stream\"} 0.25\n"));
+        // 1.5 MB en 2 s
+        assert!(text.contains("obs_agent_obs_output_bitrate_kbps{output=\"stream\"} 6000\n"));
+        assert!(text.contains("obs_agent_anomalies_open{type=\"HighCPUTemp\",severity=\"warning\"} 2\n"));
+        // Sin sensor de GPU la serie no aparece
+        assert!(!text.contains("obs_agent_gpu_temp_celsius "));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let exporter = Arc::new(OpenMetricsExporter::new());
+        exporter.update(&record(0, 0), &[]);
+        let server = MetricsServer::bind("127.0.0.1:0", Arc::clone(&exporter)).await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.spawn();
+
+        let get = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+
+        let response = get("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(OPENMETRICS_CONTENT_TYPE));
+        assert!(response.ends_with("# EOF\n"));
+        assert!(get("/").await.starts_with("HTTP/1.1 404"));
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_slow_client_is_disconnected() {
+        let exporter = Arc::new(OpenMetricsExporter::new());
+        let server = MetricsServer::bind("127.0.0.1:0", exporter)
+            .await
+            .unwrap()
+            .with_read_timeout(Duration::from_millis(100));
+        let addr = server.local_addr().unwrap();
+        let handle = server.spawn();
+
+        // Cabecera sin terminar: el servidor cierra sin responder
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n").await.unwrap();
+        let mut response = Vec::new();
+        let closed = tokio::time::timeout(Duration::from_secs(2), stream.read_to_end(&mut response)).await;
+        assert!(closed.is_ok(), "connection should be closed after the read timeout");
+        assert!(response.is_empty());
+        handle.abort();
+    }
+}
@@ -0,0 +1,341 @@
+use obs_agent_core::domain::models::{ObsProcessInfo, ProcessEvent, ProcessUsage};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, System};
+use tracing::{debug, warn};
+
+/// Nombres del ejecutable principal de OBS (Linux, Windows, macOS)
+const OBS_PROCESS_NAMES: &[&str] = &["obs", "obs64.exe", "obs32.exe", "obs.exe", "OBS"];
+
+/// Proceso visto en una muestra (independiente de sysinfo para poder testear)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    /// Segundos desde epoch
+    pub start_time: u64,
+}
+
+/// Localiza OBS y sus descendientes (browser sources, etc.)
+///
+/// Si hay varias instancias se toma la más antigua.
+pub fn find_obs_tree(processes: &[ProcessEntry]) -> Option<(&ProcessEntry, Vec<&ProcessEntry>)> {
+    let main = processes
+        .iter()
+        .filter(|p| OBS_PROCESS_NAMES.iter().any(|name| p.name.eq_ignore_ascii_case(name)))
+        .min_by_key(|p| (p.start_time, p.pid))?;
+
+    let mut children: HashMap<u32, Vec<&ProcessEntry>> = HashMap::new();
+    for process in processes {
+        if let Some(parent) = process.parent {
+            children.entry(parent).or_default().push(process);
+        }
+    }
+
+    let mut helpers = Vec::new();
+    let mut pending = vec![main.pid];
+    while let Some(pid) = pending.pop() {
+        for child in children.get(&pid).into_iter().flatten() {
+            pending.push(child.pid);
+            helpers.push(*child);
+        }
+    }
+    helpers.sort_by_key(|p| p.pid);
+
+    Some((main, helpers))
+}
+
+/// Detecta arranques, reinicios y crashes de OBS entre muestras
+#[derive(Debug, Default)]
+pub struct ProcessTracker {
+    /// (pid, start_time) de la instancia actual
+    current: Option<(u32, u64)>,
+    last_pid: Option<u32>,
+    restarts: u32,
+    crashes: u32,
+}
+
+impl ProcessTracker {
+    /// Registra la instancia observada; `crashed_since` indica si hay un reporte
+    /// de crash posterior al arranque dado (segundos desde epoch)
+    pub fn observe(
+        &mut self,
+        instance: Option<(u32, u64)>,
+        crashed_since: impl Fn(u64) -> bool,
+    ) -> Option<ProcessEvent> {
+        let previous = self.current;
+        self.current = instance;
+
+        let event = match (previous, instance) {
+            (None, Some((pid, _))) => match self.last_pid {
+                Some(previous_pid) if previous_pid != pid => {
+                    self.restarts += 1;
+                    Some(ProcessEvent::Restarted { previous_pid })
+                }
+                _ => Some(ProcessEvent::Started),
+            },
+            (Some((pid, started)), None) => {
+                if crashed_since(started) {
+                    self.crashes += 1;
+                    Some(ProcessEvent::Crashed { pid })
+                } else {
+                    Some(ProcessEvent::Exited { pid })
+                }
+            }
+            (Some(old), Some(new)) if old != new => {
+                // Se cerró y volvió a abrir entre dos muestras
+                self.restarts += 1;
+                if crashed_since(old.1) {
+                    self.crashes += 1;
+                    Some(ProcessEvent::Crashed { pid: old.0 })
+                } else {
+                    Some(ProcessEvent::Restarted { previous_pid: old.0 })
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            debug!("OBS process event: {:?}", event);
+        }
+        if let Some((pid, _)) = instance {
+            self.last_pid = Some(pid);
+        }
+        event
+    }
+
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    pub fn crashes(&self) -> u32 {
+        self.crashes
+    }
+}
+
+/// Monitorea el proceso de OBS y sus helpers vía sysinfo
+pub struct ObsProcessMonitor {
+    system: Mutex<System>,
+    tracker: Mutex<ProcessTracker>,
+    last_refresh: Mutex<Option<Instant>>,
+    proc_root: PathBuf,
+    crash_dir: Option<PathBuf>,
+}
+
+impl ObsProcessMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(System::new()),
+            tracker: Mutex::new(ProcessTracker::default()),
+            last_refresh: Mutex::new(None),
+            proc_root: PathBuf::from("/proc"),
+            crash_dir: dirs::config_dir().map(|dir| dir.join("obs-studio").join("crashes")),
+        }
+    }
+
+    /// Cambia el directorio donde OBS deja los reportes de crash
+    pub fn with_crash_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.crash_dir = Some(dir.into());
+        self
+    }
+
+    /// Toma una muestra del proceso de OBS
+    pub fn sample(&self) -> ObsProcessInfo {
+        let mut system = self.system.lock().unwrap();
+        system.refresh_processes_specifics(
+            ProcessRefreshKind::new().with_cpu().with_memory().with_disk_usage(),
+        );
+
+        let now = Instant::now();
+        let elapsed = self
+            .last_refresh
+            .lock()
+            .unwrap()
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or(0.0);
+
+        // En Linux los hilos aparecen como procesos hijos; se descartan
+        let threads: HashSet<Pid> = system
+            .processes()
+            .values()
+            .filter_map(|p| p.tasks())
+            .flatten()
+            .copied()
+            .collect();
+        let entries: Vec<ProcessEntry> = system
+            .processes()
+            .values()
+            .filter(|p| !threads.contains(&p.pid()))
+            .map(|p| ProcessEntry {
+                pid: p.pid().as_u32(),
+                parent: p.parent().map(|parent| parent.as_u32()),
+                name: p.name().to_string(),
+                start_time: p.start_time(),
+            })
+            .collect();
+
+        let tree = find_obs_tree(&entries);
+        let instance = tree.as_ref().map(|(main, _)| (main.pid, main.start_time));
+
+        let mut tracker = self.tracker.lock().unwrap();
+        let event = tracker.observe(instance, |started| self.crash_reported_since(started));
+        if let Some(ProcessEvent::Crashed { pid }) = event {
+            warn!("OBS (pid {}) crashed", pid);
+        }
+
+        let usage = |entry: &ProcessEntry| {
+            system
+                .process(Pid::from_u32(entry.pid))
+                .map(|process| self.usage(entry, process, elapsed))
+        };
+
+        ObsProcessInfo {
+            main: tree.as_ref().and_then(|(main, _)| usage(main)),
+            helpers: tree
+                .as_ref()
+                .map(|(_, helpers)| helpers.iter().filter_map(|h| usage(h)).collect())
+                .unwrap_or_default(),
+            uptime_secs: tree
+                .as_ref()
+                .map(|(main, _)| unix_now().saturating_sub(main.start_time))
+                .unwrap_or(0),
+            restarts: tracker.restarts(),
+            crashes: tracker.crashes(),
+            event,
+        }
+    }
+
+    fn usage(&self, entry: &ProcessEntry, process: &sysinfo::Process, elapsed_secs: f64) -> ProcessUsage {
+        let disk = process.disk_usage();
+        let rate = |bytes: u64| if elapsed_secs > 0.0 { bytes as f64 / elapsed_secs } else { 0.0 };
+        let proc_dir = self.proc_root.join(entry.pid.to_string());
+
+        ProcessUsage {
+            pid: entry.pid,
+            name: entry.name.clone(),
+            cpu_percent: process.cpu_usage(),
+            memory_mb: process.memory() as f64 / 1_048_576.0,
+            threads: read_thread_count(&proc_dir)
+                .or_else(|| process.tasks().map(|tasks| tasks.len() as u32 + 1)),
+            open_files: std::fs::read_dir(proc_dir.join("fd")).ok().map(|fds| fds.count() as u32),
+            disk_read_bytes_per_sec: rate(disk.read_bytes),
+            disk_write_bytes_per_sec: rate(disk.written_bytes),
+        }
+    }
+
+    /// ¿Hay un reporte de crash de OBS escrito después de `started`?
+    fn crash_reported_since(&self, started: u64) -> bool {
+        let Some(dir) = &self.crash_dir else {
+            return false;
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return false;
+        };
+
+        entries
+            .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+            .filter_map(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .any(|modified| modified.as_secs() >= started)
+    }
+}
+
+impl Default for ObsProcessMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Campo `Threads:` de `/proc/<pid>/status`
+fn read_thread_count(proc_dir: &Path) -> Option<u32> {
+    std::fs::read_to_string(proc_dir.join("status"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent: Option<u32>, name: &str, start_time: u64) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent,
+            name: name.to_string(),
+            start_time,
+        }
+    }
+
+    #[test]
+    fn test_find_obs_tree_with_browser_helpers() {
+        let processes = vec![
+            entry(1, None, "systemd", 0),
+            entry(500, Some(1), "obs", 1000),
+            entry(510, Some(500), "obs-browser-page", 1001),
+            entry(511, Some(510), "obs-browser-page", 1002),
+            entry(600, Some(1), "obs-agent", 1100),
+        ];
+
+        let (main, helpers) = find_obs_tree(&processes).unwrap();
+        assert_eq!(main.pid, 500);
+        assert_eq!(helpers.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![510, 511]);
+
+        // "obs-agent" no es OBS
+        assert!(find_obs_tree(&processes[3..]).is_none());
+        // Windows
+        assert_eq!(find_obs_tree(&[entry(7, None, "obs64.exe", 5)]).unwrap().0.pid, 7);
+    }
+
+    #[test]
+    fn test_tracker_detects_restart_and_crash() {
+        let mut tracker = ProcessTracker::default();
+        let no_crash = |_| false;
+
+        assert_eq!(tracker.observe(Some((500, 1000)), no_crash), Some(ProcessEvent::Started));
+        assert_eq!(tracker.observe(Some((500, 1000)), no_crash), None);
+
+        // Cierre limpio y nueva instancia
+        assert_eq!(tracker.observe(None, no_crash), Some(ProcessEvent::Exited { pid: 500 }));
+        assert_eq!(tracker.observe(None, no_crash), None);
+        assert_eq!(
+            tracker.observe(Some((700, 2000)), no_crash),
+            Some(ProcessEvent::Restarted { previous_pid: 500 })
+        );
+
+        // Crash con reporte posterior al arranque, reabierto antes de la siguiente muestra
+        let crashed = |started| started <= 2500;
+        assert_eq!(tracker.observe(Some((800, 3000)), crashed), Some(ProcessEvent::Crashed { pid: 700 }));
+        assert_eq!(tracker.observe(None, |_| false), Some(ProcessEvent::Exited { pid: 800 }));
+
+        assert_eq!(tracker.restarts(), 2);
+        assert_eq!(tracker.crashes(), 1);
+    }
+
+    #[test]
+    fn test_crash_report_detection() {
+        let dir = std::env::temp_dir().join(format!("obs-agent-crashes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let monitor = ObsProcessMonitor::new().with_crash_dir(&dir);
+
+        assert!(!monitor.crash_reported_since(0));
+        std::fs::write(dir.join("Crash 2026-10-18 21-04-11.txt"), "Unhandled exception").unwrap();
+        assert!(monitor.crash_reported_since(unix_now() - 60));
+        assert!(!monitor.crash_reported_since(unix_now() + 3600));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::Result;
+use obs_agent_core::application::ports::{DiskInfo, MonitorPort};
+use obs_agent_core::domain::models::{
+    CoreTemperature, CpuFrequencyStats, GPUInfo, HardwareInfo, MonitorSnapshot, ObsProcessInfo, PowerStatus,
+    RAMInfo,
+};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Muestras pendientes por suscriptor antes de que empiece a perderlas
+const BROADCAST_CAPACITY: usize = 16;
+/// Cada cuánto se repite la detección de hardware (lenta y casi estática)
+const HARDWARE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Muestrea un `MonitorPort` en segundo plano y publica cada muestra
+///
+/// La última muestra está siempre disponible vía `watch`; quien necesite cada
+/// muestra (grabación, streaming a clientes) se suscribe al `broadcast`.
+pub struct MonitorSampler {
+    monitor: Arc<dyn MonitorPort>,
+    interval: Duration,
+}
+
+impl MonitorSampler {
+    pub fn new(monitor: Arc<dyn MonitorPort>) -> Self {
+        Self {
+            monitor,
+            interval: Duration::from_secs(2),
+        }
+    }
+
+    /// Cambia el intervalo entre muestras
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(250));
+        self
+    }
+
+    /// Toma una muestra de forma síncrona
+    pub fn snapshot(monitor: &dyn MonitorPort, sequence: u64) -> MonitorSnapshot {
+        let gpus = monitor.get_gpus().unwrap_or_default();
+        let gpu_temp = gpus
+            .iter()
+            .filter_map(|gpu| gpu.temperature_c)
+            .max_by(|a, b| a.total_cmp(b))
+            .or_else(|| monitor.get_gpu_temp().ok().flatten());
+
+        MonitorSnapshot {
+            sequence,
+            timestamp: chrono::Utc::now(),
+            cpu_usage: monitor.get_cpu_usage().unwrap_or(0.0),
+            cpu_temp: monitor.get_cpu_temp().ok().flatten(),
+            cpu_frequency: monitor.get_cpu_frequency().ok().flatten(),
+            gpu_temp,
+            gpus,
+            memory: monitor.get_memory_info().ok(),
+            power: monitor.get_power_status().ok().flatten(),
+            obs_process: monitor
+                .get_obs_process()
+                .map_err(|e| debug!("Could not sample OBS process: {}", e))
+                .ok()
+                .flatten(),
+        }
+    }
+
+    /// Lanza la tarea de muestreo (requiere un runtime de Tokio)
+    pub fn spawn(self) -> SamplerHandle {
+        let (latest_tx, latest) = watch::channel(None);
+        let (hardware_tx, hardware) = watch::channel(None);
+        let (updates, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let monitor = Arc::clone(&self.monitor);
+        let publisher = updates.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut sequence = 0;
+            let mut hardware_at: Option<Instant> = None;
+
+            loop {
+                ticker.tick().await;
+
+                // sysfs, /proc y nvidia-smi bloquean: fuera del executor
+                let monitor = Arc::clone(&self.monitor);
+                let detect = !matches!(hardware_at, Some(at) if at.elapsed() < HARDWARE_INTERVAL);
+                let sampled = tokio::task::spawn_blocking(move || {
+                    let hardware = detect.then(|| monitor.detect_hardware());
+                    (Self::snapshot(monitor.as_ref(), sequence), hardware)
+                })
+                .await;
+                let (snapshot, hardware) = match sampled {
+                    Ok((snapshot, hardware)) => (Arc::new(snapshot), hardware),
+                    Err(e) => {
+                        warn!("Monitor sampling failed: {}", e);
+                        continue;
+                    }
+                };
+                sequence += 1;
+
+                match hardware {
+                    Some(Ok(hardware)) => {
+                        hardware_tx.send_replace(Some(Arc::new(hardware)));
+                        hardware_at = Some(Instant::now());
+                    }
+                    Some(Err(e)) => {
+                        debug!("Hardware detection failed: {}", e);
+                        hardware_at = Some(Instant::now());
+                    }
+                    None => {}
+                }
+
+                latest_tx.send_replace(Some(Arc::clone(&snapshot)));
+                // Sin suscriptores el envío falla; no es un error
+                let _ = publisher.send(snapshot);
+            }
+        });
+
+        SamplerHandle {
+            monitor,
+            latest,
+            hardware,
+            updates,
+            task,
+        }
+    }
+}
+
+/// Acceso al feed del sampler; la tarea se detiene al soltarlo
+pub struct SamplerHandle {
+    monitor: Arc<dyn MonitorPort>,
+    latest: watch::Receiver<Option<Arc<MonitorSnapshot>>>,
+    hardware: watch::Receiver<Option<Arc<HardwareInfo>>>,
+    updates: broadcast::Sender<Arc<MonitorSnapshot>>,
+    task: JoinHandle<()>,
+}
+
+impl SamplerHandle {
+    /// Última muestra (`None` hasta la primera)
+    pub fn latest(&self) -> Option<Arc<MonitorSnapshot>> {
+        self.latest.borrow().clone()
+    }
+
+    /// Receptor que siempre ve la muestra más reciente
+    pub fn watch(&self) -> watch::Receiver<Option<Arc<MonitorSnapshot>>> {
+        self.latest.clone()
+    }
+
+    /// Receptor de cada nueva muestra
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<MonitorSnapshot>> {
+        self.updates.subscribe()
+    }
+
+    /// `MonitorPort` que responde con la última muestra en lugar de volver a leer el hardware
+    pub fn monitor(&self) -> Arc<dyn MonitorPort> {
+        Arc::new(SampledMonitor {
+            inner: Arc::clone(&self.monitor),
+            latest: self.watch(),
+            hardware: self.hardware.clone(),
+        })
+    }
+
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for SamplerHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// `MonitorPort` respaldado por el sampler: lecturas dinámicas y hardware
+/// desde la última muestra. Lo que el sampler no tiene (discos, lecturas antes
+/// de la primera muestra) va al puerto original sin ocupar un worker del runtime.
+pub struct SampledMonitor {
+    inner: Arc<dyn MonitorPort>,
+    latest: watch::Receiver<Option<Arc<MonitorSnapshot>>>,
+    hardware: watch::Receiver<Option<Arc<HardwareInfo>>>,
+}
+
+impl SampledMonitor {
+    fn latest(&self) -> Option<Arc<MonitorSnapshot>> {
+        self.latest.borrow().clone()
+    }
+}
+
+/// Ejecuta una lectura bloqueante; en un worker del runtime multihilo se le
+/// cede el hilo (`block_in_place`) para no frenar al resto de tareas
+fn blocking<T>(read: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => tokio::task::block_in_place(read),
+        _ => read(),
+    }
+}
+
+impl MonitorPort for SampledMonitor {
+    fn get_cpu_temp(&self) -> Result<Option<f32>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.cpu_temp),
+            None => blocking(|| self.inner.get_cpu_temp()),
+        }
+    }
+
+    fn get_cpu_core_temps(&self) -> Result<Vec<CoreTemperature>> {
+        blocking(|| self.inner.get_cpu_core_temps())
+    }
+
+    fn get_cpu_frequency(&self) -> Result<Option<CpuFrequencyStats>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.cpu_frequency.clone()),
+            None => blocking(|| self.inner.get_cpu_frequency()),
+        }
+    }
+
+    fn get_gpu_temp(&self) -> Result<Option<f32>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.gpu_temp),
+            None => blocking(|| self.inner.get_gpu_temp()),
+        }
+    }
+
+    fn get_gpus(&self) -> Result<Vec<GPUInfo>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.gpus.clone()),
+            None => blocking(|| self.inner.get_gpus()),
+        }
+    }
+
+    fn get_cpu_usage(&self) -> Result<f32> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.cpu_usage),
+            None => blocking(|| self.inner.get_cpu_usage()),
+        }
+    }
+
+    fn get_power_status(&self) -> Result<Option<PowerStatus>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.power.clone()),
+            None => blocking(|| self.inner.get_power_status()),
+        }
+    }
+
+    fn get_memory_info(&self) -> Result<RAMInfo> {
+        match self.latest().and_then(|s| s.memory.clone()) {
+            Some(memory) => Ok(memory),
+            None => blocking(|| self.inner.get_memory_info()),
+        }
+    }
+
+    fn get_disk_space(&self) -> Result<DiskInfo> {
+        blocking(|| self.inner.get_disk_space())
+    }
+
+    fn get_disk_space_for(&self, path: &Path) -> Result<DiskInfo> {
+        blocking(|| self.inner.get_disk_space_for(path))
+    }
+
+    /// El evento (crash, reinicio) corresponde a la muestra en la que se detectó
+    fn get_obs_process(&self) -> Result<Option<ObsProcessInfo>> {
+        match self.latest() {
+            Some(snapshot) => Ok(snapshot.obs_process.clone()),
+            None => blocking(|| self.inner.get_obs_process()),
+        }
+    }
+
+    fn detect_hardware(&self) -> Result<HardwareInfo> {
+        let cached = self.hardware.borrow().clone();
+        match cached {
+            Some(hardware) => Ok(hardware.as_ref().clone()),
+            None => blocking(|| self.inner.detect_hardware()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{SimulatedMonitorAdapter, Simulation, SimulationScenario};
+
+    #[tokio::test]
+    async fn test_sampler_publishes_snapshots() {
+        let simulation = Arc::new(Simulation::new(SimulationScenario::Nominal).with_speed(0.0));
+        let sampler = MonitorSampler::new(Arc::new(SimulatedMonitorAdapter::new(simulation)))
+            .with_interval(Duration::from_millis(250))
+            .spawn();
+        let mut updates = sampler.subscribe();
+
+        let first = updates.recv().await.unwrap();
+        let second = updates.recv().await.unwrap();
+        assert_eq!(second.sequence, first.sequence + 1);
+        assert!(second.timestamp >= first.timestamp);
+        assert_eq!(sampler.latest().unwrap().sequence, second.sequence);
+
+        // El puerto respaldado responde con la última muestra
+        let monitor = sampler.monitor();
+        assert_eq!(monitor.get_cpu_usage().unwrap(), second.cpu_usage);
+        assert_eq!(monitor.get_gpus().unwrap().len(), 1);
+        assert!(monitor.get_obs_process().unwrap().unwrap().is_running());
+    }
+}
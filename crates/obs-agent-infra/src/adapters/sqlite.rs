@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// Base de datos SQLite con sus migraciones aplicadas (histórico, tokens)
+pub(crate) struct SqliteDatabase {
+    pub path: PathBuf,
+    pub pool: SqlitePool,
+}
+
+impl SqliteDatabase {
+    /// Abre (o crea) `path` y aplica las migraciones pendientes; `label`
+    /// nombra la base en los mensajes ("metrics store", "token store")
+    pub async fn open(path: impl Into<PathBuf>, label: &str, migrator: &Migrator) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            // WAL: lectores (GUI, servidor) no bloquean al monitor que escribe
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await
+            .with_context(|| format!("Failed to open {} {}", label, path.display()))?;
+
+        migrator
+            .run(&pool)
+            .await
+            .with_context(|| format!("Failed to migrate {}", label))?;
+
+        info!("{} at {}", capitalize(label), path.display());
+        Ok(Self { path, pool })
+    }
+
+    /// Ubicación por defecto de `file` (directorio de datos del usuario)
+    pub fn default_path(file: &str) -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("obs-agent")
+            .join(file)
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Las marcas de tiempo se guardan en milisegundos desde la época
+pub(crate) fn from_millis(timestamp_ms: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default()
+}
@@ -0,0 +1,274 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use obs_agent_core::application::ports::TelemetrySink;
+use obs_agent_core::domain::services::TelemetryRecord;
+use std::collections::BTreeMap;
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// Tamaño máximo de datagrama (cabe en una trama Ethernet sin fragmentar)
+const DEFAULT_MAX_PACKET: usize = 1432;
+
+/// Cómo se codifican las etiquetas en cada métrica
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsdTagFormat {
+    /// `name:1|g|#key:value` (Datadog, Telegraf con `datadog_extensions`)
+    #[default]
+    Datadog,
+    /// `name,key=value:1|g` (Telegraf)
+    Influx,
+    /// Sin etiquetas (StatsD clásico)
+    None,
+}
+
+impl std::str::FromStr for StatsdTagFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "datadog" | "dogstatsd" => Ok(Self::Datadog),
+            "influx" | "telegraf" => Ok(Self::Influx),
+            "none" | "plain" => Ok(Self::None),
+            _ => anyhow::bail!("Unknown StatsD tag format '{}' (datadog, influx, none)", s),
+        }
+    }
+}
+
+/// Envía muestras como gauges StatsD por UDP
+///
+/// Las métricas se acumulan y se envían agrupadas en datagramas de hasta
+/// `max_packet` bytes cuando se alcanzan `batch_size` métricas o en cada `flush`.
+pub struct StatsdSink {
+    socket: UdpSocket,
+    target: String,
+    prefix: String,
+    tags: BTreeMap<String, String>,
+    tag_format: StatsdTagFormat,
+    batch_size: usize,
+    max_packet: usize,
+    buffer: Mutex<Vec<String>>,
+}
+
+impl StatsdSink {
+    /// Prepara el envío a `target` (`host:puerto`, normalmente el 8125)
+    pub async fn connect(target: &str) -> Result<Self> {
+        let addr = tokio::net::lookup_host(target)
+            .await
+            .with_context(|| format!("Failed to resolve {}", target))?
+            .next()
+            .with_context(|| format!("No address found for {}", target))?;
+        let bind = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind).await.context("Failed to bind StatsD socket")?;
+        socket
+            .connect(addr)
+            .await
+            .with_context(|| format!("Failed to connect StatsD socket to {}", target))?;
+
+        Ok(Self {
+            socket,
+            target: target.to_string(),
+            prefix: "obs_agent.".to_string(),
+            tags: BTreeMap::new(),
+            tag_format: StatsdTagFormat::default(),
+            batch_size: 200,
+            max_packet: DEFAULT_MAX_PACKET,
+            buffer: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Prefijo de cada métrica (por defecto `obs_agent.`)
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Etiquetas añadidas a cada métrica
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    pub fn with_tag_format(mut self, format: StatsdTagFormat) -> Self {
+        self.tag_format = format;
+        self
+    }
+
+    /// Métricas acumuladas antes de enviar sin esperar al `flush`
+    pub fn with_batch_size(mut self, metrics: usize) -> Self {
+        self.batch_size = metrics.max(1);
+        self
+    }
+
+    pub fn with_max_packet(mut self, bytes: usize) -> Self {
+        self.max_packet = bytes.max(64);
+        self
+    }
+
+    /// Una línea StatsD por valor de la muestra
+    pub fn lines(&self, record: &TelemetryRecord) -> Vec<String> {
+        record
+            .metric_samples()
+            .into_iter()
+            .filter(|sample| sample.value.is_finite())
+            .map(|sample| {
+                let mut tags = self.tags.clone();
+                tags.extend(sample.tags);
+                let name = format!("{}{}", self.prefix, sample.name);
+                let value = sample.value;
+                match self.tag_format {
+                    StatsdTagFormat::Datadog if !tags.is_empty() => {
+                        let tags: Vec<String> =
+                            tags.iter().map(|(k, v)| format!("{}:{}", sanitize(k), sanitize(v))).collect();
+                        format!("{}:{}|g|#{}", name, value, tags.join(","))
+                    }
+                    StatsdTagFormat::Influx => {
+                        let tags: String =
+                            tags.iter().map(|(k, v)| format!(",{}={}", sanitize(k), sanitize(v))).collect();
+                        format!("{}{}:{}|g", name, tags, value)
+                    }
+                    _ => format!("{}:{}|g", name, value),
+                }
+            })
+            .collect()
+    }
+
+    async fn send(&self, lines: Vec<String>) -> Result<()> {
+        let mut packet = String::new();
+        let mut packets = 0;
+        for line in &lines {
+            if !packet.is_empty() && packet.len() + 1 + line.len() > self.max_packet {
+                self.socket.send(packet.as_bytes()).await.context("Failed to send StatsD packet")?;
+                packets += 1;
+                packet.clear();
+            }
+            if !packet.is_empty() {
+                packet.push('\n');
+            }
+            packet.push_str(line);
+        }
+        if !packet.is_empty() {
+            self.socket.send(packet.as_bytes()).await.context("Failed to send StatsD packet")?;
+            packets += 1;
+        }
+
+        debug!("Sent {} metric(s) in {} packet(s) to {}", lines.len(), packets, self.target);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for StatsdSink {
+    async fn record(&self, record: &TelemetryRecord) -> Result<()> {
+        let lines = self.lines(record);
+        let full = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.extend(lines);
+            buffer.len() >= self.batch_size
+        };
+        if full {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// UDP no confirma la entrega: lo que falle al enviarse se descarta
+    async fn flush(&self) -> Result<()> {
+        let batch = std::mem::take(&mut *self.buffer.lock().await);
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.send(batch).await
+    }
+}
+
+/// StatsD no tiene escapes: los separadores dentro de una etiqueta se
+/// sustituyen por `_` para no partir la línea ni inventar etiquetas
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            ',' | '=' | ':' | '|' | '#' | '\n' | '\r' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::models::OutputStatus;
+    use obs_agent_core::domain::services::SystemContext;
+
+    fn record() -> TelemetryRecord {
+        TelemetryRecord {
+            outputs: vec![OutputStatus {
+                name: "stream".to_string(),
+                active: true,
+                ..Default::default()
+            }],
+            ..TelemetryRecord::from_context(SystemContext {
+                cpu_usage: 40.0,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tag_formats() {
+        let sink = StatsdSink::connect("127.0.0.1:8125")
+            .await
+            .unwrap()
+            .with_tags([("host".to_string(), "studio".to_string())]);
+        let lines = sink.lines(&record());
+        assert_eq!(lines[0], "obs_agent.cpu_usage_percent:40|g|#host:studio");
+        assert!(lines.contains(&"obs_agent.obs_output_active:1|g|#host:studio,output:stream".to_string()));
+
+        let sink = sink.with_tag_format(StatsdTagFormat::Influx).with_prefix("");
+        assert_eq!(sink.lines(&record())[0], "cpu_usage_percent,host=studio:40|g");
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_sanitized() {
+        let mut record = record();
+        record.outputs[0].name = "rtmp://a,b=c|d#e\nf".to_string();
+        let sink = StatsdSink::connect("127.0.0.1:8125")
+            .await
+            .unwrap()
+            .with_tags([("site:id".to_string(), "a,b".to_string())]);
+        let lines = sink.lines(&record);
+        assert!(lines.contains(&"obs_agent.obs_output_active:1|g|#output:rtmp_//a_b_c_d_e_f,site_id:a_b".to_string()));
+
+        let sink = sink.with_tag_format(StatsdTagFormat::Influx);
+        let lines = sink.lines(&record);
+        assert!(lines.contains(&"obs_agent.obs_output_active,output=rtmp_//a_b_c_d_e_f,site_id=a_b:1|g".to_string()));
+        assert!(lines.iter().all(|line| !line.contains('\n')));
+    }
+
+    #[tokio::test]
+    async fn test_batches_into_udp_packets() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = StatsdSink::connect(&server.local_addr().unwrap().to_string())
+            .await
+            .unwrap()
+            .with_tag_format(StatsdTagFormat::None)
+            .with_max_packet(128);
+
+        let per_record = sink.lines(&record()).len();
+        sink.record(&record()).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let mut received = Vec::new();
+        let mut buffer = [0u8; 2048];
+        while received.len() < per_record {
+            let len = tokio::time::timeout(std::time::Duration::from_secs(2), server.recv(&mut buffer))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(len <= 128);
+            received.extend(String::from_utf8_lossy(&buffer[..len]).lines().map(str::to_string));
+        }
+        assert_eq!(received.len(), per_record);
+        assert!(received.iter().all(|line| line.ends_with("|g")));
+    }
+}
@@ -0,0 +1,651 @@
+use obs_agent_core::domain::models::{
+    CoreFrequency, CoreTemperature, GPUInfo, GPUVendor, PowerProfile, PowerStatus,
+};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Drivers hwmon que reportan la temperatura de la CPU
+const CPU_HWMON_DRIVERS: &[&str] = &["coretemp", "k10temp", "zenpower"];
+
+/// Tipos de thermal zone de CPU, en orden de preferencia
+const CPU_THERMAL_ZONES: &[&str] = &["x86_pkg_temp", "cpu-thermal", "cpu_thermal", "soc_thermal", "acpitz"];
+
+/// Tarjeta gráfica enumerada desde `/sys/class/drm/card*/device`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrmGpu {
+    pub card: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Dirección PCI (p.ej. "0000:01:00.0")
+    pub pci_slot: Option<String>,
+    pub driver: Option<String>,
+    pub driver_version: Option<String>,
+    /// Product name que exponen algunas amdgpu
+    pub product_name: Option<String>,
+    /// GPU usada por el firmware para el arranque (normalmente la principal)
+    pub boot_vga: bool,
+    /// Tiene render node (`renderD*`), necesario para VA-API
+    pub has_render_node: bool,
+    pub vram_total_bytes: Option<u64>,
+    pub vram_used_bytes: Option<u64>,
+    pub busy_percent: Option<f32>,
+    pub temperature: Option<f32>,
+}
+
+impl DrmGpu {
+    pub fn vendor(&self) -> GPUVendor {
+        GPUVendor::from_pci_vendor(self.vendor_id)
+    }
+
+    pub fn pci_id(&self) -> String {
+        format!("{:04x}:{:04x}", self.vendor_id, self.device_id)
+    }
+
+    /// Capacidades de encoding según vendor y driver en uso
+    pub fn to_gpu_info(&self) -> GPUInfo {
+        let vendor = self.vendor();
+        let driver = self.driver.as_deref().unwrap_or_default();
+
+        // NVENC requiere el driver propietario; nouveau no lo expone
+        let nvenc = vendor == GPUVendor::NVIDIA && driver == "nvidia";
+        let amf = vendor == GPUVendor::AMD && driver == "amdgpu";
+        let qsv = vendor == GPUVendor::Intel && matches!(driver, "i915" | "xe");
+        // nouveau y radeon no tienen encoder VA-API usable (sin firmware o sin VCE en el driver)
+        let vaapi = self.has_render_node && matches!(driver, "amdgpu" | "i915" | "xe");
+
+        let mb = |bytes: u64| bytes / 1_048_576;
+        let memory_total_mb = self.vram_total_bytes.map(mb).unwrap_or(0);
+        let memory_used_mb = self.vram_used_bytes.map(mb).unwrap_or(0);
+
+        GPUInfo {
+            name: self
+                .product_name
+                .clone()
+                .unwrap_or_else(|| format!("{:?} GPU [{}]", vendor, self.pci_id())),
+            vendor,
+            memory_total_mb,
+            memory_free_mb: memory_total_mb.saturating_sub(memory_used_mb),
+            driver_version: self
+                .driver_version
+                .clone()
+                .or_else(|| self.driver.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            supports_nvenc: nvenc,
+            supports_nvenc_hevc: nvenc,
+            supports_amf: amf,
+            supports_qsv: qsv,
+            supports_vaapi: vaapi,
+            pci_id: Some(self.pci_id()),
+            temperature_c: self.temperature,
+            usage_percent: self.busy_percent,
+            encoder_usage_percent: None,
+            encoder_sessions: None,
+        }
+    }
+}
+
+/// Temperaturas de CPU leídas de sysfs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTemperatures {
+    /// Temperatura del paquete (Package id / Tdie / Tctl)
+    pub package: Option<f32>,
+    pub cores: Vec<CoreTemperature>,
+}
+
+impl CpuTemperatures {
+    /// Temperatura representativa: paquete o, si no hay, el core más caliente
+    pub fn representative(&self) -> Option<f32> {
+        self.package.or_else(|| {
+            self.cores
+                .iter()
+                .map(|c| c.celsius)
+                .max_by(|a, b| a.total_cmp(b))
+        })
+    }
+}
+
+/// Lector de `/sys` con raíz configurable (para probar contra un árbol falso)
+#[derive(Debug, Clone)]
+pub struct Sysfs {
+    root: PathBuf,
+}
+
+impl Sysfs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Lee un atributo sin espacios finales
+    pub fn read_string(&self, path: &Path) -> Option<String> {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    pub fn read_u64(&self, path: &Path) -> Option<u64> {
+        self.read_string(path)?.parse().ok()
+    }
+
+    /// Lee un valor en miligrados y lo convierte a °C
+    fn read_millicelsius(&self, path: &Path) -> Option<f32> {
+        let value: i64 = self.read_string(path)?.parse().ok()?;
+        Some(value as f32 / 1000.0)
+    }
+
+    /// Entradas de `class/<name>` cuyo nombre empieza por `prefix`, ordenadas
+    pub fn class_entries(&self, class: &str, prefix: &str) -> Vec<PathBuf> {
+        let dir = self.root.join("class").join(class);
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.file_name().to_string_lossy().starts_with(prefix))
+                    .map(|e| e.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort_by_key(|p| natural_key(p));
+        entries
+    }
+
+    /// Temperaturas de CPU: hwmon primero, thermal zones como respaldo
+    pub fn cpu_temperatures(&self) -> CpuTemperatures {
+        let mut temps = CpuTemperatures::default();
+
+        for hwmon in self.class_entries("hwmon", "hwmon") {
+            let Some(driver) = self.read_string(&hwmon.join("name")) else {
+                continue;
+            };
+            if !CPU_HWMON_DRIVERS.contains(&driver.as_str()) {
+                continue;
+            }
+
+            debug!("Reading CPU temperatures from {} ({})", hwmon.display(), driver);
+            let hwmon_temps = self.hwmon_cpu_temperatures(&hwmon);
+
+            // Con varios sockets nos quedamos con el paquete más caliente
+            temps.package = match (temps.package, hwmon_temps.package) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+            temps.cores.extend(hwmon_temps.cores);
+        }
+
+        if temps.package.is_none() && temps.cores.is_empty() {
+            temps.package = self.thermal_zone_cpu_temperature();
+        }
+
+        temps
+    }
+
+    fn hwmon_cpu_temperatures(&self, hwmon: &Path) -> CpuTemperatures {
+        let mut temps = CpuTemperatures::default();
+        // Tdie es la temperatura real; Tctl puede llevar un offset en algunos Ryzen
+        let mut tdie = None;
+        let mut tctl = None;
+
+        for input in self.temp_inputs(hwmon) {
+            let Some(celsius) = self.read_millicelsius(&input) else {
+                continue;
+            };
+            let name = input.file_name().unwrap_or_default().to_string_lossy();
+            let label_path = input.with_file_name(name.replace("_input", "_label"));
+            let label = self.read_string(&label_path).unwrap_or_default();
+
+            if label.starts_with("Package id") {
+                temps.package = Some(temps.package.map_or(celsius, |p: f32| p.max(celsius)));
+            } else if label == "Tdie" {
+                tdie = Some(celsius);
+            } else if label == "Tctl" || label.is_empty() {
+                // k10temp en CPUs antiguas expone temp1 sin etiqueta
+                tctl = tctl.or(Some(celsius));
+            } else if let Some(index) = label.strip_prefix("Core ") {
+                temps.cores.push(CoreTemperature {
+                    core: index.trim().parse().ok(),
+                    label,
+                    celsius,
+                });
+            } else if label.starts_with("Tccd") {
+                temps.cores.push(CoreTemperature {
+                    label,
+                    core: None,
+                    celsius,
+                });
+            }
+        }
+
+        temps.package = temps.package.or(tdie).or(tctl);
+        temps
+    }
+
+    /// Ficheros `temp*_input` de un hwmon, en orden numérico
+    fn temp_inputs(&self, hwmon: &Path) -> Vec<PathBuf> {
+        let mut inputs: Vec<PathBuf> = std::fs::read_dir(hwmon)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        let name = p.file_name().unwrap_or_default().to_string_lossy();
+                        name.starts_with("temp") && name.ends_with("_input")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        inputs.sort_by_key(|p| natural_key(p));
+        inputs
+    }
+
+    fn thermal_zone_cpu_temperature(&self) -> Option<f32> {
+        let zones: Vec<(String, f32)> = self
+            .class_entries("thermal", "thermal_zone")
+            .into_iter()
+            .filter_map(|zone| {
+                let zone_type = self.read_string(&zone.join("type"))?;
+                let celsius = self.read_millicelsius(&zone.join("temp"))?;
+                Some((zone_type, celsius))
+            })
+            .collect();
+
+        CPU_THERMAL_ZONES.iter().find_map(|wanted| {
+            zones
+                .iter()
+                .find(|(zone_type, _)| zone_type == wanted)
+                .map(|(zone_type, celsius)| {
+                    debug!("Using thermal zone '{}' for CPU temperature", zone_type);
+                    *celsius
+                })
+        })
+    }
+}
+
+impl Sysfs {
+    /// Frecuencia de cada CPU lógica desde `devices/system/cpu/cpu*/cpufreq` (kHz)
+    pub fn cpu_frequencies(&self) -> Vec<CoreFrequency> {
+        let dir = self.root.join("devices").join("system").join("cpu");
+        let mut cpus: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        let name = p.file_name().unwrap_or_default().to_string_lossy();
+                        name.len() > 3 && name.starts_with("cpu") && name[3..].chars().all(|c| c.is_ascii_digit())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        cpus.sort_by_key(|p| natural_key(p));
+
+        cpus.iter()
+            .filter_map(|cpu| {
+                let cpufreq = cpu.join("cpufreq");
+                let mhz = |file: &str| self.read_u64(&cpufreq.join(file)).map(|khz| khz as f64 / 1000.0);
+                let (_, core) = natural_key(cpu);
+
+                Some(CoreFrequency {
+                    core: core as usize,
+                    current_mhz: mhz("scaling_cur_freq")?,
+                    // intel_pstate: base_frequency; amd-pstate: amd_pstate_nominal_freq
+                    base_mhz: mhz("base_frequency").or_else(|| mhz("amd_pstate_nominal_freq")),
+                    max_mhz: mhz("cpuinfo_max_freq"),
+                    usage_percent: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Fuente de alimentación (`class/power_supply`) y perfil ACPI (`firmware/acpi/platform_profile`)
+    pub fn power_status(&self) -> PowerStatus {
+        let mut mains_online = None;
+        let mut batteries = Vec::new();
+
+        for supply in self.class_entries("power_supply", "") {
+            // Baterías de periféricos (ratón, mando) tienen scope "Device"
+            if self.read_string(&supply.join("scope")).as_deref() == Some("Device") {
+                continue;
+            }
+            match self.read_string(&supply.join("type")).as_deref() {
+                Some("Mains") | Some("USB") => {
+                    let online = self.read_u64(&supply.join("online")) == Some(1);
+                    mains_online = Some(mains_online.unwrap_or(false) || online);
+                }
+                Some("Battery") => batteries.push((
+                    self.read_string(&supply.join("status")).unwrap_or_default(),
+                    self.read_u64(&supply.join("capacity")),
+                )),
+                _ => {}
+            }
+        }
+
+        let on_battery = !batteries.is_empty()
+            && match mains_online {
+                Some(online) => !online,
+                None => batteries.iter().any(|(status, _)| status == "Discharging"),
+            };
+        let capacities: Vec<u64> = batteries.iter().filter_map(|(_, capacity)| *capacity).collect();
+
+        PowerStatus {
+            on_battery,
+            battery_percent: (!capacities.is_empty())
+                .then(|| capacities.iter().sum::<u64>() as f32 / capacities.len() as f32),
+            profile: self
+                .read_string(&self.root.join("firmware").join("acpi").join("platform_profile"))
+                .and_then(|name| PowerProfile::parse(&name)),
+        }
+    }
+
+    /// Enumera las GPUs de `class/drm` (solo `cardN`, no los conectores)
+    pub fn gpus(&self) -> Vec<DrmGpu> {
+        self.class_entries("drm", "card")
+            .into_iter()
+            .filter(|card| {
+                let name = card.file_name().unwrap_or_default().to_string_lossy();
+                name[4..].chars().all(|c| c.is_ascii_digit())
+            })
+            .filter_map(|card| self.drm_gpu(&card))
+            .collect()
+    }
+
+    fn drm_gpu(&self, card: &Path) -> Option<DrmGpu> {
+        let device = card.join("device");
+        let vendor_id = self.read_hex(&device.join("vendor"))?;
+        let device_id = self.read_hex(&device.join("device")).unwrap_or(0);
+
+        let driver = self.uevent_value(&device, "DRIVER").or_else(|| {
+            std::fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|link| link.file_name().map(|n| n.to_string_lossy().to_string()))
+        });
+        let driver_version = driver
+            .as_ref()
+            .and_then(|d| self.read_string(&self.root.join("module").join(d).join("version")));
+
+        let has_render_node = std::fs::read_dir(device.join("drm"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .any(|e| e.file_name().to_string_lossy().starts_with("renderD"))
+            })
+            .unwrap_or(false);
+
+        let gpu = DrmGpu {
+            card: card.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            vendor_id,
+            device_id,
+            pci_slot: self.uevent_value(&device, "PCI_SLOT_NAME"),
+            driver,
+            driver_version,
+            product_name: self
+                .read_string(&device.join("product_name"))
+                .filter(|name| !name.is_empty()),
+            boot_vga: self.read_u64(&device.join("boot_vga")) == Some(1),
+            has_render_node,
+            // amdgpu y xe exponen VRAM y uso; i915 integrada no tiene VRAM propia
+            vram_total_bytes: self.read_u64(&device.join("mem_info_vram_total")),
+            vram_used_bytes: self.read_u64(&device.join("mem_info_vram_used")),
+            busy_percent: self
+                .read_u64(&device.join("gpu_busy_percent"))
+                .map(|p| p as f32),
+            temperature: self.gpu_temperature(&device),
+        };
+
+        debug!("Found GPU {} ({}, driver {:?})", gpu.card, gpu.pci_id(), gpu.driver);
+        Some(gpu)
+    }
+
+    /// Temperatura del hwmon de la GPU (amdgpu: "edge"; i915/xe: primer sensor)
+    fn gpu_temperature(&self, device: &Path) -> Option<f32> {
+        let hwmon_dir = device.join("hwmon");
+        let mut hwmons: Vec<PathBuf> = std::fs::read_dir(&hwmon_dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        hwmons.sort_by_key(|p| natural_key(p));
+
+        let mut fallback = None;
+        for hwmon in hwmons {
+            for input in self.temp_inputs(&hwmon) {
+                let Some(celsius) = self.read_millicelsius(&input) else {
+                    continue;
+                };
+                let name = input.file_name().unwrap_or_default().to_string_lossy();
+                let label = self.read_string(&input.with_file_name(name.replace("_input", "_label")));
+                if label.as_deref() == Some("edge") {
+                    return Some(celsius);
+                }
+                fallback = fallback.or(Some(celsius));
+            }
+        }
+        fallback
+    }
+
+    /// Lee un ID hexadecimal con formato `0x10de`
+    fn read_hex(&self, path: &Path) -> Option<u16> {
+        let value = self.read_string(path)?;
+        u16::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+    }
+
+    fn uevent_value(&self, device: &Path, key: &str) -> Option<String> {
+        let uevent = std::fs::read_to_string(device.join("uevent")).ok()?;
+        uevent.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(|value| value.to_string())
+        })
+    }
+}
+
+impl Default for Sysfs {
+    fn default() -> Self {
+        Self::new("/sys")
+    }
+}
+
+/// Clave de orden que compara el sufijo numérico (hwmon2 < hwmon10)
+fn natural_key(path: &Path) -> (String, u64) {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let prefix: String = name.chars().take_while(|c| !c.is_ascii_digit()).collect();
+    let number = name[prefix.len()..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0);
+    (prefix, number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Árbol sysfs falso en un directorio temporal
+    struct FakeSysfs {
+        root: PathBuf,
+    }
+
+    impl FakeSysfs {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("obs-agent-sysfs-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&root).unwrap();
+            Self { root }
+        }
+
+        fn write(&self, path: &str, content: &str) -> &Self {
+            let path = self.root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, format!("{}\n", content)).unwrap();
+            self
+        }
+
+        fn sysfs(&self) -> Sysfs {
+            Sysfs::new(&self.root)
+        }
+    }
+
+    impl Drop for FakeSysfs {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn test_intel_coretemp() {
+        let fake = FakeSysfs::new();
+        fake.write("class/hwmon/hwmon0/name", "acpitz")
+            .write("class/hwmon/hwmon0/temp1_input", "99000")
+            .write("class/hwmon/hwmon3/name", "coretemp")
+            .write("class/hwmon/hwmon3/temp1_label", "Package id 0")
+            .write("class/hwmon/hwmon3/temp1_input", "67000")
+            .write("class/hwmon/hwmon3/temp2_label", "Core 0")
+            .write("class/hwmon/hwmon3/temp2_input", "64000")
+            .write("class/hwmon/hwmon3/temp10_label", "Core 8")
+            .write("class/hwmon/hwmon3/temp10_input", "66500");
+
+        let temps = fake.sysfs().cpu_temperatures();
+
+        assert_eq!(temps.package, Some(67.0));
+        assert_eq!(temps.cores.len(), 2);
+        assert_eq!(temps.cores[1].core, Some(8));
+        assert_eq!(temps.cores[1].celsius, 66.5);
+    }
+
+    #[test]
+    fn test_amd_prefers_tdie_over_tctl() {
+        let fake = FakeSysfs::new();
+        fake.write("class/hwmon/hwmon1/name", "k10temp")
+            .write("class/hwmon/hwmon1/temp1_label", "Tctl")
+            .write("class/hwmon/hwmon1/temp1_input", "80000")
+            .write("class/hwmon/hwmon1/temp2_label", "Tdie")
+            .write("class/hwmon/hwmon1/temp2_input", "70000")
+            .write("class/hwmon/hwmon1/temp3_label", "Tccd1")
+            .write("class/hwmon/hwmon1/temp3_input", "72250");
+
+        let temps = fake.sysfs().cpu_temperatures();
+
+        assert_eq!(temps.package, Some(70.0));
+        assert_eq!(temps.cores[0].label, "Tccd1");
+        assert_eq!(temps.cores[0].core, None);
+    }
+
+    #[test]
+    fn test_enumerates_amd_and_intel_gpus() {
+        let fake = FakeSysfs::new();
+        fake.write("class/drm/card0/device/vendor", "0x8086")
+            .write("class/drm/card0/device/device", "0xa780")
+            .write("class/drm/card0/device/uevent", "DRIVER=i915\nPCI_ID=8086:A780")
+            .write("class/drm/card0/device/boot_vga", "0")
+            .write("class/drm/card0/device/drm/renderD128/dev", "226:128")
+            .write("class/drm/card0-HDMI-A-1/status", "connected")
+            .write("class/drm/card1/device/vendor", "0x1002")
+            .write("class/drm/card1/device/device", "0x73bf")
+            .write("class/drm/card1/device/uevent", "DRIVER=amdgpu")
+            .write("class/drm/card1/device/boot_vga", "1")
+            .write("class/drm/card1/device/gpu_busy_percent", "37")
+            .write("class/drm/card1/device/mem_info_vram_total", "17163091968")
+            .write("class/drm/card1/device/mem_info_vram_used", "1073741824")
+            .write("class/drm/card1/device/hwmon/hwmon4/temp1_label", "edge")
+            .write("class/drm/card1/device/hwmon/hwmon4/temp1_input", "52000")
+            .write("class/drm/card1/device/hwmon/hwmon4/temp2_label", "junction")
+            .write("class/drm/card1/device/hwmon/hwmon4/temp2_input", "61000")
+            .write("module/amdgpu/version", "6.8.0");
+
+        let gpus = fake.sysfs().gpus();
+        assert_eq!(gpus.len(), 2);
+
+        let intel = gpus[0].to_gpu_info();
+        assert_eq!(intel.vendor, GPUVendor::Intel);
+        assert!(intel.supports_qsv && intel.supports_vaapi);
+        assert!(!intel.supports_amf && !intel.supports_nvenc);
+
+        let amd = &gpus[1];
+        assert!(amd.boot_vga);
+        assert!(!amd.has_render_node);
+        let info = amd.to_gpu_info();
+        assert_eq!(info.pci_id.as_deref(), Some("1002:73bf"));
+        assert!(info.supports_amf && !info.supports_vaapi);
+        assert_eq!(info.memory_total_mb, 16368);
+        assert_eq!(info.memory_free_mb, 15344);
+        assert_eq!(info.temperature_c, Some(52.0));
+        assert_eq!(info.usage_percent, Some(37.0));
+        assert_eq!(info.driver_version, "6.8.0");
+    }
+
+    #[test]
+    fn test_nouveau_has_no_hardware_encoder() {
+        let fake = FakeSysfs::new();
+        fake.write("class/drm/card0/device/vendor", "0x10de")
+            .write("class/drm/card0/device/device", "0x2684")
+            .write("class/drm/card0/device/uevent", "DRIVER=nouveau")
+            .write("class/drm/card0/device/drm/renderD128/dev", "226:128");
+
+        let info = fake.sysfs().gpus()[0].to_gpu_info();
+        assert_eq!(info.vendor, GPUVendor::NVIDIA);
+        assert!(!info.supports_nvenc);
+        assert!(!info.supports_vaapi);
+    }
+
+    #[test]
+    fn test_thermal_zone_fallback() {
+        let fake = FakeSysfs::new();
+        fake.write("class/thermal/thermal_zone0/type", "acpitz")
+            .write("class/thermal/thermal_zone0/temp", "45000")
+            .write("class/thermal/thermal_zone1/type", "x86_pkg_temp")
+            .write("class/thermal/thermal_zone1/temp", "58000");
+
+        assert_eq!(fake.sysfs().cpu_temperatures().representative(), Some(58.0));
+
+        let empty = FakeSysfs::new();
+        assert_eq!(empty.sysfs().cpu_temperatures().representative(), None);
+    }
+
+    #[test]
+    fn test_cpu_frequencies() {
+        let fake = FakeSysfs::new();
+        fake.write("devices/system/cpu/cpu0/cpufreq/scaling_cur_freq", "2400000")
+            .write("devices/system/cpu/cpu0/cpufreq/base_frequency", "3600000")
+            .write("devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq", "5000000")
+            .write("devices/system/cpu/cpu10/cpufreq/scaling_cur_freq", "3400000")
+            .write("devices/system/cpu/cpu10/cpufreq/amd_pstate_nominal_freq", "3400000")
+            .write("devices/system/cpu/cpu2/cpufreq/scaling_cur_freq", "1200000")
+            .write("devices/system/cpu/cpu2/cpufreq/cpuinfo_max_freq", "3000000")
+            .write("devices/system/cpu/cpufreq/boost", "1")
+            .write("devices/system/cpu/cpuidle/current_driver", "intel_idle");
+
+        let cores = fake.sysfs().cpu_frequencies();
+        assert_eq!(cores.iter().map(|c| c.core).collect::<Vec<_>>(), vec![0, 2, 10]);
+        assert_eq!(cores[0].current_mhz, 2400.0);
+        assert_eq!(cores[0].base_mhz, Some(3600.0));
+        assert_eq!(cores[1].base_mhz, None);
+        assert_eq!(cores[1].max_mhz, Some(3000.0));
+        assert_eq!(cores[2].base_mhz, Some(3400.0));
+    }
+
+    #[test]
+    fn test_laptop_on_battery() {
+        let fake = FakeSysfs::new();
+        fake.write("class/power_supply/AC/type", "Mains")
+            .write("class/power_supply/AC/online", "0")
+            .write("class/power_supply/BAT0/type", "Battery")
+            .write("class/power_supply/BAT0/status", "Discharging")
+            .write("class/power_supply/BAT0/capacity", "47")
+            .write("class/power_supply/hidpp_battery_0/type", "Battery")
+            .write("class/power_supply/hidpp_battery_0/scope", "Device")
+            .write("class/power_supply/hidpp_battery_0/capacity", "5")
+            .write("firmware/acpi/platform_profile", "low-power");
+
+        let power = fake.sysfs().power_status();
+        assert!(power.on_battery);
+        assert_eq!(power.battery_percent, Some(47.0));
+        assert_eq!(power.profile, Some(PowerProfile::PowerSaver));
+
+        fake.write("class/power_supply/AC/online", "1");
+        assert!(!fake.sysfs().power_status().on_battery);
+
+        // Sobremesa: sin batería ni perfil
+        assert_eq!(FakeSysfs::new().sysfs().power_status(), PowerStatus::default());
+    }
+}
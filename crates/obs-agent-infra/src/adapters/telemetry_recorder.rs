@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use obs_agent_core::application::ports::TelemetrySink;
+use obs_agent_core::domain::services::TelemetryRecord;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+/// Graba muestras de telemetría en NDJSON (una muestra JSON por línea)
+///
+/// Las escrituras van por `tokio::fs` para no bloquear el runtime en cada muestra.
+pub struct NdjsonTelemetryRecorder {
+    path: PathBuf,
+    writer: Mutex<BufWriter<tokio::fs::File>>,
+}
+
+impl NdjsonTelemetryRecorder {
+    /// Crea (o trunca) el archivo de grabación
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        info!("Recording telemetry to {}", path.display());
+        Ok(Self::from_file(path, file))
+    }
+
+    /// Abre el archivo para añadir muestras al final
+    pub fn append(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        info!("Appending telemetry to {}", path.display());
+        Ok(Self::from_file(path, file))
+    }
+
+    fn from_file(path: PathBuf, file: File) -> Self {
+        Self {
+            path,
+            writer: Mutex::new(BufWriter::new(tokio::fs::File::from_std(file))),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for NdjsonTelemetryRecorder {
+    async fn record(&self, record: &TelemetryRecord) -> Result<()> {
+        let line = serde_json::to_string(record).context("Failed to serialize telemetry")?;
+        let mut writer = self.writer.lock().await;
+        writer.write_all(format!("{}\n", line).as_bytes()).await.context("Failed to write telemetry")?;
+        // Cada muestra se vacía a disco para no perderla si el proceso muere
+        writer.flush().await.context("Failed to flush telemetry")?;
+        debug!("Recorded telemetry sample at {}", record.timestamp);
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.writer.lock().await.flush().await.context("Failed to flush telemetry")
+    }
+}
+
+/// Lee una grabación NDJSON completa (ignora líneas vacías)
+pub fn read_recording(path: &Path) -> Result<Vec<TelemetryRecord>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open recording {}", path.display()))?;
+
+    let mut records = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.context("Failed to read recording")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid telemetry record", path.display(), index + 1))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::services::SystemContext;
+
+    #[tokio::test]
+    async fn test_record_and_read_back() {
+        let path = std::env::temp_dir().join(format!("obs-agent-{}.ndjson", uuid::Uuid::new_v4()));
+
+        let recorder = NdjsonTelemetryRecorder::create(&path).unwrap();
+        for cpu_temp in [50.0, 60.0, 70.0] {
+            let record = TelemetryRecord::from_context(SystemContext {
+                cpu_temp,
+                ..Default::default()
+            });
+            recorder.record(&record).await.unwrap();
+        }
+        drop(recorder);
+
+        let recorder = NdjsonTelemetryRecorder::append(&path).unwrap();
+        recorder
+            .record(&TelemetryRecord::from_context(SystemContext::default()))
+            .await
+            .unwrap();
+        drop(recorder);
+
+        let records = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[2].context.cpu_temp, 70.0);
+    }
+}
@@ -0,0 +1,591 @@
+use crate::auth;
+use crate::board::OpenAnomaly;
+use crate::error::{ApiError, ErrorBody};
+use crate::fleet;
+use crate::live;
+use crate::models::*;
+use crate::openapi::UpstreamErrors;
+use crate::state::AppState;
+use actix_web::{get, middleware, post, put, web, HttpMessage, HttpRequest, HttpResponse};
+use obs_agent_core::domain::events::DomainEvent;
+use obs_agent_core::domain::models::{parse_duration, ApiToken, AuditEntry, HardwareInfo, MonitorSnapshot, TimeRange};
+use obs_agent_core::domain::services::*;
+use std::sync::Arc;
+
+type Result<T> = std::result::Result<T, ApiError>;
+
+/// Rutas de la API REST (`/api/v1/...`)
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    // Los latidos de la flota llevan la muestra y el reporte de salud completos
+    let json = web::JsonConfig::default()
+        .limit(256 * 1024)
+        .error_handler(|err, _| ApiError::BadRequest(err.to_string()).into());
+    cfg.app_data(json)
+        .app_data(web::QueryConfig::default().error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()))
+        .service(
+            services(web::scope("/api/v1"))
+                .wrap(middleware::from_fn(auth::authorize))
+                .default_service(web::to(|| async {
+                    Err::<HttpResponse, _>(ApiError::NotFound("No such API endpoint".to_string()))
+                })),
+        )
+        .service(
+            web::resource("/metrics")
+                .wrap(middleware::from_fn(auth::authorize))
+                .route(web::get().to(metrics)),
+        );
+}
+
+/// Handlers de `/api/v1`: la misma lista los registra en `configure` y los
+/// documenta en [`ApiV1`], así que ninguna ruta queda fuera de la especificación
+macro_rules! api_v1 {
+    ($($handler:ident $(:: $rest:ident)*),* $(,)?) => {
+        fn services(scope: actix_web::Scope) -> actix_web::Scope {
+            scope$(.service($handler$(::$rest)*))*
+        }
+
+        /// Operaciones de `/api/v1` (ver `openapi::ApiDoc`)
+        #[derive(utoipa::OpenApi)]
+        #[openapi(paths($($handler$(::$rest)*),*))]
+        pub struct ApiV1;
+    };
+}
+
+api_v1!(
+    hardware_info,
+    live_hardware,
+    list_scenes,
+    current_scene,
+    set_current_scene,
+    obs_stats,
+    health_check,
+    anomalies,
+    open_anomalies,
+    acknowledge_anomaly,
+    fix_anomaly,
+    start_output,
+    stop_output,
+    optimize,
+    live::events_sse,
+    live::events_ws,
+    audit,
+    fleet::register,
+    fleet::fleet_summary,
+    fleet::list_hosts,
+    fleet::host_detail,
+    fleet::remove_host,
+    fleet::receive_heartbeat,
+    fleet::send_command,
+);
+
+/// Última muestra en formato OpenMetrics, para que Prometheus la recoja
+async fn metrics(state: web::Data<AppState>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(obs_agent_infra::OPENMETRICS_CONTENT_TYPE)
+        .body(state.metrics.render())
+}
+
+/// Detección de hardware fuera del hilo del worker (sysinfo bloquea)
+pub async fn detect_hardware(state: &AppState) -> Result<HardwareInfo> {
+    let monitor = Arc::clone(&state.monitor);
+    web::block(move || monitor.detect_hardware())
+        .await
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Hardware detection panicked: {}", e)))?
+        .map_err(ApiError::Internal)
+}
+
+#[utoipa::path(
+    tag = "hardware",
+    summary = "Detect hardware and recommended settings",
+    responses((status = 200, description = "OK", body = HardwareInfo)),
+)]
+#[get("/hardware")]
+async fn hardware_info(state: web::Data<AppState>) -> Result<web::Json<HardwareInfo>> {
+    Ok(web::Json(detect_hardware(&state).await?))
+}
+
+/// Última muestra del sampler (temperaturas, uso, GPUs, proceso de OBS)
+#[utoipa::path(
+    tag = "hardware",
+    summary = "Latest sample from the background sampler",
+    responses(
+        (status = 200, description = "OK", body = MonitorSnapshot),
+        (status = 503, description = "No sample taken yet", body = ErrorBody),
+    ),
+)]
+#[get("/hardware/live")]
+async fn live_hardware(state: web::Data<AppState>) -> Result<web::Json<MonitorSnapshot>> {
+    let snapshot = state
+        .sampler
+        .latest()
+        .ok_or_else(|| ApiError::Unavailable("No hardware sample taken yet".to_string()))?;
+    Ok(web::Json(MonitorSnapshot::clone(&snapshot)))
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "List scenes and their sources",
+    responses((status = 200, description = "OK", body = Vec<SceneView>), UpstreamErrors),
+)]
+#[get("/scenes")]
+async fn list_scenes(state: web::Data<AppState>) -> Result<web::Json<Vec<SceneView>>> {
+    let (scenes, current) = state
+        .upstream("Listing OBS scenes", async {
+            tokio::try_join!(state.obs.get_scenes(), state.obs.get_current_scene())
+        })
+        .await?;
+    Ok(web::Json(scenes.into_iter().map(|scene| SceneView::new(scene, &current)).collect()))
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "Current program scene",
+    responses((status = 200, description = "OK", body = CurrentScene), UpstreamErrors),
+)]
+#[get("/scenes/current")]
+async fn current_scene(state: web::Data<AppState>) -> Result<web::Json<CurrentScene>> {
+    let name = state.upstream("Reading the OBS program scene", state.obs.get_current_scene()).await?;
+    Ok(web::Json(CurrentScene { name }))
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "Switch the program scene (operator)",
+    request_body = CurrentScene,
+    responses(
+        (status = 204, description = "Done"),
+        (status = 400, description = "Empty scene name or invalid body", body = ErrorBody),
+        (status = 404, description = "Scene not found", body = ErrorBody),
+        UpstreamErrors,
+    ),
+)]
+#[put("/scenes/current")]
+async fn set_current_scene(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<CurrentScene>,
+) -> Result<HttpResponse> {
+    let name = body.into_inner().name;
+    auth::audit_details(&request, format!("scene: {}", name));
+    state.switch_scene(&name).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "OBS render, output and resource statistics",
+    responses((status = 200, description = "OK", body = StatsView), UpstreamErrors),
+)]
+#[get("/stats")]
+async fn obs_stats(state: web::Data<AppState>) -> Result<web::Json<StatsView>> {
+    let (stats, outputs) = state
+        .upstream("Reading OBS stats", async {
+            tokio::try_join!(state.obs.get_stats(), state.obs.get_output_status())
+        })
+        .await?;
+    Ok(web::Json(StatsView::new(stats, outputs)))
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Full health check; `quick=true` only checks local sensors",
+    params(HealthQuery),
+    responses((status = 200, description = "OK", body = HealthResponse), UpstreamErrors),
+)]
+#[get("/health")]
+async fn health_check(state: web::Data<AppState>, query: web::Query<HealthQuery>) -> Result<web::Json<HealthResponse>> {
+    if query.quick {
+        // Solo sensores locales: no depende de OBS
+        let healthy = state.health_service().quick_check().await?;
+        return Ok(web::Json(HealthResponse::Quick(QuickHealth { healthy })));
+    }
+
+    let report = state.check_health().await?;
+    Ok(web::Json(HealthResponse::Full(HealthView::from(report))))
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Scan a fresh sample for anomalies",
+    params(ScanQuery),
+    responses(
+        (status = 200, description = "OK", body = AnomalyScan),
+        (status = 400, description = "Unknown severity", body = ErrorBody),
+        UpstreamErrors,
+    ),
+)]
+#[get("/anomalies")]
+async fn anomalies(state: web::Data<AppState>, query: web::Query<ScanQuery>) -> Result<web::Json<AnomalyScan>> {
+    let min_severity = query.min_severity()?;
+    let sample = state.upstream("Sampling OBS", state.health_service().sample()).await?;
+    let anomalies = state.detector.scan_filtered(&sample.context, min_severity);
+    Ok(web::Json(AnomalyScan {
+        timestamp: sample.timestamp,
+        min_severity,
+        anomalies,
+    }))
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Open anomalies from the live feed, most severe first, with acknowledgement state",
+    responses((status = 200, description = "OK", body = Vec<OpenAnomaly>)),
+)]
+#[get("/anomalies/open")]
+async fn open_anomalies(state: web::Data<AppState>) -> web::Json<Vec<OpenAnomaly>> {
+    web::Json(state.board.list())
+}
+
+fn anomaly_id(id: &str) -> Result<uuid::Uuid> {
+    id.parse().map_err(|_| ApiError::BadRequest(format!("Invalid anomaly id '{}'", id)))
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Acknowledge an open anomaly (operator)",
+    params(("id" = Uuid, Path, description = "Id of an open anomaly")),
+    responses(
+        (status = 200, description = "OK", body = OpenAnomaly),
+        (status = 400, description = "Invalid id", body = ErrorBody),
+        (status = 404, description = "Anomaly not open", body = ErrorBody),
+    ),
+)]
+#[post("/anomalies/{id}/ack")]
+async fn acknowledge_anomaly(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<String>,
+) -> Result<web::Json<OpenAnomaly>> {
+    let id = anomaly_id(&id)?;
+    // Sin autenticación no hay nombre de token
+    let by = request
+        .extensions()
+        .get::<ApiToken>()
+        .map_or_else(|| "local".to_string(), |token| token.name.clone());
+    let entry = state
+        .board
+        .acknowledge(id, &by)
+        .ok_or_else(|| ApiError::NotFound(format!("Anomaly {} is not open", id)))?;
+    auth::audit_details(&request, format!("acknowledged {:?}", entry.anomaly.anomaly_type));
+    Ok(web::Json(entry))
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Apply the registered remediation to an open anomaly and verify it (operator)",
+    params(("id" = Uuid, Path, description = "Id of an open anomaly")),
+    responses(
+        (status = 200, description = "OK", body = RemediationReport),
+        (status = 400, description = "Invalid id", body = ErrorBody),
+        (status = 404, description = "Anomaly not open", body = ErrorBody),
+    ),
+)]
+#[post("/anomalies/{id}/fix")]
+async fn fix_anomaly(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<String>,
+) -> Result<web::Json<RemediationReport>> {
+    let id = anomaly_id(&id)?;
+    let entry = state
+        .board
+        .get(id)
+        .ok_or_else(|| ApiError::NotFound(format!("Anomaly {} is not open", id)))?;
+
+    let report = state.remediation().remediate(&entry.anomaly).await;
+    auth::audit_details(&request, format!("fix {:?}: {:?}", report.anomaly_type, report.outcome));
+    if !report.changes.is_empty() {
+        state.events.publish_event(DomainEvent::config_changed(report.changes.clone()));
+    }
+    Ok(web::Json(report))
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "Start streaming or recording; does nothing if already active (operator)",
+    params(("output" = OutputName, Path, description = "OBS output")),
+    responses(
+        (status = 204, description = "Done"),
+        (status = 404, description = "Unknown output", body = ErrorBody),
+        UpstreamErrors,
+    ),
+)]
+#[post("/outputs/{output}/start")]
+async fn start_output(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    output: web::Path<String>,
+) -> Result<HttpResponse> {
+    set_output(&request, &state, &output, true).await
+}
+
+#[utoipa::path(
+    tag = "obs",
+    summary = "Stop streaming or recording; does nothing if already stopped (operator)",
+    params(("output" = OutputName, Path, description = "OBS output")),
+    responses(
+        (status = 204, description = "Done"),
+        (status = 404, description = "Unknown output", body = ErrorBody),
+        UpstreamErrors,
+    ),
+)]
+#[post("/outputs/{output}/stop")]
+async fn stop_output(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    output: web::Path<String>,
+) -> Result<HttpResponse> {
+    set_output(&request, &state, &output, false).await
+}
+
+/// Inicia o detiene el stream o la grabación; no hace nada si ya está así
+async fn set_output(request: &HttpRequest, state: &AppState, output: &str, active: bool) -> Result<HttpResponse> {
+    let output = output.parse::<OutputName>()?.as_str();
+    auth::audit_details(request, format!("{}: {}", output, if active { "start" } else { "stop" }));
+
+    let outputs = state.upstream("Reading OBS outputs", state.obs.get_output_status()).await?;
+    if outputs.iter().any(|status| status.name == output && status.active == active) {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+    let what = format!("{} the {} output", if active { "Starting" } else { "Stopping" }, output);
+    state.upstream(&what, state.obs.set_output_active(output, active)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = "health",
+    summary = "Ask the AI optimizer for settings that fit this machine (operator)",
+    responses(
+        (status = 200, description = "OK", body = OptimizedConfig),
+        (status = 503, description = "AI optimizer not configured", body = ErrorBody),
+        UpstreamErrors,
+    ),
+)]
+#[post("/optimize")]
+async fn optimize(state: web::Data<AppState>) -> Result<web::Json<OptimizedConfig>> {
+    let ai = state
+        .ai
+        .clone()
+        .ok_or_else(|| ApiError::Unavailable("AI optimizer not configured (set GEMINI_API_KEY)".to_string()))?;
+    let hardware = detect_hardware(&state).await?;
+
+    // Subida medida hacia el servidor de ingesta configurado (si OBS responde)
+    let upload = match &state.network {
+        Some(network) => {
+            let ingest = state.obs.get_stream_settings().await.ok().and_then(|s| s.ingest());
+            network.sample(ingest.as_ref()).await.ok().and_then(|n| n.estimated_upload_kbps)
+        }
+        None => None,
+    };
+
+    let mut optimizer = ConfigOptimizer::new(ai);
+    if let Some(upload) = upload {
+        optimizer = optimizer.with_upload_kbps(upload);
+    }
+    if let Some(power) = state.monitor.get_power_status().ok().flatten() {
+        optimizer = optimizer.with_power_status(power);
+    }
+
+    let config = state.upstream("AI optimization", optimizer.optimize(&hardware)).await?;
+    let valid = optimizer.validate_config(&config, &hardware);
+    Ok(web::Json(OptimizedConfig {
+        upload_kbps: upload,
+        max_bitrate_kbps: optimizer.max_bitrate(),
+        power_saving: optimizer.is_power_saving(),
+        valid,
+        ..OptimizedConfig::new(config)
+    }))
+}
+
+/// Auditoría de acciones (solo tokens `admin`)
+#[utoipa::path(
+    tag = "admin",
+    summary = "Mutations and denied attempts, newest first (admin)",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "OK", body = Vec<AuditEntry>),
+        (status = 400, description = "Invalid duration", body = ErrorBody),
+        (status = 503, description = "Authentication disabled", body = ErrorBody),
+    ),
+)]
+#[get("/audit")]
+async fn audit(state: web::Data<AppState>, query: web::Query<AuditQuery>) -> Result<web::Json<Vec<AuditEntry>>> {
+    let auth = state
+        .auth
+        .as_ref()
+        .ok_or_else(|| ApiError::Unavailable("Authentication is disabled, nothing is audited".to_string()))?;
+    let since = match query.since.as_deref() {
+        Some(since) => parse_duration(since).map_err(|e| ApiError::BadRequest(e.to_string()))?,
+        None => std::time::Duration::from_secs(24 * 3600),
+    };
+    let entries = auth
+        .store
+        .query_audit(TimeRange::last(since), query.limit.unwrap_or(100).min(1000))
+        .await?;
+    Ok(web::Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use obs_agent_core::domain::models::Severity;
+    use obs_agent_infra::*;
+
+    fn simulated_state() -> web::Data<AppState> {
+        let simulation = Arc::new(Simulation::new(SimulationScenario::Nominal));
+        let (obs, monitor) = simulated_ports(Arc::clone(&simulation));
+        let sampler = MonitorSampler::new(monitor).spawn();
+        web::Data::new(AppState::new(obs, sampler).with_network(Arc::new(SimulatedNetworkAdapter::new(simulation))))
+    }
+
+    #[actix_web::test]
+    async fn test_scenes_and_switching() {
+        let app = test::init_service(App::new().app_data(simulated_state()).configure(configure)).await;
+
+        let scenes: Vec<SceneView> =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/v1/scenes").to_request()).await;
+        assert!(scenes.iter().any(|s| s.name == "Main" && s.current && !s.sources.is_empty()));
+
+        let request = test::TestRequest::put()
+            .uri("/api/v1/scenes/current")
+            .set_json(CurrentScene { name: "BRB".to_string() })
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+        let current: CurrentScene =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/v1/scenes/current").to_request())
+                .await;
+        assert_eq!(current.name, "BRB");
+
+        let request = test::TestRequest::put()
+            .uri("/api/v1/scenes/current")
+            .set_json(CurrentScene { name: "Nope".to_string() })
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_health_and_anomalies() {
+        let app = test::init_service(App::new().app_data(simulated_state()).configure(configure)).await;
+
+        let health: HealthView =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/v1/health").to_request()).await;
+        assert!(health.report.can_stream);
+        assert!(!health.summary.is_empty());
+
+        let live = test::call_service(&app, test::TestRequest::get().uri("/api/v1/hardware/live").to_request()).await;
+        assert!(live.status() == StatusCode::OK || live.status() == StatusCode::SERVICE_UNAVAILABLE);
+
+        let scan: AnomalyScan = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get().uri("/api/v1/anomalies?severity=critical").to_request(),
+        )
+        .await;
+        assert!(scan.anomalies.iter().all(|a| a.severity == Severity::Critical));
+
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get().uri("/api/v1/anomalies?severity=loud").to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_output_controls() {
+        let app = test::init_service(App::new().app_data(simulated_state()).configure(configure)).await;
+        let post = |uri: &str| test::TestRequest::post().uri(uri).to_request();
+
+        let response = test::call_service(&app, post("/api/v1/outputs/record/start")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        // Repetir no es un error
+        let response = test::call_service(&app, post("/api/v1/outputs/record/start")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let stats: StatsView =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/v1/stats").to_request()).await;
+        assert!(stats.outputs.iter().any(|o| o.name == "record" && o.active));
+
+        let response = test::call_service(&app, post("/api/v1/outputs/record/stop")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = test::call_service(&app, post("/api/v1/outputs/replay/start")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_open_anomalies_ack_and_fix() {
+        use obs_agent_core::domain::models::{Anomaly, AnomalyTransition, AnomalyType};
+
+        let state = simulated_state();
+        let anomaly = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "warm");
+        state.board.apply(&AnomalyTransition::Raised { anomaly: anomaly.clone() });
+        let app = test::init_service(App::new().app_data(state).configure(configure)).await;
+
+        let open: Vec<OpenAnomaly> =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/v1/anomalies/open").to_request())
+                .await;
+        assert_eq!(open.len(), 1);
+        assert!(open[0].acknowledged_by.is_none());
+
+        let uri = format!("/api/v1/anomalies/{}/ack", anomaly.id);
+        let acknowledged: OpenAnomaly =
+            test::call_and_read_body_json(&app, test::TestRequest::post().uri(&uri).to_request()).await;
+        assert_eq!(acknowledged.acknowledged_by.as_deref(), Some("local"));
+
+        let missing = format!("/api/v1/anomalies/{}/fix", uuid::Uuid::new_v4());
+        let response = test::call_service(&app, test::TestRequest::post().uri(&missing).to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response =
+            test::call_service(&app, test::TestRequest::post().uri("/api/v1/anomalies/abc/ack").to_request()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_error_mapping() {
+        // Sin IA configurada el optimizador no está disponible
+        let app = test::init_service(App::new().app_data(simulated_state()).configure(configure)).await;
+        let response = test::call_service(&app, test::TestRequest::post().uri("/api/v1/optimize").to_request()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["error"], "unavailable");
+
+        let response = test::call_service(
+            &app,
+            test::TestRequest::put()
+                .uri("/api/v1/scenes/current")
+                .insert_header(("content-type", "application/json"))
+                .set_payload("{}")
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // OBS inalcanzable: error del servicio externo
+        let (_, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let obs = Arc::new(OBSAdapter::new("127.0.0.1", 1, None));
+        let state = web::Data::new(AppState::new(obs, MonitorSampler::new(monitor).spawn()));
+        let app = test::init_service(App::new().app_data(state).configure(configure)).await;
+        let response = test::call_service(&app, test::TestRequest::get().uri("/api/v1/stats").to_request()).await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["error"], "upstream_error");
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_endpoint() {
+        let state = simulated_state();
+        let record = state.health_service().sample().await.unwrap();
+        state.metrics.update(&record, &[]);
+        let app = test::init_service(App::new().app_data(state).configure(configure)).await;
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("content-type").unwrap(), obs_agent_infra::OPENMETRICS_CONTENT_TYPE);
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(body.contains("obs_agent_up 1\n"));
+        assert!(body.ends_with("# EOF\n"));
+
+        let response = test::call_service(&app, test::TestRequest::post().uri("/metrics").to_request()).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,543 @@
+use crate::error::ApiError;
+use crate::state::AppState;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, Method};
+use actix_web::middleware::{Logger, Next};
+use actix_web::{web, HttpMessage, HttpRequest, ResponseError};
+use obs_agent_core::application::ports::TokenStore;
+use obs_agent_core::domain::models::{ApiToken, AuditEntry, Scope};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Peticiones por minuto y token si no se configura otro límite
+pub const DEFAULT_RATE_LIMIT: u32 = 120;
+/// Tokens inválidos por minuto y dirección remota antes de bloquearla
+pub const DEFAULT_FAILED_AUTH_LIMIT: u32 = 10;
+/// Cubetas guardadas antes de descartar las que ya están llenas
+const MAX_BUCKETS: usize = 10_000;
+
+/// Límite de peticiones por token (cubeta de fichas)
+///
+/// Cada token puede gastar de golpe hasta `per_minute` peticiones y recupera
+/// una ficha cada `60 / per_minute` segundos.
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute.max(1),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Gasta una ficha de `key`; si no quedan devuelve cuánto esperar
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = self.refill(&mut buckets, key, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(self.wait(bucket))
+        }
+    }
+
+    /// Cuánto esperar si `key` no tiene fichas, sin gastar ninguna
+    pub fn retry_after(&self, key: &str, now: Instant) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = self.refill(&mut buckets, key, now);
+        (bucket.tokens < 1.0).then(|| self.wait(bucket))
+    }
+
+    fn refill<'a>(&self, buckets: &'a mut HashMap<String, Bucket>, key: &str, now: Instant) -> &'a mut Bucket {
+        let capacity = self.per_minute as f64;
+        // Las claves pueden ser direcciones remotas: se olvidan las cubetas ya llenas
+        if buckets.len() >= MAX_BUCKETS {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.saturating_duration_since(bucket.updated).as_secs_f64() * capacity / 60.0 < capacity
+            });
+        }
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity / 60.0).min(capacity);
+        bucket.updated = now;
+        bucket
+    }
+
+    fn wait(&self, bucket: &Bucket) -> Duration {
+        Duration::from_secs_f64((1.0 - bucket.tokens) * 60.0 / self.per_minute as f64)
+    }
+}
+
+/// Autenticación de la API: tokens con alcance, límite por token y auditoría
+pub struct Auth {
+    pub store: Arc<dyn TokenStore>,
+    pub limiter: RateLimiter,
+    /// Intentos con un token inválido por dirección remota (fuerza bruta)
+    pub failures: RateLimiter,
+}
+
+impl Auth {
+    pub fn new(store: Arc<dyn TokenStore>) -> Self {
+        Self {
+            store,
+            limiter: RateLimiter::new(DEFAULT_RATE_LIMIT),
+            failures: RateLimiter::new(DEFAULT_FAILED_AUTH_LIMIT),
+        }
+    }
+
+    pub fn with_rate_limit(mut self, per_minute: u32) -> Self {
+        self.limiter = RateLimiter::new(per_minute);
+        self
+    }
+
+    pub fn with_failed_auth_limit(mut self, per_minute: u32) -> Self {
+        self.failures = RateLimiter::new(per_minute);
+        self
+    }
+}
+
+/// Detalle de una acción para la auditoría (p.ej. la escena elegida)
+#[derive(Debug, Clone)]
+pub struct AuditDetails(pub String);
+
+/// Añade el detalle de la acción a la entrada de auditoría de la petición
+pub fn audit_details(request: &HttpRequest, details: impl Into<String>) {
+    request.extensions_mut().insert(AuditDetails(details.into()));
+}
+
+/// Alcance mínimo para una ruta: leer basta para `GET`, modificar exige
+/// `operator` y la auditoría solo la ve `admin`
+///
+/// `/mcp` deja pasar cualquier token: cada herramienta comprueba su alcance.
+pub fn required_scope(method: &Method, path: &str) -> Scope {
+    let path = path.trim_end_matches('/');
+    if path.ends_with("/audit") {
+        Scope::Admin
+    } else if method == Method::GET || method == Method::HEAD || path == "/mcp" {
+        Scope::Read
+    } else {
+        Scope::Operator
+    }
+}
+
+/// Las peticiones que modifican algo quedan en la auditoría
+fn is_mutation(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Los latidos de la flota solo se auditan si se rechazan: llegan cada pocos segundos por equipo
+fn is_heartbeat(path: &str) -> bool {
+    path.trim_end_matches('/').ends_with("/heartbeat")
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenQuery {
+    access_token: Option<String>,
+}
+
+/// Rutas de eventos en vivo: EventSource y WebSocket no pueden enviar
+/// cabeceras desde el navegador
+fn accepts_query_token(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    path.ends_with("/events") || path.ends_with("/events/ws")
+}
+
+/// Secreto de `Authorization: Bearer ...` o, solo en las rutas de eventos,
+/// de `?access_token=`
+fn bearer_token(request: &ServiceRequest) -> Option<String> {
+    let header = request
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer ").or_else(|| value.strip_prefix("bearer ")))
+        .map(|token| token.trim().to_string());
+    header.or_else(|| {
+        if !accepts_query_token(request.path()) {
+            return None;
+        }
+        web::Query::<TokenQuery>::from_query(request.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().access_token)
+    })
+}
+
+/// Línea de petición para el log de acceso, sin el valor de `access_token`
+pub fn redacted_request_line(request: &ServiceRequest) -> String {
+    let query = request
+        .query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some(("access_token", _)) => "access_token=[redacted]",
+            _ => pair,
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    let target = if query.is_empty() {
+        request.path().to_string()
+    } else {
+        format!("{}?{}", request.path(), query)
+    };
+    format!("{} {} {:?}", request.method(), target, request.version())
+}
+
+/// Log de acceso con el formato por defecto de actix, sin secretos en la URL
+pub fn access_logger() -> Logger {
+    Logger::new(r#"%a "%{request_line}xi" %s %b "%{Referer}i" "%{User-Agent}i" %T"#)
+        .custom_request_replace("request_line", redacted_request_line)
+}
+
+/// Sin autenticación, una mutación solo se acepta sin `Origin` (clientes que no
+/// son navegadores), desde el mismo origen o desde uno configurado en CORS:
+/// así otra web abierta en el navegador no puede controlar OBS (CSRF)
+///
+/// El mismo origen solo cuenta si el `Host` es una IP o `localhost`: con un
+/// nombre cualquiera podría ser otra web re-resuelta a esta máquina (DNS rebinding).
+pub fn origin_allowed(request: &HttpRequest, allowed: &[String]) -> bool {
+    let Some(origin) = request.headers().get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    if allowed.iter().any(|allowed| allowed == origin) {
+        return true;
+    }
+    let authority = origin.split_once("://").map(|(_, authority)| authority);
+    let host = request.headers().get(header::HOST).and_then(|host| host.to_str().ok());
+    match (authority, host) {
+        (Some(authority), Some(host)) => authority.eq_ignore_ascii_case(host) && is_local_name(host),
+        _ => false,
+    }
+}
+
+/// `Host` que ningún DNS ajeno puede apuntar a esta máquina
+fn is_local_name(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    name.eq_ignore_ascii_case("localhost") || name.parse::<std::net::IpAddr>().is_ok()
+}
+
+/// Middleware de `/api/v1`: sin autenticación configurada deja pasar todo
+///
+/// Los rechazos se devuelven como respuesta (no como `Err`) para que pasen
+/// por el resto de middlewares con sus cabeceras `WWW-Authenticate` / `Retry-After`.
+pub async fn authorize(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let Some(state) = request.app_data::<web::Data<AppState>>().cloned() else {
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+    let Some(auth) = state.auth.as_ref() else {
+        if is_mutation(request.method()) && !origin_allowed(request.request(), &state.allowed_origins) {
+            return Ok(reject(request, ApiError::Forbidden("Cross-origin request rejected".to_string())));
+        }
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+
+    let method = request.method().clone();
+    let path = request.path().to_string();
+    let remote_addr = request.peer_addr().map(|addr| addr.ip().to_string());
+    let audit = |token: Option<&ApiToken>, status: u16, details: Option<String>| AuditEntry {
+        timestamp: chrono::Utc::now(),
+        token_id: token.map(|t| t.id.clone()),
+        token_name: token.map(|t| t.name.clone()),
+        method: method.to_string(),
+        path: path.clone(),
+        status,
+        details,
+        remote_addr: remote_addr.clone(),
+    };
+
+    // Una dirección que ya falló demasiadas veces no llega a probar el token
+    let blocked = remote_addr.as_deref().and_then(|addr| auth.failures.retry_after(addr, Instant::now()));
+    if let Some(retry_after) = blocked {
+        if is_mutation(&method) {
+            record(auth, audit(None, 429, Some("too many invalid tokens".to_string()))).await;
+        }
+        return Ok(reject(request, ApiError::TooManyRequests(retry_after)));
+    }
+
+    let token = match bearer_token(&request) {
+        Some(secret) => match auth.store.authenticate(&secret).await {
+            Ok(None) => {
+                if let Some(addr) = &remote_addr {
+                    if auth.failures.check(addr, Instant::now()).is_err() {
+                        warn!("Too many invalid API tokens from {}", addr);
+                    }
+                }
+                None
+            }
+            Ok(token) => token,
+            Err(e) => return Ok(reject(request, ApiError::Internal(e))),
+        },
+        None => None,
+    };
+    let denied = match &token {
+        None => Some(ApiError::Unauthorized("Missing or invalid API token".to_string())),
+        Some(token) => {
+            let required = required_scope(&method, &path);
+            if !token.scope.allows(required) {
+                Some(ApiError::Forbidden(format!(
+                    "Token '{}' has scope '{}', '{}' is required",
+                    token.name, token.scope, required
+                )))
+            } else {
+                auth.limiter.check(&token.id, Instant::now()).err().map(ApiError::TooManyRequests)
+            }
+        }
+    };
+    if let Some(error) = denied {
+        if is_mutation(&method) {
+            record(auth, audit(token.as_ref(), error.status_code().as_u16(), None)).await;
+        }
+        return Ok(reject(request, error));
+    }
+
+    let token = token.expect("authorized requests carry a token");
+    request.extensions_mut().insert(token.clone());
+    let response = next.call(request).await?;
+
+    if is_mutation(&method) && !is_heartbeat(&path) {
+        let details = response.request().extensions().get::<AuditDetails>().map(|d| d.0.clone());
+        record(auth, audit(Some(&token), response.status().as_u16(), details)).await;
+    }
+    Ok(response.map_into_left_body())
+}
+
+fn reject<B>(request: ServiceRequest, error: ApiError) -> ServiceResponse<EitherBody<B>> {
+    request.into_response(error.error_response()).map_into_right_body()
+}
+
+async fn record(auth: &Auth, entry: AuditEntry) {
+    if let Err(e) = auth.store.record_audit(&entry).await {
+        warn!("Could not record audit entry for {} {}: {:#}", entry.method, entry.path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::configure;
+    use crate::models::CurrentScene;
+    use actix_web::http::StatusCode;
+    use actix_web::{test as atest, App};
+    use obs_agent_core::domain::models::TimeRange;
+    use obs_agent_infra::*;
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let limiter = RateLimiter::new(60);
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check("a", start).is_ok());
+        }
+        let retry_after = limiter.check("a", start).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(1));
+        // Cada token tiene su propia cubeta
+        assert!(limiter.check("b", start).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(1)).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/api/v1/scenes"), Scope::Read);
+        assert_eq!(required_scope(&Method::PUT, "/api/v1/scenes/current"), Scope::Operator);
+        assert_eq!(required_scope(&Method::POST, "/api/v1/optimize"), Scope::Operator);
+        assert_eq!(required_scope(&Method::GET, "/api/v1/audit"), Scope::Admin);
+        assert_eq!(required_scope(&Method::POST, "/mcp"), Scope::Read);
+        assert_eq!(required_scope(&Method::POST, "/api/v1/fleet/hosts/studio-1/heartbeat"), Scope::Operator);
+        assert!(is_heartbeat("/api/v1/fleet/hosts/studio-1/heartbeat"));
+        assert!(!is_heartbeat("/api/v1/fleet/hosts/studio-1/commands"));
+    }
+
+    #[actix_web::test]
+    async fn test_scopes_rate_limit_and_audit() {
+        let path = std::env::temp_dir().join(format!("obs-agent-auth-{}.db", unique_suffix()));
+        let store = Arc::new(SqliteTokenStore::open(&path).await.unwrap());
+        let (_, reader) = store.create_token("dashboard", Scope::Read).await.unwrap();
+        let (_, operator) = store.create_token("stream-deck", Scope::Operator).await.unwrap();
+        let (_, admin) = store.create_token("admin", Scope::Admin).await.unwrap();
+
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let state = web::Data::new(
+            AppState::new(obs, MonitorSampler::new(monitor).spawn())
+                .with_auth(Auth::new(store.clone()).with_rate_limit(5)),
+        );
+        let app = atest::init_service(App::new().app_data(state).configure(configure)).await;
+        let switch = |secret: Option<&str>| {
+            let request = atest::TestRequest::put()
+                .uri("/api/v1/scenes/current")
+                .set_json(CurrentScene { name: "BRB".to_string() });
+            match secret {
+                Some(secret) => request.insert_header(("Authorization", format!("Bearer {}", secret))),
+                None => request,
+            }
+            .to_request()
+        };
+
+        let response = atest::call_service(&app, switch(None)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers().get("WWW-Authenticate").unwrap(), "Bearer");
+        assert_eq!(atest::call_service(&app, switch(Some(&reader))).await.status(), StatusCode::FORBIDDEN);
+        assert_eq!(atest::call_service(&app, switch(Some(&operator))).await.status(), StatusCode::NO_CONTENT);
+
+        // El token en la query solo vale para los eventos (EventSource / WebSocket)
+        let request = atest::TestRequest::get().uri(&format!("/api/v1/events?access_token={}", reader));
+        assert_eq!(atest::call_service(&app, request.to_request()).await.status(), StatusCode::OK);
+        let request = atest::TestRequest::get().uri(&format!("/api/v1/scenes/current?access_token={}", reader));
+        assert_eq!(atest::call_service(&app, request.to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+        let audit_request = |secret: &str| {
+            atest::TestRequest::get()
+                .uri("/api/v1/audit?since=1h")
+                .insert_header(("Authorization", format!("Bearer {}", secret)))
+                .to_request()
+        };
+        assert_eq!(atest::call_service(&app, audit_request(&operator)).await.status(), StatusCode::FORBIDDEN);
+        let entries: Vec<AuditEntry> = atest::call_and_read_body_json(&app, audit_request(&admin)).await;
+        let statuses: Vec<u16> = entries.iter().map(|e| e.status).collect();
+        assert_eq!(statuses, vec![204, 403, 401]);
+        assert_eq!(entries[0].token_name.as_deref(), Some("stream-deck"));
+        assert_eq!(entries[0].details.as_deref(), Some("scene: BRB"));
+        assert_eq!(entries[1].token_name.as_deref(), Some("dashboard"));
+
+        // Límite de 5 por minuto: el operador ya gastó 1
+        for _ in 0..4 {
+            assert_eq!(atest::call_service(&app, switch(Some(&operator))).await.status(), StatusCode::NO_CONTENT);
+        }
+        let response = atest::call_service(&app, switch(Some(&operator))).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+
+        let now = chrono::Utc::now();
+        let audit = store
+            .query_audit(TimeRange::new(now - chrono::Duration::minutes(1), now + chrono::Duration::seconds(1)), 1)
+            .await
+            .unwrap();
+        assert_eq!(audit[0].status, 429);
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_tokens_are_limited_per_address() {
+        let path = std::env::temp_dir().join(format!("obs-agent-auth-{}.db", unique_suffix()));
+        let store = Arc::new(SqliteTokenStore::open(&path).await.unwrap());
+        let (_, reader) = store.create_token("dashboard", Scope::Read).await.unwrap();
+
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let state = web::Data::new(
+            AppState::new(obs, MonitorSampler::new(monitor).spawn())
+                .with_auth(Auth::new(store.clone()).with_failed_auth_limit(3)),
+        );
+        let app = atest::init_service(App::new().app_data(state).configure(configure)).await;
+        let get = |secret: &str, peer: &str| {
+            atest::TestRequest::get()
+                .uri("/api/v1/scenes/current")
+                .peer_addr(peer.parse().unwrap())
+                .insert_header(("Authorization", format!("Bearer {}", secret)))
+                .to_request()
+        };
+
+        for _ in 0..3 {
+            let response = atest::call_service(&app, get("obsa_guess", "10.0.0.1:4000")).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        // Bloqueada incluso con un token válido; otra dirección sigue entrando
+        let response = atest::call_service(&app, get(&reader, "10.0.0.1:4001")).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+        assert_eq!(atest::call_service(&app, get(&reader, "10.0.0.2:4000")).await.status(), StatusCode::OK);
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_no_auth_rejects_cross_origin_mutations() {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let state = web::Data::new(
+            AppState::new(obs, MonitorSampler::new(monitor).spawn())
+                .with_allowed_origins(vec!["http://deck.local".to_string()]),
+        );
+        let app = atest::init_service(App::new().app_data(state).configure(configure)).await;
+        let switch = |origin: Option<&str>| {
+            let request = atest::TestRequest::put()
+                .uri("/api/v1/scenes/current")
+                .insert_header(("Host", "127.0.0.1:8080"))
+                .set_json(CurrentScene { name: "BRB".to_string() });
+            match origin {
+                Some(origin) => request.insert_header(("Origin", origin)),
+                None => request,
+            }
+            .to_request()
+        };
+
+        let response = atest::call_service(&app, switch(Some("https://evil.example"))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(atest::call_service(&app, switch(Some("null"))).await.status(), StatusCode::FORBIDDEN);
+        // Clientes sin navegador, el propio dashboard y los orígenes configurados
+        assert_eq!(atest::call_service(&app, switch(None)).await.status(), StatusCode::NO_CONTENT);
+        let same_origin = switch(Some("http://127.0.0.1:8080"));
+        assert_eq!(atest::call_service(&app, same_origin).await.status(), StatusCode::NO_CONTENT);
+        let configured = switch(Some("http://deck.local"));
+        assert_eq!(atest::call_service(&app, configured).await.status(), StatusCode::NO_CONTENT);
+        // Otra web re-resuelta a esta máquina llega con su propio nombre en `Host`
+        let rebound = atest::TestRequest::put()
+            .uri("/api/v1/scenes/current")
+            .insert_header(("Host", "evil.example:8080"))
+            .insert_header(("Origin", "http://evil.example:8080"))
+            .set_json(CurrentScene { name: "BRB".to_string() })
+            .to_request();
+        assert_eq!(atest::call_service(&app, rebound).await.status(), StatusCode::FORBIDDEN);
+        // Las lecturas no cambian nada
+        let request = atest::TestRequest::get()
+            .uri("/api/v1/scenes/current")
+            .insert_header(("Origin", "https://evil.example"))
+            .to_request();
+        assert_eq!(atest::call_service(&app, request).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_access_log_redacts_query_token() {
+        let request = atest::TestRequest::get()
+            .uri("/api/v1/events?topics=metrics&access_token=obsa_secret")
+            .to_srv_request();
+        assert_eq!(
+            redacted_request_line(&request),
+            "GET /api/v1/events?topics=metrics&access_token=[redacted] HTTP/1.1"
+        );
+    }
+
+    fn unique_suffix() -> String {
+        format!("{}-{}", std::process::id(), chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default())
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use obs_agent_core::domain::models::{Anomaly, AnomalyTransition};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Anomalía activa con su estado de reconocimiento
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OpenAnomaly {
+    #[serde(flatten)]
+    pub anomaly: Anomaly,
+    /// Primera vez que se detectó
+    pub raised_at: DateTime<Utc>,
+    /// Token (o `local` sin autenticación) que la reconoció
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+/// Anomalías abiertas según las transiciones del feed en vivo
+///
+/// Reconocer una anomalía no la cierra: sigue abierta hasta que deja de
+/// detectarse, pero el panel deja de destacarla.
+#[derive(Debug, Default)]
+pub struct AnomalyBoard {
+    open: Mutex<Vec<OpenAnomaly>>,
+}
+
+impl AnomalyBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&self, transition: &AnomalyTransition) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        match transition {
+            AnomalyTransition::Raised { anomaly } => {
+                open.retain(|entry| entry.anomaly.id != anomaly.id);
+                open.push(OpenAnomaly {
+                    raised_at: anomaly.timestamp,
+                    anomaly: anomaly.clone(),
+                    acknowledged_by: None,
+                    acknowledged_at: None,
+                });
+            }
+            AnomalyTransition::Cleared {
+                anomaly_type,
+                severity,
+                source,
+                ..
+            } => open.retain(|entry| {
+                (entry.anomaly.anomaly_type, entry.anomaly.severity, &entry.anomaly.source)
+                    != (*anomaly_type, *severity, source)
+            }),
+        }
+    }
+
+    /// Abiertas, de la más grave a la menos y de la más reciente a la más antigua
+    pub fn list(&self) -> Vec<OpenAnomaly> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        open.sort_by(|a, b| {
+            b.anomaly
+                .severity
+                .cmp(&a.anomaly.severity)
+                .then(b.raised_at.cmp(&a.raised_at))
+        });
+        open
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<OpenAnomaly> {
+        self.open
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|entry| entry.anomaly.id == id)
+            .cloned()
+    }
+
+    /// Marca como reconocida; `None` si ya no está abierta
+    pub fn acknowledge(&self, id: Uuid, by: &str) -> Option<OpenAnomaly> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = open.iter_mut().find(|entry| entry.anomaly.id == id)?;
+        if entry.acknowledged_by.is_none() {
+            entry.acknowledged_by = Some(by.to_string());
+            entry.acknowledged_at = Some(Utc::now());
+        }
+        Some(entry.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use obs_agent_core::domain::models::{AnomalyType, Severity};
+
+    #[test]
+    fn test_board_follows_transitions() {
+        let board = AnomalyBoard::new();
+        let warm = Anomaly::new(AnomalyType::HighCPUTemp, Severity::Warning, "warm");
+        let unplugged =
+            Anomaly::new(AnomalyType::MissingSource, Severity::Critical, "webcam").with_source("Main:Webcam");
+        board.apply(&AnomalyTransition::Raised { anomaly: warm.clone() });
+        board.apply(&AnomalyTransition::Raised { anomaly: unplugged.clone() });
+
+        let ids: Vec<Uuid> = board.list().iter().map(|entry| entry.anomaly.id).collect();
+        assert_eq!(ids, vec![unplugged.id, warm.id]);
+
+        let acknowledged = board.acknowledge(warm.id, "stream-deck").unwrap();
+        assert_eq!(acknowledged.acknowledged_by.as_deref(), Some("stream-deck"));
+        // El primero en reconocerla queda registrado
+        assert_eq!(board.acknowledge(warm.id, "admin").unwrap().acknowledged_by.as_deref(), Some("stream-deck"));
+        assert!(board.acknowledge(Uuid::new_v4(), "admin").is_none());
+
+        board.apply(&AnomalyTransition::Cleared {
+            anomaly_type: AnomalyType::MissingSource,
+            severity: Severity::Critical,
+            source: Some("Main:Webcam".to_string()),
+            timestamp: Utc::now(),
+        });
+        assert!(board.get(unplugged.id).is_none());
+        assert_eq!(board.list().len(), 1);
+    }
+}
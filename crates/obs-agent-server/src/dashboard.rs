@@ -0,0 +1,36 @@
+use actix_web::{web, HttpResponse};
+
+const DASHBOARD_HTML: &str = include_str!("../static/dashboard.html");
+
+/// Panel web embebido; solo usa la API pública, así que el token lo pone el
+/// navegador y la página en sí no necesita autenticación
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/", web::get().to(dashboard))
+        .route("/dashboard", web::get().to(dashboard));
+}
+
+async fn dashboard() -> HttpResponse {
+    HttpResponse::Ok().content_type("text/html; charset=utf-8").body(DASHBOARD_HTML)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_dashboard_served() {
+        let app = test::init_service(App::new().configure(configure)).await;
+        for uri in ["/", "/dashboard"] {
+            let response = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers().get("content-type").unwrap(), "text/html; charset=utf-8");
+        }
+
+        // Las llamadas del panel existen en la API
+        for path in ["/anomalies/open", "/scenes/current", "/outputs/", "/events?"] {
+            assert!(DASHBOARD_HTML.contains(path), "dashboard does not use {}", path);
+        }
+    }
+}
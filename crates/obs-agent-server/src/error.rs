@@ -0,0 +1,114 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+
+/// Errores de la API y su código HTTP
+#[derive(Debug)]
+pub enum ApiError {
+    /// Parámetros o cuerpo inválidos (400)
+    BadRequest(String),
+    /// Falta el token o no es válido (401)
+    Unauthorized(String),
+    /// El token no tiene el alcance necesario (403)
+    Forbidden(String),
+    /// Recurso inexistente, p.ej. una escena (404)
+    NotFound(String),
+    /// El token superó su límite de peticiones; reintentar tras el intervalo (429)
+    TooManyRequests(std::time::Duration),
+    /// Función no disponible con la configuración actual, p.ej. sin clave de IA (503)
+    Unavailable(String),
+    /// OBS o la IA respondieron con error o no se pudo conectar (502)
+    Upstream(anyhow::Error),
+    /// OBS o la IA no respondieron a tiempo (504)
+    Timeout(String),
+    /// Fallo interno, p.ej. al leer sensores (500)
+    Internal(anyhow::Error),
+}
+
+/// Cuerpo JSON de las respuestas de error
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ErrorBody {
+    /// Código estable para clientes (`bad_request`, `not_found`...)
+    pub error: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "bad_request",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::TooManyRequests(_) => "rate_limited",
+            Self::NotFound(_) => "not_found",
+            Self::Unavailable(_) => "unavailable",
+            Self::Upstream(_) => "upstream_error",
+            Self::Timeout(_) => "upstream_timeout",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadRequest(message)
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::NotFound(message)
+            | Self::Unavailable(message)
+            | Self::Timeout(message) => f.write_str(message),
+            Self::TooManyRequests(retry_after) => {
+                write!(f, "Rate limit exceeded, retry in {}s", whole_secs(*retry_after))
+            }
+            Self::Upstream(error) | Self::Internal(error) => write!(f, "{:#}", error),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Internal(error)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Upstream(_) => StatusCode::BAD_GATEWAY,
+            Self::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if self.status_code().is_server_error() {
+            tracing::warn!("API error: {}", self);
+        }
+        let mut response = HttpResponse::build(self.status_code());
+        match self {
+            Self::Unauthorized(_) => {
+                response.insert_header(("WWW-Authenticate", "Bearer"));
+            }
+            Self::TooManyRequests(retry_after) => {
+                response.insert_header(("Retry-After", whole_secs(*retry_after).to_string()));
+            }
+            _ => {}
+        }
+        response.json(ErrorBody {
+            error: self.code(),
+            message: self.to_string(),
+        })
+    }
+}
+
+/// Segundos enteros para `Retry-After` (redondeo hacia arriba, mínimo 1)
+fn whole_secs(duration: std::time::Duration) -> u64 {
+    (duration.as_secs_f64().ceil() as u64).max(1)
+}
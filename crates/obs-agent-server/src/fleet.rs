@@ -0,0 +1,801 @@
+use crate::auth;
+use crate::error::{ApiError, ErrorBody};
+use crate::state::AppState;
+use actix_web::{delete, get, post, web, HttpMessage, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use obs_agent_core::application::ports::{
+    AgentRegistration, CommandKind, CommandResult, FleetCommand, Heartbeat, HeartbeatAck, RegistrationAck,
+};
+use obs_agent_core::domain::models::{Anomaly, AnomalyType, ApiToken, HardwareInfo, Severity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+type Result<T> = std::result::Result<T, ApiError>;
+
+/// Latido que se pide a los agentes si no se configura otro
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Comandos que se recuerdan por equipo
+const COMMAND_HISTORY: usize = 50;
+
+/// Conexión de un equipo según su último latido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HostStatus {
+    /// Latido en los últimos 3 intervalos
+    Online,
+    /// Sin latido en 3 intervalos pero sí en los últimos 10
+    Late,
+    Offline,
+}
+
+impl HostStatus {
+    pub fn of(last_seen: DateTime<Utc>, now: DateTime<Utc>, interval: Duration) -> Self {
+        let age = (now - last_seen).to_std().unwrap_or_default();
+        if age <= interval * 3 {
+            Self::Online
+        } else if age <= interval * 10 {
+            Self::Late
+        } else {
+            Self::Offline
+        }
+    }
+}
+
+impl std::str::FromStr for HostStatus {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "online" => Ok(Self::Online),
+            "late" => Ok(Self::Late),
+            "offline" => Ok(Self::Offline),
+            _ => Err(ApiError::BadRequest(format!("Unknown status '{}' (online, late, offline)", s))),
+        }
+    }
+}
+
+/// Estado de un comando enviado a un equipo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandState {
+    /// En cola hasta el próximo latido
+    Pending,
+    /// Entregado al agente, sin resultado todavía
+    Delivered,
+    Succeeded,
+    Failed,
+}
+
+/// Comando con su estado y resultado
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommandRecord {
+    #[serde(flatten)]
+    pub command: FleetCommand,
+    pub state: CommandState,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub result: Option<CommandResult>,
+}
+
+/// Equipo del inventario
+#[derive(Debug, Clone)]
+struct FleetHost {
+    registration: AgentRegistration,
+    /// Id del token que lo registró (`None` sin autenticación)
+    owner: Option<String>,
+    registered_at: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    /// Cuándo cambió la huella del hardware respecto al registro anterior
+    hardware_changed_at: Option<DateTime<Utc>>,
+    last_heartbeat: Option<Heartbeat>,
+    /// Del más antiguo al más reciente
+    commands: VecDeque<CommandRecord>,
+}
+
+impl FleetHost {
+    /// Otro token no puede suplantar al agente que registró el equipo
+    fn check_owner(&self, host_id: &str, owner: Option<&str>) -> Result<()> {
+        if self.owner.as_deref() == owner {
+            return Ok(());
+        }
+        Err(ApiError::Forbidden(format!(
+            "Host '{}' was registered with another token; remove it to register it again",
+            host_id
+        )))
+    }
+}
+
+/// Resumen de un equipo para las vistas de la flota
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HostView {
+    pub host_id: String,
+    pub hostname: String,
+    pub status: HostStatus,
+    pub last_seen: DateTime<Utc>,
+    pub registered_at: DateTime<Utc>,
+    pub agent_version: String,
+    pub fingerprint: String,
+    pub hardware_changed_at: Option<DateTime<Utc>>,
+    pub os: String,
+    pub cpu: String,
+    pub gpus: Vec<String>,
+    pub ram_gb: f64,
+    /// El último latido trajo una muestra de OBS
+    pub obs_connected: bool,
+    /// Por qué el agente no pudo muestrear OBS
+    pub error: Option<String>,
+    pub scene: Option<String>,
+    pub streaming: bool,
+    pub recording: bool,
+    pub cpu_usage: Option<f32>,
+    pub cpu_temp: Option<f32>,
+    pub gpu_temp: Option<f32>,
+    pub dropped_frames_percent: Option<f64>,
+    pub healthy: Option<bool>,
+    pub can_stream: Option<bool>,
+    pub anomalies: Vec<Anomaly>,
+    pub pending_commands: usize,
+}
+
+impl HostView {
+    fn new(host: &FleetHost, now: DateTime<Utc>, interval: Duration) -> Self {
+        let hardware = &host.registration.hardware;
+        let heartbeat = host.last_heartbeat.as_ref();
+        let sample = heartbeat.and_then(|h| h.sample.as_ref());
+        let health = heartbeat.and_then(|h| h.health.as_ref());
+        let output_active = |name: &str| {
+            sample.is_some_and(|s| s.outputs.iter().any(|output| output.name == name && output.active))
+        };
+
+        Self {
+            host_id: host.registration.host_id.clone(),
+            hostname: hardware.hostname.clone(),
+            status: HostStatus::of(host.last_seen, now, interval),
+            last_seen: host.last_seen,
+            registered_at: host.registered_at,
+            agent_version: host.registration.agent_version.clone(),
+            fingerprint: host.registration.fingerprint.clone(),
+            hardware_changed_at: host.hardware_changed_at,
+            os: format!("{} {}", hardware.os, hardware.os_version),
+            cpu: hardware.cpu.brand.clone(),
+            gpus: hardware.gpus.iter().map(|gpu| gpu.name.clone()).collect(),
+            ram_gb: hardware.ram.total_gb,
+            obs_connected: sample.is_some(),
+            error: heartbeat.and_then(|h| h.error.clone()),
+            scene: sample.and_then(|s| s.scene.clone()),
+            streaming: output_active("stream"),
+            recording: output_active("record"),
+            cpu_usage: sample.map(|s| s.context.cpu_usage),
+            cpu_temp: sample.map(|s| s.context.cpu_temp),
+            gpu_temp: sample.map(|s| s.context.gpu_temp),
+            dropped_frames_percent: sample.map(|s| s.context.obs_dropped_frames_percent),
+            healthy: health.map(|h| h.is_healthy),
+            can_stream: health.map(|h| h.can_stream),
+            anomalies: health.map(|h| h.anomalies.clone()).unwrap_or_default(),
+            pending_commands: host.commands.iter().filter(|c| c.state == CommandState::Pending).count(),
+        }
+    }
+
+    fn has_anomaly(&self, anomaly_type: AnomalyType) -> bool {
+        self.anomalies.iter().any(|anomaly| anomaly.anomaly_type == anomaly_type)
+    }
+}
+
+/// Detalle de un equipo: hardware, último latido y comandos
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HostDetail {
+    #[serde(flatten)]
+    pub host: HostView,
+    pub hardware: HardwareInfo,
+    pub last_heartbeat: Option<Heartbeat>,
+    /// Del más reciente al más antiguo
+    pub commands: Vec<CommandRecord>,
+}
+
+/// Cifras de toda la flota
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct FleetSummary {
+    pub hosts: usize,
+    pub online: usize,
+    pub late: usize,
+    pub offline: usize,
+    pub streaming: usize,
+    pub recording: usize,
+    /// Con alguna anomalía en el último latido
+    pub unhealthy: usize,
+    /// Con alguna anomalía crítica
+    pub critical: usize,
+    pub dropping_frames: usize,
+    pub pending_commands: usize,
+}
+
+/// Filtros de `GET /fleet/hosts`
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FleetQuery {
+    /// `online`, `late` u `offline`
+    #[param(value_type = Option<HostStatus>)]
+    pub status: Option<String>,
+    /// Con una anomalía de este tipo (p.ej. `HighCPUTemp`)
+    #[param(value_type = Option<AnomalyType>)]
+    pub anomaly: Option<String>,
+    /// Con alguna anomalía de al menos esta severidad (`info`, `warning`, `critical`)
+    pub severity: Option<String>,
+    /// Con anomalía `DroppedFrames` en el último latido
+    pub dropping_frames: Option<bool>,
+    pub streaming: Option<bool>,
+    pub recording: Option<bool>,
+    /// Escena de programa
+    pub scene: Option<String>,
+}
+
+impl FleetQuery {
+    /// Equipos que cumplen todos los filtros indicados
+    pub fn apply(&self, hosts: Vec<HostView>) -> Result<Vec<HostView>> {
+        let status = self.status.as_deref().map(str::parse::<HostStatus>).transpose()?;
+        let anomaly = self
+            .anomaly
+            .as_deref()
+            .map(|name| {
+                serde_json::from_value::<AnomalyType>(serde_json::Value::String(name.to_string()))
+                    .map_err(|_| ApiError::BadRequest(format!("Unknown anomaly type '{}'", name)))
+            })
+            .transpose()?;
+        let severity = match &self.severity {
+            Some(_) => Some(crate::models::ScanQuery { severity: self.severity.clone() }.min_severity()?),
+            None => None,
+        };
+
+        Ok(hosts
+            .into_iter()
+            .filter(|host| status.iter().all(|&status| host.status == status))
+            .filter(|host| anomaly.iter().all(|&anomaly| host.has_anomaly(anomaly)))
+            .filter(|host| severity.iter().all(|&min| host.anomalies.iter().any(|a| a.severity >= min)))
+            .filter(|host| {
+                self.dropping_frames.iter().all(|&dropping| host.has_anomaly(AnomalyType::DroppedFrames) == dropping)
+            })
+            .filter(|host| self.streaming.iter().all(|&streaming| host.streaming == streaming))
+            .filter(|host| self.recording.iter().all(|&recording| host.recording == recording))
+            .filter(|host| self.scene.iter().all(|scene| host.scene.as_ref() == Some(scene)))
+            .collect())
+    }
+}
+
+/// Inventario de la flota y colas de comandos, en memoria
+///
+/// Si el servidor se reinicia los agentes reciben 404 en su siguiente
+/// latido y vuelven a registrarse, así que el inventario se reconstruye solo.
+pub struct FleetRegistry {
+    hosts: Mutex<BTreeMap<String, FleetHost>>,
+    heartbeat_interval: Duration,
+}
+
+impl Default for FleetRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEARTBEAT)
+    }
+}
+
+impl FleetRegistry {
+    pub fn new(heartbeat_interval: Duration) -> Self {
+        Self {
+            hosts: Mutex::new(BTreeMap::new()),
+            heartbeat_interval: heartbeat_interval.max(Duration::from_secs(1)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, FleetHost>> {
+        self.hosts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Alta o nuevo registro de un equipo; conserva su cola de comandos
+    ///
+    /// `owner` es el id del token que lo pide: solo ese token puede volver a
+    /// registrar el equipo o enviar sus latidos.
+    pub fn register(
+        &self,
+        registration: AgentRegistration,
+        owner: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<RegistrationAck> {
+        validate_host_id(&registration.host_id)?;
+        let host_id = registration.host_id.clone();
+        let mut hosts = self.lock();
+        match hosts.get_mut(&host_id) {
+            Some(host) => {
+                host.check_owner(&host_id, owner)?;
+                if host.registration.fingerprint != registration.fingerprint {
+                    warn!(
+                        "Hardware of fleet host '{}' changed ({} -> {})",
+                        host_id, host.registration.fingerprint, registration.fingerprint
+                    );
+                    host.hardware_changed_at = Some(now);
+                }
+                host.registration = registration;
+                host.last_seen = now;
+            }
+            None => {
+                info!("Fleet host '{}' registered ({})", host_id, registration.hardware.hostname);
+                hosts.insert(
+                    host_id.clone(),
+                    FleetHost {
+                        registration,
+                        owner: owner.map(str::to_string),
+                        registered_at: now,
+                        last_seen: now,
+                        hardware_changed_at: None,
+                        last_heartbeat: None,
+                        commands: VecDeque::new(),
+                    },
+                );
+            }
+        }
+        Ok(RegistrationAck {
+            host_id,
+            heartbeat_interval_secs: self.heartbeat_interval.as_secs(),
+        })
+    }
+
+    /// Guarda el latido y entrega los comandos pendientes
+    pub fn heartbeat(
+        &self,
+        host_id: &str,
+        owner: Option<&str>,
+        heartbeat: Heartbeat,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<FleetCommand>> {
+        let mut hosts = self.lock();
+        let host = hosts.get_mut(host_id).ok_or_else(|| unknown_host(host_id))?;
+        host.check_owner(host_id, owner)?;
+        host.last_seen = now;
+
+        for result in &heartbeat.results {
+            match host.commands.iter_mut().find(|record| record.command.id == result.command_id) {
+                Some(record) => {
+                    record.state = if result.success { CommandState::Succeeded } else { CommandState::Failed };
+                    record.result = Some(result.clone());
+                }
+                None => warn!("Fleet host '{}' reported an unknown command {}", host_id, result.command_id),
+            }
+        }
+        host.last_heartbeat = Some(heartbeat);
+
+        let mut delivered = Vec::new();
+        for record in host.commands.iter_mut().filter(|record| record.state == CommandState::Pending) {
+            record.state = CommandState::Delivered;
+            record.delivered_at = Some(now);
+            delivered.push(record.command.clone());
+        }
+        Ok(delivered)
+    }
+
+    /// Pone un comando en la cola del equipo; `None` si no está registrado
+    pub fn enqueue(
+        &self,
+        host_id: &str,
+        kind: CommandKind,
+        issued_by: &str,
+        now: DateTime<Utc>,
+    ) -> Option<FleetCommand> {
+        let mut hosts = self.lock();
+        let host = hosts.get_mut(host_id)?;
+        let command = FleetCommand {
+            id: Uuid::new_v4(),
+            kind,
+            issued_at: now,
+            issued_by: issued_by.to_string(),
+        };
+        host.commands.push_back(CommandRecord {
+            command: command.clone(),
+            state: CommandState::Pending,
+            delivered_at: None,
+            result: None,
+        });
+        // Se olvidan primero los comandos ya terminados
+        while host.commands.len() > COMMAND_HISTORY {
+            match host.commands.iter().position(|record| record.state != CommandState::Pending) {
+                Some(index) => host.commands.remove(index),
+                None => host.commands.pop_front(),
+            };
+        }
+        Some(command)
+    }
+
+    /// Da de baja un equipo
+    pub fn remove(&self, host_id: &str) -> bool {
+        self.lock().remove(host_id).is_some()
+    }
+
+    pub fn hosts(&self, now: DateTime<Utc>) -> Vec<HostView> {
+        self.lock().values().map(|host| HostView::new(host, now, self.heartbeat_interval)).collect()
+    }
+
+    pub fn host(&self, host_id: &str, now: DateTime<Utc>) -> Option<HostDetail> {
+        let hosts = self.lock();
+        let host = hosts.get(host_id)?;
+        Some(HostDetail {
+            host: HostView::new(host, now, self.heartbeat_interval),
+            hardware: host.registration.hardware.clone(),
+            last_heartbeat: host.last_heartbeat.clone(),
+            commands: host.commands.iter().rev().cloned().collect(),
+        })
+    }
+
+    pub fn summary(&self, now: DateTime<Utc>) -> FleetSummary {
+        self.hosts(now).iter().fold(FleetSummary::default(), |mut summary, host| {
+            summary.hosts += 1;
+            match host.status {
+                HostStatus::Online => summary.online += 1,
+                HostStatus::Late => summary.late += 1,
+                HostStatus::Offline => summary.offline += 1,
+            }
+            summary.streaming += host.streaming as usize;
+            summary.recording += host.recording as usize;
+            summary.unhealthy += !host.anomalies.is_empty() as usize;
+            summary.critical += host.anomalies.iter().any(|a| a.severity == Severity::Critical) as usize;
+            summary.dropping_frames += host.has_anomaly(AnomalyType::DroppedFrames) as usize;
+            summary.pending_commands += host.pending_commands;
+            summary
+        })
+    }
+}
+
+/// Letras, dígitos, `-`, `_` y `.`: el id va en la URL
+fn validate_host_id(host_id: &str) -> Result<()> {
+    let valid = !host_id.is_empty()
+        && host_id.len() <= 64
+        && host_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Invalid host id '{}': use up to 64 letters, digits, '-', '_' or '.'",
+            host_id
+        )))
+    }
+}
+
+fn unknown_host(host_id: &str) -> ApiError {
+    ApiError::NotFound(format!("Host '{}' is not registered", host_id))
+}
+
+/// Token de la petición (`None` sin autenticación)
+fn token_id(request: &HttpRequest) -> Option<String> {
+    request.extensions().get::<ApiToken>().map(|token| token.id.clone())
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Register an agent or update its hardware (operator)",
+    request_body = AgentRegistration,
+    responses(
+        (status = 200, description = "OK", body = RegistrationAck),
+        (status = 400, description = "Invalid host id", body = ErrorBody),
+        (status = 403, description = "Host registered with another token", body = ErrorBody),
+    ),
+)]
+#[post("/fleet/register")]
+pub async fn register(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<AgentRegistration>,
+) -> Result<web::Json<RegistrationAck>> {
+    let registration = body.into_inner();
+    auth::audit_details(&request, format!("host: {}", registration.host_id));
+    let owner = token_id(&request);
+    Ok(web::Json(state.fleet.register(registration, owner.as_deref(), Utc::now())?))
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Agent heartbeat; answers with the commands queued for the host (operator)",
+    params(("host_id" = String, Path, description = "Host id the agent registered with")),
+    request_body = Heartbeat,
+    responses(
+        (status = 200, description = "OK", body = HeartbeatAck),
+        (status = 403, description = "Host registered with another token", body = ErrorBody),
+        (status = 404, description = "Host not registered, register again", body = ErrorBody),
+    ),
+)]
+#[post("/fleet/hosts/{host_id}/heartbeat")]
+pub async fn receive_heartbeat(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    host_id: web::Path<String>,
+    body: web::Json<Heartbeat>,
+) -> Result<web::Json<HeartbeatAck>> {
+    let owner = token_id(&request);
+    let commands = state.fleet.heartbeat(&host_id, owner.as_deref(), body.into_inner(), Utc::now())?;
+    Ok(web::Json(HeartbeatAck { commands }))
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Registered hosts matching every given filter",
+    params(FleetQuery),
+    responses(
+        (status = 200, description = "OK", body = Vec<HostView>),
+        (status = 400, description = "Invalid filter", body = ErrorBody),
+    ),
+)]
+#[get("/fleet/hosts")]
+pub async fn list_hosts(state: web::Data<AppState>, query: web::Query<FleetQuery>) -> Result<web::Json<Vec<HostView>>> {
+    Ok(web::Json(query.apply(state.fleet.hosts(Utc::now()))?))
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "A host with its hardware, last heartbeat and commands",
+    params(("host_id" = String, Path, description = "Host id the agent registered with")),
+    responses(
+        (status = 200, description = "OK", body = HostDetail),
+        (status = 404, description = "Host not registered", body = ErrorBody),
+    ),
+)]
+#[get("/fleet/hosts/{host_id}")]
+pub async fn host_detail(state: web::Data<AppState>, host_id: web::Path<String>) -> Result<web::Json<HostDetail>> {
+    let detail = state.fleet.host(&host_id, Utc::now()).ok_or_else(|| unknown_host(&host_id))?;
+    Ok(web::Json(detail))
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Remove a host from the inventory; it registers again on its next heartbeat (operator)",
+    params(("host_id" = String, Path, description = "Host id the agent registered with")),
+    responses(
+        (status = 204, description = "Done"),
+        (status = 404, description = "Host not registered", body = ErrorBody),
+    ),
+)]
+#[delete("/fleet/hosts/{host_id}")]
+pub async fn remove_host(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    host_id: web::Path<String>,
+) -> Result<HttpResponse> {
+    auth::audit_details(&request, format!("host: {}", host_id));
+    if !state.fleet.remove(&host_id) {
+        return Err(unknown_host(&host_id));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Encola un comando; el agente lo recibe en su próximo latido
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Queue a command; the agent runs it after its next heartbeat (operator)",
+    params(("host_id" = String, Path, description = "Host id the agent registered with")),
+    request_body = CommandKind,
+    responses(
+        (status = 202, description = "Queued", body = FleetCommand),
+        (status = 400, description = "Invalid command", body = ErrorBody),
+        (status = 404, description = "Host not registered", body = ErrorBody),
+    ),
+)]
+#[post("/fleet/hosts/{host_id}/commands")]
+pub async fn send_command(
+    request: HttpRequest,
+    state: web::Data<AppState>,
+    host_id: web::Path<String>,
+    body: web::Json<CommandKind>,
+) -> Result<HttpResponse> {
+    let kind = body.into_inner();
+    auth::audit_details(&request, format!("{}: {}", host_id, kind));
+    match &kind {
+        CommandKind::SwitchScene { scene } if scene.trim().is_empty() => {
+            return Err(ApiError::BadRequest("Scene name must not be empty".to_string()));
+        }
+        CommandKind::StartOutput { output } | CommandKind::StopOutput { output }
+            if !matches!(output.as_str(), "stream" | "record") =>
+        {
+            return Err(ApiError::BadRequest(format!("Unknown output '{}' (stream, record)", output)));
+        }
+        _ => {}
+    }
+
+    // Sin autenticación no hay nombre de token
+    let issued_by = request
+        .extensions()
+        .get::<ApiToken>()
+        .map_or_else(|| "local".to_string(), |token| token.name.clone());
+    let command = state
+        .fleet
+        .enqueue(&host_id, kind, &issued_by, Utc::now())
+        .ok_or_else(|| unknown_host(&host_id))?;
+    Ok(HttpResponse::Accepted().json(command))
+}
+
+#[utoipa::path(
+    tag = "fleet",
+    summary = "Host counts by status and problem",
+    responses((status = 200, description = "OK", body = FleetSummary)),
+)]
+#[get("/fleet/summary")]
+pub async fn fleet_summary(state: web::Data<AppState>) -> web::Json<FleetSummary> {
+    web::Json(state.fleet.summary(Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::configure;
+    use actix_web::http::StatusCode;
+    use actix_web::{test as atest, App};
+    use async_trait::async_trait;
+    use obs_agent_core::application::ports::FleetPort;
+    use obs_agent_core::domain::services::{FleetAgent, HealthCheckService};
+    use obs_agent_infra::*;
+    use std::sync::Arc;
+
+    /// Agente conectado al inventario sin pasar por HTTP
+    struct LocalFleet(web::Data<AppState>);
+
+    #[async_trait]
+    impl FleetPort for LocalFleet {
+        async fn register(&self, registration: &AgentRegistration) -> anyhow::Result<RegistrationAck> {
+            self.0.fleet.register(registration.clone(), None, Utc::now()).map_err(|e| anyhow::anyhow!("{}", e))
+        }
+
+        async fn heartbeat(&self, host_id: &str, heartbeat: &Heartbeat) -> anyhow::Result<Option<Vec<FleetCommand>>> {
+            match self.0.fleet.heartbeat(host_id, None, heartbeat.clone(), Utc::now()) {
+                Ok(commands) => Ok(Some(commands)),
+                Err(ApiError::NotFound(_)) => Ok(None),
+                Err(e) => Err(anyhow::anyhow!("{}", e)),
+            }
+        }
+    }
+
+    fn agent(central: &web::Data<AppState>, host_id: &str, scenario: SimulationScenario) -> FleetAgent {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(scenario)));
+        let hardware = monitor.detect_hardware().unwrap();
+        FleetAgent::new(
+            Arc::new(LocalFleet(central.clone())),
+            obs.clone(),
+            HealthCheckService::new(obs, monitor),
+            AgentRegistration::new(host_id, hardware),
+        )
+    }
+
+    #[test]
+    fn test_host_status() {
+        let now = Utc::now();
+        let interval = Duration::from_secs(15);
+        assert_eq!(HostStatus::of(now - chrono::Duration::seconds(20), now, interval), HostStatus::Online);
+        assert_eq!(HostStatus::of(now - chrono::Duration::seconds(60), now, interval), HostStatus::Late);
+        assert_eq!(HostStatus::of(now - chrono::Duration::minutes(10), now, interval), HostStatus::Offline);
+        assert!("sideways".parse::<HostStatus>().is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_agents_report_and_run_commands() {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let central = web::Data::new(AppState::new(obs, MonitorSampler::new(monitor).spawn()));
+        let studio = agent(&central, "studio-1", SimulationScenario::Nominal);
+        let booth = agent(&central, "booth-2", SimulationScenario::Nominal);
+        assert_eq!(studio.beat().await.unwrap(), 0);
+        assert_eq!(booth.beat().await.unwrap(), 0);
+
+        let app = atest::init_service(App::new().app_data(central.clone()).configure(configure)).await;
+        let hosts: Vec<HostView> =
+            atest::call_and_read_body_json(&app, atest::TestRequest::get().uri("/api/v1/fleet/hosts").to_request())
+                .await;
+        assert_eq!(hosts.len(), 2);
+        assert!(hosts.iter().all(|host| host.status == HostStatus::Online && host.obs_connected));
+        assert_eq!(hosts[1].fingerprint.len(), 16);
+
+        // Comando para un equipo: llega en su próximo latido y el resultado en el siguiente
+        let request = atest::TestRequest::post()
+            .uri("/api/v1/fleet/hosts/studio-1/commands")
+            .set_json(CommandKind::SwitchScene { scene: "BRB".to_string() })
+            .to_request();
+        let response = atest::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let command: FleetCommand = atest::read_body_json(response).await;
+        assert_eq!(command.issued_by, "local");
+
+        assert_eq!(booth.beat().await.unwrap(), 0);
+        assert_eq!(studio.beat().await.unwrap(), 1);
+        assert_eq!(studio.beat().await.unwrap(), 0);
+        let detail: HostDetail = atest::call_and_read_body_json(
+            &app,
+            atest::TestRequest::get().uri("/api/v1/fleet/hosts/studio-1").to_request(),
+        )
+        .await;
+        assert_eq!(detail.commands[0].state, CommandState::Succeeded);
+        assert_eq!(detail.host.scene.as_deref(), Some("BRB"));
+
+        let filtered: Vec<HostView> = atest::call_and_read_body_json(
+            &app,
+            atest::TestRequest::get().uri("/api/v1/fleet/hosts?scene=BRB&status=online").to_request(),
+        )
+        .await;
+        assert_eq!(filtered.iter().map(|h| h.host_id.as_str()).collect::<Vec<_>>(), vec!["studio-1"]);
+
+        for uri in [
+            "/api/v1/fleet/hosts?status=sideways",
+            "/api/v1/fleet/hosts?anomaly=Gremlins",
+            "/api/v1/fleet/hosts?severity=loud",
+        ] {
+            let response = atest::call_service(&app, atest::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+        }
+        let request = atest::TestRequest::post()
+            .uri("/api/v1/fleet/hosts/nowhere/commands")
+            .set_json(CommandKind::RunHealthCheck)
+            .to_request();
+        assert_eq!(atest::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+        // Tras un reinicio del servidor el agente se registra de nuevo solo
+        let request = atest::TestRequest::delete().uri("/api/v1/fleet/hosts/booth-2").to_request();
+        assert_eq!(atest::call_service(&app, request).await.status(), StatusCode::NO_CONTENT);
+        assert_eq!(central.fleet.summary(Utc::now()).hosts, 1);
+        booth.beat().await.unwrap();
+        assert_eq!(central.fleet.summary(Utc::now()).hosts, 2);
+    }
+
+    #[actix_web::test]
+    async fn test_filters_dropping_frames() {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let central = web::Data::new(AppState::new(obs, MonitorSampler::new(monitor).spawn()));
+        let studio = agent(&central, "studio-1", SimulationScenario::Nominal);
+        studio.beat().await.unwrap();
+
+        let mut heartbeat = studio.heartbeat().await;
+        heartbeat.health.as_mut().unwrap().anomalies =
+            vec![Anomaly::new(AnomalyType::DroppedFrames, Severity::Critical, "8% of frames dropped")];
+        central.fleet.heartbeat("studio-1", None, heartbeat, Utc::now()).unwrap();
+        agent(&central, "booth-2", SimulationScenario::Nominal).beat().await.unwrap();
+
+        let dropping = FleetQuery {
+            dropping_frames: Some(true),
+            ..Default::default()
+        };
+        let hosts = dropping.apply(central.fleet.hosts(Utc::now())).unwrap();
+        assert_eq!(hosts.iter().map(|h| h.host_id.as_str()).collect::<Vec<_>>(), vec!["studio-1"]);
+
+        let summary = central.fleet.summary(Utc::now());
+        assert_eq!((summary.hosts, summary.dropping_frames, summary.critical), (2, 1, 1));
+        let later = Utc::now() + chrono::Duration::minutes(10);
+        assert_eq!(central.fleet.summary(later).offline, 2);
+
+        let registration = AgentRegistration {
+            host_id: "bad host/1".to_string(),
+            ..AgentRegistration::new("x", central.monitor.detect_hardware().unwrap())
+        };
+        assert!(central.fleet.register(registration, None, Utc::now()).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_host_is_bound_to_its_token() {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        let central = web::Data::new(AppState::new(obs, MonitorSampler::new(monitor).spawn()));
+        let registration = AgentRegistration::new("studio-1", central.monitor.detect_hardware().unwrap());
+        let fleet = &central.fleet;
+        fleet.register(registration.clone(), Some("token-a"), Utc::now()).unwrap();
+
+        // Otro token no puede hacerse pasar por el agente
+        let stolen = fleet.register(registration.clone(), Some("token-b"), Utc::now()).unwrap_err();
+        assert!(matches!(stolen, ApiError::Forbidden(_)));
+        let heartbeat = Heartbeat {
+            timestamp: Utc::now(),
+            sample: None,
+            health: None,
+            error: None,
+            results: Vec::new(),
+        };
+        let forged = fleet.heartbeat("studio-1", Some("token-b"), heartbeat.clone(), Utc::now()).unwrap_err();
+        assert!(matches!(forged, ApiError::Forbidden(_)));
+        assert!(fleet.heartbeat("studio-1", None, heartbeat.clone(), Utc::now()).is_err());
+
+        fleet.heartbeat("studio-1", Some("token-a"), heartbeat, Utc::now()).unwrap();
+        fleet.register(registration.clone(), Some("token-a"), Utc::now()).unwrap();
+        // Tras darlo de baja, cualquier token puede registrarlo de nuevo
+        assert!(fleet.remove("studio-1"));
+        fleet.register(registration, Some("token-b"), Utc::now()).unwrap();
+    }
+}
@@ -0,0 +1,531 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::state::AppState;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use obs_agent_core::domain::events::DomainEvent;
+use obs_agent_core::domain::models::{AnomalyTransition, MonitorSnapshot};
+use obs_agent_core::domain::services::{AnomalyTracker, TelemetryRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use utoipa::{IntoParams, ToSchema};
+
+/// Mensajes en cola por cliente antes de empezar a descartar los más viejos
+const DEFAULT_CAPACITY: usize = 256;
+/// Comentario SSE / ping WebSocket para mantener viva la conexión
+const KEEPALIVE: Duration = Duration::from_secs(15);
+/// Un cliente WebSocket que no acepta un mensaje en este tiempo se desconecta
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Canales a los que se puede suscribir un cliente
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    /// `DomainEvent`s (anomalía detectada, stream iniciado/detenido, health check...)
+    Events,
+    /// Muestra de hardware y telemetría de OBS en cada ciclo del sampler
+    Metrics,
+    /// Anomalías que aparecen o se resuelven
+    Anomalies,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 3] = [Topic::Events, Topic::Metrics, Topic::Anomalies];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::Metrics => "metrics",
+            Self::Anomalies => "anomalies",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|topic| topic.name() == name.trim())
+    }
+
+    /// Lista separada por comas; vacía equivale a todos los canales
+    pub fn parse_list(list: Option<&str>) -> Result<BTreeSet<Topic>, ApiError> {
+        let names: Vec<&str> = list.unwrap_or_default().split(',').filter(|n| !n.trim().is_empty()).collect();
+        if names.is_empty() {
+            return Ok(Self::ALL.into_iter().collect());
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                Self::parse(name).ok_or_else(|| {
+                    ApiError::BadRequest(format!("Unknown topic '{}' (events, metrics, anomalies)", name))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Muestra publicada en el canal `metrics`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MetricsUpdate {
+    #[schema(value_type = MonitorSnapshot)]
+    pub snapshot: Arc<MonitorSnapshot>,
+    /// Telemetría de OBS; `None` si OBS no respondió
+    pub sample: Option<TelemetryRecord>,
+}
+
+/// Contenido de un mensaje según su canal
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "topic", content = "data", rename_all = "snake_case")]
+pub enum LivePayload {
+    Events(DomainEvent),
+    Metrics(Box<MetricsUpdate>),
+    Anomalies(AnomalyTransition),
+}
+
+impl LivePayload {
+    pub fn topic(&self) -> Topic {
+        match self {
+            Self::Events(_) => Topic::Events,
+            Self::Metrics(_) => Topic::Metrics,
+            Self::Anomalies(_) => Topic::Anomalies,
+        }
+    }
+}
+
+/// Mensaje del feed; un cliente lento recibe en su lugar
+/// `{"topic":"lagged","skipped":n}` con los mensajes que perdió
+#[derive(Serialize, ToSchema)]
+#[schema(as = LiveMessage)]
+struct Envelope<'a> {
+    seq: u64,
+    #[serde(flatten)]
+    payload: &'a LivePayload,
+}
+
+/// Mensaje ya serializado, compartido entre todos los clientes
+#[derive(Debug)]
+pub struct LiveMessage {
+    pub seq: u64,
+    pub topic: Topic,
+    /// `{"seq":..,"topic":..,"data":..}`
+    pub json: String,
+}
+
+/// Lo que recibe un suscriptor
+#[derive(Debug)]
+pub enum LiveItem {
+    Message(Arc<LiveMessage>),
+    /// El cliente fue demasiado lento y se descartaron mensajes
+    Lagged(u64),
+}
+
+impl LiveItem {
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Message(message) => message.json.clone(),
+            Self::Lagged(skipped) => format!("{{\"topic\":\"lagged\",\"skipped\":{}}}", skipped),
+        }
+    }
+}
+
+/// Difusión de eventos en vivo a los clientes SSE y WebSocket
+///
+/// Cada cliente tiene su propia cola acotada: si no consume a tiempo pierde
+/// los mensajes más viejos y recibe un aviso `lagged`, sin frenar al resto.
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<LiveMessage>>,
+    seq: AtomicU64,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity.max(1)).0,
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    pub fn publish(&self, payload: LivePayload) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed) + 1;
+        let json = match serde_json::to_string(&Envelope { seq, payload: &payload }) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Could not serialize live {} message: {}", payload.topic().name(), e);
+                return;
+            }
+        };
+        let _ = self.sender.send(Arc::new(LiveMessage {
+            seq,
+            topic: payload.topic(),
+            json,
+        }));
+    }
+
+    pub fn publish_event(&self, event: DomainEvent) {
+        self.publish(LivePayload::Events(event));
+    }
+
+    pub fn subscribe(&self, topics: BTreeSet<Topic>) -> LiveSubscription {
+        LiveSubscription {
+            receiver: self.sender.subscribe(),
+            topics,
+        }
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cola de un cliente con su filtro de canales
+pub struct LiveSubscription {
+    receiver: broadcast::Receiver<Arc<LiveMessage>>,
+    pub topics: BTreeSet<Topic>,
+}
+
+impl LiveSubscription {
+    /// Siguiente mensaje de los canales suscritos (`None` si el hub se cerró)
+    pub async fn next(&mut self) -> Option<LiveItem> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(message) if self.topics.contains(&message.topic) => return Some(LiveItem::Message(message)),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => return Some(LiveItem::Lagged(skipped)),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Sigue las anomalías abiertas en cada muestra del sampler y publica la
+/// muestra, las transiciones y los cambios de estado del stream a los clientes
+pub fn spawn_feed(state: web::Data<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tracker = AnomalyTracker::new();
+        // Estado del stream en la muestra anterior (`None` = desconocido)
+        let mut streaming: Option<bool> = None;
+        let mut stream_secs = 0.0;
+        let mut updates = state.sampler.subscribe();
+
+        loop {
+            let snapshot = match updates.recv().await {
+                Ok(snapshot) => snapshot,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let sample = match state.upstream("Sampling OBS", state.health_service().sample()).await {
+                Ok(sample) => Some(sample),
+                Err(e) => {
+                    debug!("Live feed without OBS telemetry: {}", e);
+                    None
+                }
+            };
+
+            if let Some(sample) = &sample {
+                let anomalies = state.detector.scan(&sample.context);
+                state.metrics.update(sample, &anomalies);
+                for transition in tracker.update(&anomalies, sample.timestamp) {
+                    state.board.apply(&transition);
+                    if let AnomalyTransition::Raised { anomaly } = &transition {
+                        state.events.publish_event(DomainEvent::anomaly_detected(anomaly.clone()));
+                    }
+                    state.events.publish(LivePayload::Anomalies(transition));
+                }
+
+                let stream = sample.outputs.iter().find(|output| output.name == "stream");
+                let active = stream.is_some_and(|output| output.active);
+                match streaming {
+                    Some(false) if active => state
+                        .events
+                        .publish_event(DomainEvent::stream_started(sample.scene.clone().unwrap_or_default())),
+                    Some(true) if !active => state.events.publish_event(DomainEvent::stream_stopped(stream_secs as u64)),
+                    _ => {}
+                }
+                streaming = Some(active);
+                stream_secs = stream.map_or(0.0, |output| output.duration_secs);
+            }
+
+            state.events.publish(LivePayload::Metrics(Box::new(MetricsUpdate { snapshot, sample })));
+        }
+    })
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TopicsQuery {
+    /// Canales separados por comas: `events`, `metrics`, `anomalies` (todos si se omite)
+    pub topics: Option<String>,
+}
+
+/// Server-Sent Events: un evento SSE por mensaje, con `event:` igual al canal
+#[utoipa::path(
+    tag = "live",
+    summary = "Server-Sent Events; the SSE event name is the topic",
+    params(TopicsQuery),
+    responses(
+        (status = 200, description = "Event stream", content_type = "text/event-stream", body = Envelope),
+        (status = 400, description = "Unknown topic", body = ErrorBody),
+    ),
+)]
+#[get("/events")]
+pub async fn events_sse(state: web::Data<AppState>, query: web::Query<TopicsQuery>) -> Result<HttpResponse, ApiError> {
+    let topics = Topic::parse_list(query.topics.as_deref())?;
+    let subscription = state.events.subscribe(topics);
+    let mut keepalive = tokio::time::interval(KEEPALIVE);
+    keepalive.reset();
+
+    let stream = futures::stream::unfold((subscription, keepalive), |(mut subscription, mut keepalive)| async move {
+        let chunk = tokio::select! {
+            item = subscription.next() => match item? {
+                LiveItem::Message(message) => {
+                    format!("id: {}\nevent: {}\ndata: {}\n\n", message.seq, message.topic.name(), message.json)
+                }
+                lagged @ LiveItem::Lagged(_) => format!("event: lagged\ndata: {}\n\n", lagged.to_json()),
+            },
+            _ = keepalive.tick() => ": keep-alive\n\n".to_string(),
+        };
+        Some((Ok::<_, actix_web::Error>(web::Bytes::from(chunk)), (subscription, keepalive)))
+    });
+    let preamble = futures::stream::once(async { Ok(web::Bytes::from_static(b"retry: 3000\n\n")) });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(futures::StreamExt::chain(preamble, stream)))
+}
+
+/// Mensajes de control que puede enviar un cliente WebSocket
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ClientCommand {
+    Subscribe(Vec<Topic>),
+    Unsubscribe(Vec<Topic>),
+}
+
+/// WebSocket: mensajes de texto JSON; el cliente cambia de canales con
+/// `{"subscribe":["metrics"]}` o `{"unsubscribe":["metrics"]}`
+#[utoipa::path(
+    tag = "live",
+    summary = "WebSocket with the same messages as JSON text frames; send {\"subscribe\":[..]} or {\"unsubscribe\":[..]} to change topics",
+    params(TopicsQuery),
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol"),
+        (status = 400, description = "Unknown topic", body = ErrorBody),
+    ),
+)]
+#[get("/events/ws")]
+pub async fn events_ws(
+    request: HttpRequest,
+    body: web::Payload,
+    state: web::Data<AppState>,
+    query: web::Query<TopicsQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let topics = Topic::parse_list(query.topics.as_deref())?;
+    let (response, mut session, mut messages) = actix_ws::handle(&request, body)?;
+    let mut subscription = state.events.subscribe(topics);
+
+    actix_web::rt::spawn(async move {
+        let mut keepalive = tokio::time::interval(KEEPALIVE);
+        keepalive.reset();
+
+        let reason = loop {
+            tokio::select! {
+                item = subscription.next() => {
+                    let Some(item) = item else { break None };
+                    match tokio::time::timeout(SEND_TIMEOUT, session.text(item.to_json())).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(_)) => return,
+                        Err(_) => {
+                            warn!("Closing slow WebSocket client");
+                            break Some(actix_ws::CloseReason {
+                                code: actix_ws::CloseCode::Policy,
+                                description: Some("Client too slow".to_string()),
+                            });
+                        }
+                    }
+                }
+                message = messages.recv() => match message {
+                    Some(Ok(actix_ws::Message::Text(text))) => {
+                        let reply = match serde_json::from_str::<ClientCommand>(&text) {
+                            Ok(ClientCommand::Subscribe(topics)) => {
+                                subscription.topics.extend(topics);
+                                serde_json::json!({ "topic": "subscribed", "topics": subscription.topics })
+                            }
+                            Ok(ClientCommand::Unsubscribe(topics)) => {
+                                subscription.topics.retain(|topic| !topics.contains(topic));
+                                serde_json::json!({ "topic": "subscribed", "topics": subscription.topics })
+                            }
+                            Err(e) => serde_json::json!({ "topic": "error", "message": e.to_string() }),
+                        };
+                        if session.text(reply.to_string()).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Close(reason))) => break reason,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break None,
+                },
+                _ = keepalive.tick() => {
+                    if session.ping(b"").await.is_err() {
+                        return;
+                    }
+                }
+            }
+        };
+        let _ = session.close(reason).await;
+    });
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::configure;
+    use actix_web::body::MessageBody;
+    use actix_web::{test, App, HttpServer};
+    use obs_agent_infra::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn simulated_state() -> web::Data<AppState> {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        web::Data::new(AppState::new(obs, MonitorSampler::new(monitor).spawn()))
+    }
+
+    fn cleared() -> LivePayload {
+        LivePayload::Anomalies(AnomalyTransition::Cleared {
+            anomaly_type: obs_agent_core::domain::models::AnomalyType::HighCPUTemp,
+            severity: obs_agent_core::domain::models::Severity::Warning,
+            source: None,
+            timestamp: chrono::Utc::now(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_topic_filter_and_lag() {
+        let hub = EventHub::with_capacity(2);
+        let mut events = hub.subscribe([Topic::Events].into());
+        let mut anomalies = hub.subscribe([Topic::Anomalies].into());
+
+        hub.publish(cleared());
+        hub.publish_event(DomainEvent::stream_stopped(5));
+        match events.next().await.unwrap() {
+            LiveItem::Message(message) => {
+                assert_eq!(message.topic, Topic::Events);
+                assert!(message.json.starts_with(&format!("{{\"seq\":{},\"topic\":\"events\"", message.seq)));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Cola de 2: el consumidor lento pierde los más viejos y se entera
+        for _ in 0..3 {
+            hub.publish(cleared());
+        }
+        assert!(matches!(anomalies.next().await, Some(LiveItem::Lagged(3))));
+        assert!(matches!(anomalies.next().await, Some(LiveItem::Message(_))));
+
+        assert!(Topic::parse_list(Some("metrics,nope")).is_err());
+        assert_eq!(Topic::parse_list(None).unwrap().len(), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_sse_stream() {
+        let state = simulated_state();
+        let app = test::init_service(App::new().app_data(state.clone()).configure(configure)).await;
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/api/v1/events?topics=events").to_request()).await;
+        assert_eq!(response.headers().get("content-type").unwrap(), "text/event-stream");
+
+        state.events.publish(cleared());
+        state.events.publish_event(DomainEvent::stream_started("Main"));
+
+        let mut body = response.into_body();
+        let mut text = String::new();
+        while !text.contains("StreamStarted") {
+            let chunk = std::future::poll_fn(|cx| std::pin::Pin::new(&mut body).poll_next(cx)).await;
+            text.push_str(&String::from_utf8_lossy(&chunk.unwrap().unwrap()));
+        }
+        assert!(text.starts_with("retry: 3000\n\n"));
+        assert!(text.contains("event: events\ndata: {\"seq\":"));
+        assert!(!text.contains("anomalies"));
+    }
+
+    /// Lee una trama de texto sin máscara (servidor → cliente)
+    async fn read_frame(stream: &mut tokio::net::TcpStream) -> String {
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await.unwrap();
+        let len = match header[1] & 0x7f {
+            126 => stream.read_u16().await.unwrap() as usize,
+            127 => stream.read_u64().await.unwrap() as usize,
+            len => len as usize,
+        };
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(header[0] & 0x0f, 0x1, "expected a text frame");
+        String::from_utf8(payload).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_websocket_subscription() {
+        let state = simulated_state();
+        let app_state = state.clone();
+        let server = HttpServer::new(move || App::new().app_data(app_state.clone()).configure(configure))
+            .workers(1)
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "GET /api/v1/events/ws?topics=anomalies HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\n\
+                     Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                     Sec-WebSocket-Version: 13\r\n\r\n",
+                    addr
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            response.push(stream.read_u8().await.unwrap());
+        }
+        assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 101"));
+
+        // Cambia a `events` con una trama enmascarada (cliente → servidor)
+        let command = br#"{"subscribe":["events"]}"#;
+        let mask = [1u8, 2, 3, 4];
+        let mut frame = vec![0x81, 0x80 | command.len() as u8];
+        frame.extend_from_slice(&mask);
+        frame.extend(command.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        stream.write_all(&frame).await.unwrap();
+        assert!(read_frame(&mut stream).await.contains("\"topics\":[\"events\",\"anomalies\"]"));
+
+        state.events.publish(LivePayload::Metrics(Box::new(MetricsUpdate {
+            snapshot: Arc::new(MonitorSampler::snapshot(state.monitor.as_ref(), 1)),
+            sample: None,
+        })));
+        state.events.publish_event(DomainEvent::health_check_completed(true, 0));
+        let message = read_frame(&mut stream).await;
+        assert!(message.contains("\"topic\":\"events\"") && message.contains("HealthCheckCompleted"));
+    }
+}
@@ -1,3 +1,213 @@
-pub fn main() {
-    println!("OBS Agent Server - Coming soon!");
+mod api;
+mod auth;
+mod board;
+mod dashboard;
+mod error;
+mod fleet;
+mod live;
+mod mcp;
+mod models;
+mod openapi;
+mod state;
+
+use actix_cors::Cors;
+use actix_web::{web, App, HttpServer};
+use anyhow::{Context, Result};
+use clap::Parser;
+use obs_agent_core::application::ports::*;
+use obs_agent_infra::*;
+use state::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Parser)]
+#[command(name = "obs-agent-server")]
+#[command(about = "OBS Studio Agent - REST API server", long_about = None)]
+struct ServerConfig {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080", env = "OBS_AGENT_LISTEN")]
+    listen: String,
+
+    /// OBS WebSocket host
+    #[arg(long, default_value = "localhost", env = "OBS_WEBSOCKET_HOST")]
+    obs_host: String,
+
+    /// OBS WebSocket port
+    #[arg(long, default_value = "4455", env = "OBS_WEBSOCKET_PORT")]
+    obs_port: u16,
+
+    /// OBS WebSocket password
+    #[arg(long, env = "OBS_WEBSOCKET_PASSWORD", hide_env_values = true)]
+    obs_password: Option<String>,
+
+    /// Gemini API key (enables /api/v1/optimize)
+    #[arg(long, env = "GEMINI_API_KEY", hide_env_values = true)]
+    gemini_api_key: Option<String>,
+
+    /// Browser origin allowed to call the API (repeatable, e.g. http://localhost:3000)
+    #[arg(long = "allow-origin", value_name = "ORIGIN")]
+    allowed_origins: Vec<String>,
+
+    /// API token database (default location if omitted); manage tokens with `obs-agent tokens`
+    #[arg(long, value_name = "DB", env = "OBS_AGENT_AUTH_DB")]
+    auth_db: Option<std::path::PathBuf>,
+
+    /// Serve the API without tokens (only allowed on a loopback address)
+    #[arg(long)]
+    no_auth: bool,
+
+    /// Requests per minute allowed per API token
+    #[arg(long, default_value_t = auth::DEFAULT_RATE_LIMIT, value_name = "N")]
+    rate_limit: u32,
+
+    /// Requests per minute with an invalid token allowed per client address
+    #[arg(long, default_value_t = auth::DEFAULT_FAILED_AUTH_LIMIT, value_name = "N")]
+    failed_auth_limit: u32,
+
+    /// Known upload capacity in kbps (used for the upload estimate and /optimize)
+    #[arg(long, value_name = "KBPS", env = "OBS_AGENT_UPLOAD_KBPS")]
+    upload_kbps: Option<u32>,
+
+    /// Seconds between hardware samples
+    #[arg(long, default_value = "2")]
+    sample_interval: u64,
+
+    /// Seconds before a request to OBS or the AI gives up
+    #[arg(long, default_value = "10")]
+    request_timeout: u64,
+
+    /// Seconds between heartbeats requested from fleet agents
+    #[arg(long, default_value = "15", value_name = "SECS")]
+    fleet_heartbeat: u64,
+
+    /// Speak MCP over stdin/stdout instead of serving HTTP (for assistants that launch the agent)
+    #[arg(long)]
+    mcp_stdio: bool,
+
+    /// Verbose logging
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Run against simulated OBS and sensors (nominal, thermal-ramp, network-collapse, memory-leak, source-unplug)
+    #[arg(long, value_name = "SCENARIO")]
+    simulate: Option<SimulationScenario>,
+
+    /// Simulation speed multiplier
+    #[arg(long, default_value = "1.0", requires = "simulate")]
+    sim_speed: f64,
+}
+
+impl ServerConfig {
+    /// Puertos reales o simulados según `--simulate`
+    fn ports(&self) -> (Arc<dyn OBSPort>, Arc<dyn MonitorPort>, Arc<dyn NetworkPort>) {
+        match self.simulate {
+            Some(scenario) => {
+                let simulation = Arc::new(Simulation::new(scenario).with_speed(self.sim_speed));
+                let (obs, monitor) = simulated_ports(Arc::clone(&simulation));
+                (obs, monitor, Arc::new(SimulatedNetworkAdapter::new(simulation)))
+            }
+            None => (
+                Arc::new(OBSAdapter::new(&self.obs_host, self.obs_port, self.obs_password.clone())),
+                Arc::new(MonitorAdapter::new()),
+                match self.upload_kbps {
+                    Some(kbps) => Arc::new(NetworkAdapter::new().with_upload_capacity(kbps)),
+                    None => Arc::new(NetworkAdapter::new()),
+                },
+            ),
+        }
+    }
+}
+
+/// `true` si todas las direcciones de `listen` son loopback
+fn is_loopback(listen: &str) -> Result<bool> {
+    let addrs: Vec<_> = std::net::ToSocketAddrs::to_socket_addrs(listen)
+        .with_context(|| format!("Invalid listen address {}", listen))?
+        .collect();
+    Ok(!addrs.is_empty() && addrs.iter().all(|addr| addr.ip().is_loopback()))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _ = dotenv::dotenv();
+    let config = ServerConfig::parse();
+
+    let log_level = if config.verbose { "debug" } else { "info" };
+    tracing_subscriber::fmt()
+        .with_env_filter(format!(
+            "obs_agent_server={},obs_agent_core={},obs_agent_infra={},actix_web=info",
+            log_level, log_level, log_level
+        ))
+        // stdout queda libre para el transporte MCP stdio
+        .with_writer(std::io::stderr)
+        .init();
+
+    if config.no_auth && !is_loopback(&config.listen)? {
+        anyhow::bail!("--no-auth is only allowed on a loopback address, not {}", config.listen);
+    }
+
+    let (obs, monitor, network) = config.ports();
+    let sampler = MonitorSampler::new(monitor)
+        .with_interval(Duration::from_secs(config.sample_interval.max(1)))
+        .spawn();
+    let mut state = AppState::new(obs, sampler)
+        .with_network(network)
+        .with_timeout(Duration::from_secs(config.request_timeout.max(1)))
+        .with_allowed_origins(config.allowed_origins.clone())
+        .with_fleet_heartbeat(Duration::from_secs(config.fleet_heartbeat));
+    if let Some(key) = &config.gemini_api_key {
+        state = state.with_ai(Arc::new(AIAdapter::new(key)));
+    }
+    if config.mcp_stdio {
+        info!("Serving MCP over stdio");
+        let input = tokio::io::BufReader::new(tokio::io::stdin());
+        return mcp::serve_stdio(&state, input, tokio::io::stdout()).await;
+    }
+    if config.no_auth {
+        warn!("API authentication disabled: anyone on this machine can control OBS");
+    } else {
+        let path = config.auth_db.clone().unwrap_or_else(SqliteTokenStore::default_path);
+        let store = SqliteTokenStore::open(&path).await?;
+        if !store.list_tokens().await?.iter().any(|token| token.is_active()) {
+            warn!(
+                "No API tokens in {}: create one with `obs-agent tokens create <name> --scope admin`",
+                path.display()
+            );
+        }
+        let auth = auth::Auth::new(Arc::new(store))
+            .with_rate_limit(config.rate_limit)
+            .with_failed_auth_limit(config.failed_auth_limit);
+        state = state.with_auth(auth);
+    }
+    let state = web::Data::new(state);
+    live::spawn_feed(state.clone());
+    let origins = config.allowed_origins.clone();
+
+    let server = HttpServer::new(move || {
+        // Sin orígenes configurados solo se aceptan peticiones del mismo origen
+        let cors = origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+            .allow_any_method()
+            .allow_any_header()
+            .max_age(3600);
+        App::new()
+            .wrap(cors)
+            .wrap(auth::access_logger())
+            .app_data(state.clone())
+            .configure(api::configure)
+            .configure(openapi::configure)
+            .configure(dashboard::configure)
+            .configure(mcp::configure)
+    })
+    .bind(&config.listen)
+    .with_context(|| format!("Failed to bind {}", config.listen))?;
+
+    for addr in server.addrs() {
+        info!("REST API listening on http://{}/api/v1 (live events at /api/v1/events)", addr);
+        info!("Dashboard at http://{}/ (MCP endpoint at /mcp)", addr);
+        info!("API docs at http://{}/docs (OpenAPI at /openapi.json)", addr);
+        info!("OpenMetrics at http://{}/metrics", addr);
+    }
+    server.run().await.context("REST API server failed")
 }
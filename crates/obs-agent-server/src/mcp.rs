@@ -0,0 +1,603 @@
+use crate::api::detect_hardware;
+use crate::auth;
+use crate::error::ApiError;
+use crate::models::*;
+use crate::state::AppState;
+use actix_web::{middleware, web, HttpMessage, HttpRequest, HttpResponse};
+use obs_agent_core::domain::events::DomainEvent;
+use obs_agent_core::domain::models::{ApiToken, Scope, VideoSettings};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Versiones del protocolo MCP soportadas; la primera es la preferida
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const HEALTH_URI: &str = "obs-agent://health/latest";
+const HARDWARE_URI: &str = "obs-agent://hardware";
+
+// Códigos de error JSON-RPC 2.0 (y el de MCP para recursos)
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Herramienta expuesta a los asistentes
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    /// Alcance mínimo del token cuando se llama por HTTP
+    scope: Scope,
+}
+
+const TOOLS: [Tool; 6] = [
+    Tool {
+        name: "list_scenes",
+        description: "List the OBS scenes, their sources and which one is on air",
+        scope: Scope::Read,
+    },
+    Tool {
+        name: "switch_scene",
+        description: "Switch the OBS program scene",
+        scope: Scope::Operator,
+    },
+    Tool {
+        name: "get_stats",
+        description: "Read OBS performance stats (FPS, CPU, dropped frames) and the stream/record outputs",
+        scope: Scope::Read,
+    },
+    Tool {
+        name: "run_health_check",
+        description: "Run a full pre-flight health check of OBS, the hardware and the network",
+        scope: Scope::Read,
+    },
+    Tool {
+        name: "scan_anomalies",
+        description: "Sample OBS and the hardware once and report the anomalies detected",
+        scope: Scope::Read,
+    },
+    Tool {
+        name: "apply_config",
+        description: "Apply video and/or encoder settings to OBS; omitted encoder fields keep their current value",
+        scope: Scope::Operator,
+    },
+];
+
+fn input_schema(tool: &str) -> Value {
+    match tool {
+        "switch_scene" => json!({
+            "type": "object",
+            "properties": { "name": { "type": "string", "description": "Scene to put on air" } },
+            "required": ["name"],
+        }),
+        "scan_anomalies" => json!({
+            "type": "object",
+            "properties": {
+                "severity": {
+                    "type": "string",
+                    "enum": ["info", "warning", "critical"],
+                    "description": "Minimum severity (all if omitted)",
+                },
+            },
+        }),
+        "apply_config" => json!({
+            "type": "object",
+            "properties": {
+                "video": {
+                    "type": "object",
+                    "properties": {
+                        "base_width": { "type": "integer" },
+                        "base_height": { "type": "integer" },
+                        "output_width": { "type": "integer" },
+                        "output_height": { "type": "integer" },
+                        "fps_numerator": { "type": "integer" },
+                        "fps_denominator": { "type": "integer" },
+                    },
+                    "required": [
+                        "base_width", "base_height", "output_width", "output_height",
+                        "fps_numerator", "fps_denominator",
+                    ],
+                },
+                "encoder": { "type": "string", "description": "Encoder id, e.g. obs_x264 or jim_nvenc" },
+                "preset": { "type": "string" },
+                "bitrate_kbps": { "type": "integer", "minimum": 1 },
+            },
+        }),
+        _ => json!({ "type": "object", "properties": {} }),
+    }
+}
+
+/// Cambios pedidos por `apply_config`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApplyConfig {
+    video: Option<VideoSettings>,
+    encoder: Option<String>,
+    preset: Option<String>,
+    bitrate_kbps: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResourceRead {
+    uri: String,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Atiende un mensaje JSON-RPC (o un lote) de un cliente MCP
+///
+/// `scope` es lo que puede hacer quien llama; las notificaciones no tienen
+/// respuesta, así que un lote solo de notificaciones devuelve `None`.
+pub async fn handle_message(state: &AppState, scope: Scope, message: Value) -> Option<Value> {
+    match message {
+        Value::Array(batch) if batch.is_empty() => {
+            Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Empty batch")))
+        }
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                responses.extend(handle_single(state, scope, message).await);
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_single(state, scope, message).await,
+    }
+}
+
+async fn handle_single(state: &AppState, scope: Scope, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // Respuestas del cliente: el servidor nunca hace peticiones, se ignoran
+        if message.get("result").is_some() || message.get("error").is_some() {
+            return None;
+        }
+        return Some(error_response(id.unwrap_or(Value::Null), RpcError::new(INVALID_REQUEST, "Missing method")));
+    };
+    if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Some(error_response(id.unwrap_or(Value::Null), RpcError::new(INVALID_REQUEST, "Expected jsonrpc 2.0")));
+    }
+
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    let result = dispatch(state, scope, method, params).await;
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+async fn dispatch(state: &AppState, scope: Scope, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str);
+            let version = requested
+                .filter(|version| PROTOCOL_VERSIONS.contains(version))
+                .unwrap_or(PROTOCOL_VERSIONS[0]);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": {
+                    "tools": { "listChanged": false },
+                    "resources": { "subscribe": false, "listChanged": false },
+                },
+                "serverInfo": { "name": "obs-agent", "version": env!("CARGO_PKG_VERSION") },
+                "instructions": "Tools to inspect and control a live OBS Studio instance. Run run_health_check \
+                    before going live; scan_anomalies explains dropped frames, overheating or missing sources.",
+            }))
+        }
+        "ping" => Ok(json!({})),
+        method if method.starts_with("notifications/") => Ok(Value::Null),
+        "tools/list" => Ok(json!({
+            "tools": TOOLS
+                .iter()
+                .map(|tool| json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": input_schema(tool.name),
+                }))
+                .collect::<Vec<_>>(),
+        })),
+        "tools/call" => {
+            let call: ToolCall = params_as(params)?;
+            let tool = TOOLS
+                .iter()
+                .find(|tool| tool.name == call.name)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Unknown tool '{}'", call.name)))?;
+            let result = if scope.allows(tool.scope) {
+                call_tool(state, tool.name, call.arguments.unwrap_or_else(|| json!({}))).await
+            } else {
+                Err(ApiError::Forbidden(format!("'{}' requires the '{}' scope", tool.name, tool.scope)))
+            };
+            // Los fallos de la herramienta van en el resultado para que el asistente los vea
+            Ok(match result {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+                    "structuredContent": value,
+                    "isError": false,
+                }),
+                Err(error) => json!({
+                    "content": [{ "type": "text", "text": error.to_string() }],
+                    "isError": true,
+                }),
+            })
+        }
+        "resources/list" => Ok(json!({
+            "resources": [
+                {
+                    "uri": HEALTH_URI,
+                    "name": "Latest health report",
+                    "description": "Most recent health check (runs one if none has been made yet)",
+                    "mimeType": "application/json",
+                },
+                {
+                    "uri": HARDWARE_URI,
+                    "name": "Hardware info",
+                    "description": "Detected CPU, GPUs and memory plus the latest sensor sample",
+                    "mimeType": "application/json",
+                },
+            ],
+        })),
+        "resources/read" => {
+            let read: ResourceRead = params_as(params)?;
+            let contents = read_resource(state, &read.uri).await?;
+            Ok(json!({
+                "contents": [{
+                    "uri": read.uri,
+                    "mimeType": "application/json",
+                    "text": serde_json::to_string_pretty(&contents).unwrap_or_default(),
+                }],
+            }))
+        }
+        other => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method '{}' not found", other))),
+    }
+}
+
+fn params_as<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn arguments<T: DeserializeOwned>(arguments: Value) -> Result<T, ApiError> {
+    serde_json::from_value(arguments).map_err(|e| ApiError::BadRequest(format!("Invalid arguments: {}", e)))
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError::Internal(e.into()))
+}
+
+async fn call_tool(state: &AppState, name: &str, args: Value) -> Result<Value, ApiError> {
+    match name {
+        "list_scenes" => {
+            let (scenes, current) = state
+                .upstream("Listing OBS scenes", async {
+                    tokio::try_join!(state.obs.get_scenes(), state.obs.get_current_scene())
+                })
+                .await?;
+            let scenes: Vec<SceneView> = scenes.into_iter().map(|scene| SceneView::new(scene, &current)).collect();
+            Ok(json!({ "current": current, "scenes": scenes }))
+        }
+        "switch_scene" => {
+            let CurrentScene { name } = arguments(args)?;
+            state.switch_scene(&name).await?;
+            Ok(json!({ "current": name }))
+        }
+        "get_stats" => {
+            let (stats, outputs) = state
+                .upstream("Reading OBS stats", async {
+                    tokio::try_join!(state.obs.get_stats(), state.obs.get_output_status())
+                })
+                .await?;
+            to_value(StatsView::new(stats, outputs))
+        }
+        "run_health_check" => to_value(HealthView::from(state.check_health().await?)),
+        "scan_anomalies" => {
+            let min_severity = arguments::<ScanQuery>(args)?.min_severity()?;
+            let sample = state.upstream("Sampling OBS", state.health_service().sample()).await?;
+            to_value(AnomalyScan {
+                timestamp: sample.timestamp,
+                min_severity,
+                anomalies: state.detector.scan_filtered(&sample.context, min_severity),
+            })
+        }
+        "apply_config" => apply_config(state, arguments(args)?).await,
+        other => Err(ApiError::NotFound(format!("Unknown tool '{}'", other))),
+    }
+}
+
+async fn apply_config(state: &AppState, config: ApplyConfig) -> Result<Value, ApiError> {
+    let mut changes = Vec::new();
+
+    if let Some(video) = &config.video {
+        let sizes = [video.base_width, video.base_height, video.output_width, video.output_height];
+        if sizes.contains(&0) || video.fps_numerator == 0 || video.fps_denominator == 0 {
+            return Err(ApiError::BadRequest("Video sizes and FPS must be greater than zero".to_string()));
+        }
+        state.upstream("Applying video settings", state.obs.set_video_settings(video)).await?;
+        changes.push(format!(
+            "Video {}x{} -> {}x{} @ {:.2} FPS",
+            video.base_width,
+            video.base_height,
+            video.output_width,
+            video.output_height,
+            video.fps()
+        ));
+    }
+
+    if config.encoder.is_some() || config.preset.is_some() || config.bitrate_kbps.is_some() {
+        if config.bitrate_kbps == Some(0) {
+            return Err(ApiError::BadRequest("bitrate_kbps must be greater than zero".to_string()));
+        }
+        let mut settings = state.upstream("Reading encoder settings", state.obs.get_encoder_settings()).await?;
+        if let Some(encoder) = config.encoder {
+            changes.push(format!("Encoder {} -> {}", settings.encoder, encoder));
+            settings.encoder = encoder;
+        }
+        if let Some(preset) = config.preset {
+            changes.push(format!("Preset {} -> {}", settings.preset, preset));
+            settings.preset = preset;
+        }
+        if let Some(bitrate) = config.bitrate_kbps {
+            changes.push(format!("Bitrate {} -> {} kbps", settings.bitrate_kbps, bitrate));
+            settings.bitrate_kbps = bitrate;
+        }
+        state.upstream("Applying encoder settings", state.obs.set_encoder_settings(&settings)).await?;
+    }
+
+    if changes.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Nothing to apply: pass video, encoder, preset or bitrate_kbps".to_string(),
+        ));
+    }
+    state.events.publish_event(DomainEvent::config_changed(changes.clone()));
+    Ok(json!({ "changes": changes }))
+}
+
+async fn read_resource(state: &AppState, uri: &str) -> Result<Value, RpcError> {
+    let internal = |e: ApiError| RpcError::new(INTERNAL_ERROR, e.to_string());
+    match uri {
+        HEALTH_URI => {
+            let report = match state.last_health() {
+                Some(report) => report,
+                None => state.check_health().await.map_err(internal)?,
+            };
+            to_value(HealthView::from(report)).map_err(internal)
+        }
+        HARDWARE_URI => {
+            let hardware = detect_hardware(state).await.map_err(internal)?;
+            let live = state.sampler.latest();
+            Ok(json!({ "hardware": hardware, "live": live.as_deref() }))
+        }
+        other => Err(RpcError::new(RESOURCE_NOT_FOUND, format!("Resource '{}' not found", other))),
+    }
+}
+
+/// Transporte stdio: un mensaje JSON-RPC por línea en cada sentido
+///
+/// El asistente lanza el proceso, así que tiene acceso completo; los logs
+/// deben ir a stderr para no mezclarse con los mensajes.
+pub async fn serve_stdio(
+    state: &AppState,
+    input: impl AsyncBufRead + Unpin,
+    mut output: impl AsyncWrite + Unpin,
+) -> anyhow::Result<()> {
+    let mut lines = input.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(state, Scope::Admin, message).await,
+            Err(e) => Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, format!("Invalid JSON: {}", e)))),
+        };
+        if let Some(response) = response {
+            output.write_all(response.to_string().as_bytes()).await?;
+            output.write_all(b"\n").await?;
+            output.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Transporte HTTP (`POST /mcp`), con los mismos tokens y auditoría que la API
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/mcp")
+            .wrap(middleware::from_fn(auth::authorize))
+            .route(web::post().to(mcp_http))
+            // Sin flujo de mensajes iniciados por el servidor
+            .route(web::get().to(|| async { HttpResponse::MethodNotAllowed().finish() })),
+    );
+}
+
+async fn mcp_http(request: HttpRequest, state: web::Data<AppState>, body: web::Bytes) -> HttpResponse {
+    // Una web abierta en el navegador no puede llamar a las herramientas (DNS rebinding)
+    if !auth::origin_allowed(&request, &state.allowed_origins) {
+        let error = RpcError::new(INVALID_REQUEST, "Origin not allowed");
+        return HttpResponse::Forbidden().json(error_response(Value::Null, error));
+    }
+    if !request.content_type().eq_ignore_ascii_case("application/json") {
+        let error = RpcError::new(INVALID_REQUEST, "Content-Type must be application/json");
+        return HttpResponse::UnsupportedMediaType().json(error_response(Value::Null, error));
+    }
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, format!("Invalid JSON: {}", e));
+            return HttpResponse::BadRequest().json(error_response(Value::Null, error));
+        }
+    };
+
+    let tools = called_tools(&message);
+    if !tools.is_empty() {
+        auth::audit_details(&request, format!("tools: {}", tools.join(", ")));
+    }
+    // Sin autenticación configurada (solo en loopback) se puede operar OBS, pero
+    // nada reservado a tokens `admin`
+    let scope = request.extensions().get::<ApiToken>().map_or(Scope::Operator, |token| token.scope);
+    match handle_message(&state, scope, message).await {
+        Some(response) => HttpResponse::Ok().json(response),
+        None => HttpResponse::Accepted().finish(),
+    }
+}
+
+/// Herramientas llamadas en un mensaje o lote, para la auditoría
+fn called_tools(message: &Value) -> Vec<String> {
+    let messages = match message {
+        Value::Array(batch) => batch.iter().collect(),
+        message => vec![message],
+    };
+    messages
+        .into_iter()
+        .filter(|message| message.get("method").and_then(Value::as_str) == Some("tools/call"))
+        .filter_map(|message| message.pointer("/params/name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use obs_agent_infra::*;
+    use std::sync::Arc;
+
+    fn simulated_state() -> AppState {
+        let (obs, monitor) = simulated_ports(Arc::new(Simulation::new(SimulationScenario::Nominal)));
+        AppState::new(obs, MonitorSampler::new(monitor).spawn())
+    }
+
+    fn call(id: u64, tool: &str, arguments: Value) -> Value {
+        let params = json!({ "name": tool, "arguments": arguments });
+        json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": params })
+    }
+
+    #[tokio::test]
+    async fn test_stdio_session() {
+        let messages = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2025-03-26" } }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            call(3, "switch_scene", json!({ "name": "BRB" })),
+            call(4, "list_scenes", json!({})),
+            call(5, "switch_scene", json!({ "name": "Nope" })),
+            json!({ "jsonrpc": "2.0", "id": 6, "method": "resources/read", "params": { "uri": HEALTH_URI } }),
+            json!({ "jsonrpc": "2.0", "id": 7, "method": "prompts/list" }),
+        ];
+        let mut input = messages.iter().map(Value::to_string).collect::<Vec<_>>().join("\n");
+        input.push_str("\n{not json\n");
+
+        let mut output = Vec::new();
+        serve_stdio(&simulated_state(), input.as_bytes(), &mut output).await.unwrap();
+        let responses: Vec<Value> =
+            String::from_utf8(output).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+
+        // La notificación no tiene respuesta
+        assert_eq!(responses.len(), 8);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), TOOLS.len());
+        assert_eq!(responses[2]["result"]["isError"], false);
+        assert_eq!(responses[3]["result"]["structuredContent"]["current"], "BRB");
+        assert_eq!(responses[4]["result"]["isError"], true);
+        assert!(responses[4]["result"]["content"][0]["text"].as_str().unwrap().contains("not found"));
+
+        let report: Value =
+            serde_json::from_str(responses[5]["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert!(report["summary"].is_string());
+        assert_eq!(responses[6]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[7]["error"]["code"], PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_tool_scopes_and_apply_config() {
+        let state = simulated_state();
+
+        let denied = handle_message(&state, Scope::Read, call(1, "apply_config", json!({ "bitrate_kbps": 4500 })))
+            .await
+            .unwrap();
+        assert_eq!(denied["result"]["isError"], true);
+
+        let applied = handle_message(&state, Scope::Operator, call(2, "apply_config", json!({ "bitrate_kbps": 4500 })))
+            .await
+            .unwrap();
+        let changes = applied["result"]["structuredContent"]["changes"].as_array().unwrap();
+        assert!(changes[0].as_str().unwrap().ends_with("-> 4500 kbps"));
+        assert_eq!(state.obs.get_encoder_settings().await.unwrap().bitrate_kbps, 4500);
+
+        let empty = handle_message(&state, Scope::Operator, call(3, "apply_config", json!({}))).await.unwrap();
+        assert_eq!(empty["result"]["isError"], true);
+        let unknown = handle_message(&state, Scope::Admin, call(4, "reboot", json!({}))).await.unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[actix_web::test]
+    async fn test_http_transport() {
+        let state = web::Data::new(simulated_state());
+        let app = test::init_service(App::new().app_data(state).configure(configure)).await;
+
+        let batch = json!([
+            call(1, "get_stats", json!({})),
+            { "jsonrpc": "2.0", "id": 2, "method": "resources/read", "params": { "uri": HARDWARE_URI } },
+        ]);
+        let request = test::TestRequest::post().uri("/mcp").set_json(&batch).to_request();
+        let responses: Value = test::call_and_read_body_json(&app, request).await;
+        assert!(responses[0]["result"]["structuredContent"]["outputs"].is_array());
+        assert!(responses[1]["result"]["contents"][0]["text"].as_str().unwrap().contains("hardware"));
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        let request = test::TestRequest::post().uri("/mcp").set_json(&notification).to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::ACCEPTED);
+
+        let request = test::TestRequest::post()
+            .uri("/mcp")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload("{")
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::BAD_REQUEST);
+        // Formularios y texto plano no necesitan preflight de CORS: se rechazan
+        let request = test::TestRequest::post()
+            .uri("/mcp")
+            .insert_header(("Content-Type", "text/plain"))
+            .set_payload(call(3, "get_stats", json!({})).to_string())
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let request = test::TestRequest::post()
+            .uri("/mcp")
+            .insert_header(("Host", "evil.example:8080"))
+            .insert_header(("Origin", "http://evil.example:8080"))
+            .set_json(call(4, "get_stats", json!({})))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+        let request = test::TestRequest::get().uri("/mcp").to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
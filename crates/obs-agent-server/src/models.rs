@@ -0,0 +1,224 @@
+use crate::error::ApiError;
+use chrono::{DateTime, Utc};
+use obs_agent_core::application::ports::OBSConfig;
+use obs_agent_core::domain::models::*;
+use obs_agent_core::domain::services::HealthReport;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Escena con su estado de programa
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SceneView {
+    pub name: String,
+    pub uuid: Option<String>,
+    /// Escena en programa
+    pub current: bool,
+    pub sources: Vec<SourceView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceView {
+    pub name: String,
+    pub kind: String,
+    pub available: bool,
+}
+
+impl SceneView {
+    pub fn new(scene: Scene, current: &str) -> Self {
+        Self {
+            current: scene.name == current,
+            name: scene.name,
+            uuid: scene.uuid,
+            sources: scene
+                .sources
+                .into_iter()
+                .map(|source| SourceView {
+                    name: source.name,
+                    kind: source.kind,
+                    available: source.is_available,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Escena en programa (respuesta y cuerpo para cambiarla)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CurrentScene {
+    pub name: String,
+}
+
+/// Estadísticas de OBS y de sus salidas
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatsView {
+    pub cpu_usage_percent: f64,
+    pub memory_mb: f64,
+    pub active_fps: f64,
+    pub render_skipped_frames: u64,
+    pub render_total_frames: u64,
+    pub output_skipped_frames: u64,
+    pub output_total_frames: u64,
+    pub dropped_frames_percent: f64,
+    pub outputs: Vec<OutputStatus>,
+}
+
+impl StatsView {
+    pub fn new(stats: OBSStats, outputs: Vec<OutputStatus>) -> Self {
+        Self {
+            cpu_usage_percent: stats.cpu_usage,
+            memory_mb: stats.memory_usage,
+            active_fps: stats.active_fps,
+            render_skipped_frames: stats.render_skipped_frames,
+            render_total_frames: stats.render_total_frames,
+            output_skipped_frames: stats.output_skipped_frames,
+            output_total_frames: stats.output_total_frames,
+            dropped_frames_percent: stats.dropped_frames_percent(),
+            outputs,
+        }
+    }
+}
+
+/// Reporte de salud completo con su resumen legible
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthView {
+    #[serde(flatten)]
+    pub report: HealthReport,
+    pub summary: String,
+}
+
+impl From<HealthReport> for HealthView {
+    fn from(report: HealthReport) -> Self {
+        Self {
+            summary: report.summary(),
+            report,
+        }
+    }
+}
+
+/// Resultado de `?quick=true`: solo problemas críticos
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuickHealth {
+    pub healthy: bool,
+}
+
+/// Respuesta de `/health`: el reporte completo o, con `quick=true`, solo si está sano
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum HealthResponse {
+    Full(HealthView),
+    Quick(QuickHealth),
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HealthQuery {
+    /// Solo sensores locales (no contacta con OBS)
+    #[serde(default)]
+    pub quick: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScanQuery {
+    /// Severidad mínima (`info`, `warning`, `critical`)
+    pub severity: Option<String>,
+}
+
+impl ScanQuery {
+    /// Severidad pedida; `info` (todas) si se omite
+    pub fn min_severity(&self) -> Result<Severity, ApiError> {
+        match self.severity.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("info") => Ok(Severity::Info),
+            Some("warning") => Ok(Severity::Warning),
+            Some("critical") => Ok(Severity::Critical),
+            Some(other) => Err(ApiError::BadRequest(format!(
+                "Unknown severity '{}' (info, warning, critical)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Antigüedad máxima (`30m`, `6h`, `7d`; 24 h si se omite)
+    pub since: Option<String>,
+    /// Entradas como máximo (100 por defecto, hasta 1000)
+    pub limit: Option<usize>,
+}
+
+/// Salida de OBS que se puede iniciar o detener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputName {
+    Stream,
+    Record,
+}
+
+impl OutputName {
+    /// Nombre de la salida en OBS
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stream => "stream",
+            Self::Record => "record",
+        }
+    }
+}
+
+impl std::str::FromStr for OutputName {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, ApiError> {
+        match s {
+            "stream" => Ok(Self::Stream),
+            "record" => Ok(Self::Record),
+            _ => Err(ApiError::NotFound(format!("Unknown output '{}' (stream, record)", s))),
+        }
+    }
+}
+
+/// Anomalías detectadas en una muestra
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnomalyScan {
+    pub timestamp: DateTime<Utc>,
+    pub min_severity: Severity,
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Configuración propuesta por el optimizador
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OptimizedConfig {
+    pub video: VideoSettings,
+    pub fps: f64,
+    pub encoder: String,
+    pub preset: String,
+    pub bitrate_kbps: u32,
+    /// Ajustes de audio propios del encoder
+    pub audio_settings: serde_json::Value,
+    /// Subida estimada hacia el servidor de ingesta
+    pub upload_kbps: Option<u32>,
+    /// Bitrate máximo que admite la subida medida
+    pub max_bitrate_kbps: Option<u32>,
+    /// Equipo en batería o perfil de ahorro (preset más ligero, máx. 30 FPS)
+    pub power_saving: bool,
+    /// La configuración cabe en el hardware detectado
+    pub valid: bool,
+}
+
+impl OptimizedConfig {
+    pub fn new(config: OBSConfig) -> Self {
+        Self {
+            fps: config.video.fps(),
+            video: config.video,
+            encoder: config.encoder,
+            preset: config.preset,
+            bitrate_kbps: config.bitrate,
+            audio_settings: config.audio_settings,
+            upload_kbps: None,
+            max_bitrate_kbps: None,
+            power_saving: false,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use crate::api;
+use crate::models::OutputName;
+use actix_web::{web, HttpResponse};
+use std::collections::BTreeMap;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, Ref, RefOr, Response, ResponseBuilder};
+use utoipa::{IntoResponses, Modify, OpenApi};
+
+/// Página de documentación interactiva (lee `/openapi.json`)
+const DOCS_HTML: &str = include_str!("../static/docs.html");
+
+/// Rutas de la especificación y de su documentación (sin autenticación)
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/openapi.json", web::get().to(openapi_json))
+        .route("/docs", web::get().to(docs));
+}
+
+async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+async fn docs() -> HttpResponse {
+    HttpResponse::Ok().content_type("text/html; charset=utf-8").body(DOCS_HTML)
+}
+
+/// Documento OpenAPI de `/api/v1`
+///
+/// Las operaciones salen de los atributos `#[utoipa::path]` de los handlers
+/// registrados en `api::ApiV1` y los esquemas de los tipos que devuelven.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "OBS Studio Agent API",
+        description = "Hardware, scenes, stats, health, anomalies and live events of an OBS Studio machine.\n\n\
+            Every endpoint needs an API token created with `obs-agent tokens create` unless the server \
+            runs with `--no-auth`: `read` tokens can query, `operator` tokens can also change OBS and \
+            `admin` tokens can also read the audit trail.",
+    ),
+    servers((url = "/")),
+    components(schemas(OutputName)),
+    nest((path = "/api/v1", api = api::ApiV1)),
+    tags(
+        (name = "hardware", description = "Detected hardware and live sensor readings"),
+        (name = "obs", description = "Scenes and output statistics"),
+        (name = "health", description = "Health checks, anomalies and optimization"),
+        (name = "live", description = "Server-Sent Events and WebSocket streams"),
+        (name = "admin", description = "Audit trail"),
+        (name = "fleet", description = "Agents that report to this server and the commands queued for them"),
+    ),
+    security(("bearer" = []), ("query_token" = [])),
+    modifiers(&Authentication),
+)]
+pub struct ApiDoc;
+
+/// Esquemas de autenticación y respuestas de error comunes a toda la API
+struct Authentication;
+
+impl Modify for Authentication {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("`Authorization: Bearer obsa_...`"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "query_token",
+            SecurityScheme::ApiKey(ApiKey::Query(ApiKeyValue::with_description(
+                "access_token",
+                "For EventSource and WebSocket clients that cannot send headers",
+            ))),
+        );
+
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [&mut item.get, &mut item.put, &mut item.post, &mut item.delete, &mut item.patch];
+            for operation in operations.into_iter().flatten() {
+                for (status, description) in [
+                    ("401", "Missing or invalid API token"),
+                    ("403", "Token scope too low"),
+                    ("429", "Rate limit exceeded (see Retry-After)"),
+                ] {
+                    let responses = &mut operation.responses.responses;
+                    responses.entry(status.to_string()).or_insert_with(|| error_response(description));
+                }
+            }
+        }
+    }
+}
+
+/// Respuestas de las rutas que llaman a OBS o a la IA
+pub struct UpstreamErrors;
+
+impl IntoResponses for UpstreamErrors {
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        BTreeMap::from([
+            ("502".to_string(), error_response("OBS or the AI returned an error")),
+            ("504".to_string(), error_response("OBS or the AI did not answer in time")),
+        ])
+    }
+}
+
+/// Respuesta de error con cuerpo [`ErrorBody`](crate::error::ErrorBody)
+fn error_response(description: &str) -> RefOr<Response> {
+    ResponseBuilder::new()
+        .description(description)
+        .content("application/json", ContentBuilder::new().schema(Some(Ref::from_schema_name("ErrorBody"))).build())
+        .build()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CurrentScene;
+    use crate::state::AppState;
+    use actix_web::http::{Method, StatusCode};
+    use actix_web::{test as atest, App};
+    use chrono::Utc;
+    use obs_agent_core::application::ports::{AgentRegistration, CommandKind, Heartbeat};
+    use obs_agent_core::domain::services::HealthReport;
+    use obs_agent_infra::*;
+    use serde_json::{json, Value};
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    const METHODS: [&str; 5] = ["get", "put", "post", "delete", "patch"];
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    async fn simulated_state() -> web::Data<AppState> {
+        let simulation = Arc::new(Simulation::new(SimulationScenario::Nominal));
+        let (obs, monitor) = simulated_ports(Arc::clone(&simulation));
+        let sampler = MonitorSampler::new(monitor).spawn();
+        sampler.watch().wait_for(Option::is_some).await.unwrap();
+        web::Data::new(AppState::new(obs, sampler))
+    }
+
+    fn resolve<'a>(spec: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/components/schemas/");
+                resolve(spec, &spec["components"]["schemas"][name])
+            }
+            None => schema,
+        }
+    }
+
+    /// Nombres de propiedades que admite un esquema, incluidos los de
+    /// `allOf`/`oneOf` (`serde(flatten)` y enums etiquetados); `false` si admite cualquiera
+    fn property_names(spec: &Value, schema: &Value, names: &mut BTreeSet<String>) -> bool {
+        let schema = resolve(spec, schema);
+        if let Some(properties) = schema["properties"].as_object() {
+            names.extend(properties.keys().cloned());
+        }
+        if schema.get("additionalProperties").is_some_and(|extra| extra != &json!(false)) {
+            return false;
+        }
+        ["allOf", "oneOf", "anyOf"]
+            .iter()
+            .flat_map(|key| schema[key].as_array().into_iter().flatten())
+            .all(|sub| property_names(spec, sub, names))
+    }
+
+    fn type_matches(name: &str, value: &Value) -> bool {
+        match name {
+            "null" => value.is_null(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        }
+    }
+
+    /// Validador mínimo de OpenAPI 3.1: tipos, obligatorios, enums,
+    /// `allOf`/`oneOf` y propiedades no documentadas
+    fn validate(spec: &Value, schema: &Value, value: &Value, at: &str) -> Vec<String> {
+        check(spec, schema, value, at, true)
+    }
+
+    fn check(spec: &Value, schema: &Value, value: &Value, at: &str, strict: bool) -> Vec<String> {
+        let schema = resolve(spec, schema);
+        let mut errors = Vec::new();
+        if let (true, Some(object)) = (strict, value.as_object()) {
+            let mut names = BTreeSet::new();
+            if property_names(spec, schema, &mut names) && !names.is_empty() {
+                let undocumented = object.keys().filter(|key| !names.contains(*key));
+                errors.extend(undocumented.map(|key| format!("{}: undocumented property {}", at, key)));
+            }
+        }
+        if let Some(all) = schema["allOf"].as_array() {
+            errors.extend(all.iter().flat_map(|sub| check(spec, sub, value, at, false)));
+            return errors;
+        }
+        if let Some(one) = schema["oneOf"].as_array() {
+            if !one.iter().any(|sub| check(spec, sub, value, at, false).is_empty()) {
+                errors.push(format!("{}: {} matches none of oneOf", at, value));
+            }
+            return errors;
+        }
+
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| type_matches(name, value)) {
+            errors.push(format!("{}: expected {}, got {}", at, schema["type"], value));
+            return errors;
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            if !values.contains(value) {
+                errors.push(format!("{}: {} not in enum", at, value));
+            }
+        }
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in array.iter().enumerate() {
+                errors.extend(check(spec, items, item, &format!("{}[{}]", at, index), true));
+            }
+        }
+        if let Some(object) = value.as_object() {
+            for required in schema["required"].as_array().into_iter().flatten() {
+                if !object.contains_key(required.as_str().unwrap()) {
+                    errors.push(format!("{}: missing {}", at, required));
+                }
+            }
+            for (key, field) in object {
+                let field_schema = match schema["properties"].get(key) {
+                    Some(field_schema) => field_schema,
+                    None => match schema.get("additionalProperties") {
+                        Some(extra) if extra.is_object() => extra,
+                        _ => continue,
+                    },
+                };
+                errors.extend(check(spec, field_schema, field, &format!("{}.{}", at, key), true));
+            }
+        }
+        errors
+    }
+
+    /// Operaciones documentadas como `(método, ruta)`
+    fn operations(spec: &Value) -> Vec<(String, String)> {
+        let paths = spec["paths"].as_object().unwrap();
+        paths
+            .iter()
+            .flat_map(|(path, item)| {
+                METHODS.iter().filter(|method| item.get(**method).is_some()).map(|method| (method.to_string(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[actix_web::test]
+    async fn test_every_operation_is_routed() {
+        let state = simulated_state().await;
+        let app = atest::init_service(App::new().app_data(state.clone()).configure(api::configure).configure(configure))
+            .await;
+        let spec = spec();
+        let operations = operations(&spec);
+        assert_eq!(operations.len(), 24);
+
+        for (method, path) in operations {
+            let uri = path
+                .replace("{id}", &Uuid::new_v4().to_string())
+                .replace("{output}", "stream")
+                .replace("{host_id}", "studio-1");
+            let request = atest::TestRequest::default()
+                .method(Method::from_bytes(method.to_uppercase().as_bytes()).unwrap())
+                .uri(&uri)
+                .to_request();
+            let response = atest::call_service(&app, request).await;
+            assert_ne!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{} {}", method, path);
+            // La ruta por defecto de `/api/v1` solo responde si nada coincidió
+            if response.status() == StatusCode::NOT_FOUND {
+                let body: Value = atest::read_body_json(response).await;
+                assert_ne!(body["message"], "No such API endpoint", "{} {} is documented but not routed", method, path);
+            }
+        }
+
+        // Todas las referencias apuntan a un esquema existente
+        let text = spec.to_string();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+            assert!(spec["components"]["schemas"].get(name).is_some(), "missing schema {}", name);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_responses_match_spec() {
+        let state = simulated_state().await;
+        let app = atest::init_service(App::new().app_data(state.clone()).configure(api::configure).configure(configure))
+            .await;
+
+        // Un equipo de la flota con latido para validar sus vistas
+        let hardware = api::detect_hardware(&state).await.unwrap();
+        state.fleet.register(AgentRegistration::new("studio-1", hardware.clone()), None, Utc::now()).unwrap();
+        let sample = state.health_service().sample().await.unwrap();
+        let heartbeat = Heartbeat {
+            timestamp: Utc::now(),
+            health: Some(HealthReport::from_anomalies(Vec::new(), 8.0, sample.timestamp)),
+            sample: Some(sample),
+            error: None,
+            results: Vec::new(),
+        };
+        state.fleet.heartbeat("studio-1", None, heartbeat.clone(), Utc::now()).unwrap();
+        state.fleet.enqueue("studio-1", CommandKind::RunHealthCheck, "admin", Utc::now()).unwrap();
+
+        let served: Value =
+            atest::call_and_read_body_json(&app, atest::TestRequest::get().uri("/openapi.json").to_request()).await;
+        let spec = spec();
+        assert_eq!(served, spec);
+        let docs = atest::call_service(&app, atest::TestRequest::get().uri("/docs").to_request()).await;
+        assert_eq!(docs.status(), StatusCode::OK);
+
+        let anomaly = Uuid::new_v4().to_string();
+        let cases: Vec<(&str, &str, String, Option<Value>)> = vec![
+            ("get", "/hardware", "/hardware".into(), None),
+            ("get", "/hardware/live", "/hardware/live".into(), None),
+            ("get", "/scenes", "/scenes".into(), None),
+            ("get", "/scenes/current", "/scenes/current".into(), None),
+            ("put", "/scenes/current", "/scenes/current".into(), Some(json!(CurrentScene { name: "BRB".into() }))),
+            ("get", "/stats", "/stats".into(), None),
+            ("get", "/health", "/health".into(), None),
+            ("get", "/health", "/health?quick=true".into(), None),
+            ("get", "/anomalies", "/anomalies".into(), None),
+            ("get", "/anomalies/open", "/anomalies/open".into(), None),
+            ("post", "/anomalies/{id}/ack", format!("/anomalies/{}/ack", anomaly), None),
+            ("post", "/anomalies/{id}/fix", format!("/anomalies/{}/fix", anomaly), None),
+            ("post", "/outputs/{output}/start", "/outputs/stream/start".into(), None),
+            ("post", "/outputs/{output}/stop", "/outputs/stream/stop".into(), None),
+            ("post", "/optimize", "/optimize".into(), None),
+            ("get", "/audit", "/audit".into(), None),
+            ("get", "/fleet/summary", "/fleet/summary".into(), None),
+            ("get", "/fleet/hosts", "/fleet/hosts".into(), None),
+            ("get", "/fleet/hosts", "/fleet/hosts?status=online".into(), None),
+            ("get", "/fleet/hosts", "/fleet/hosts?status=nope".into(), None),
+            ("get", "/fleet/hosts/{host_id}", "/fleet/hosts/studio-1".into(), None),
+            (
+                "post",
+                "/fleet/register",
+                "/fleet/register".into(),
+                Some(json!(AgentRegistration::new("studio-2", hardware))),
+            ),
+            (
+                "post",
+                "/fleet/hosts/{host_id}/heartbeat",
+                "/fleet/hosts/studio-1/heartbeat".into(),
+                Some(json!(heartbeat)),
+            ),
+            (
+                "post",
+                "/fleet/hosts/{host_id}/commands",
+                "/fleet/hosts/studio-1/commands".into(),
+                Some(json!(CommandKind::SwitchScene { scene: "Main".into() })),
+            ),
+            ("delete", "/fleet/hosts/{host_id}", "/fleet/hosts/studio-2".into(), None),
+            ("delete", "/fleet/hosts/{host_id}", "/fleet/hosts/studio-2".into(), None),
+        ];
+
+        let mut statuses = BTreeSet::new();
+        for (method, path, uri, body) in cases {
+            let uri = format!("/api/v1{}", uri);
+            let request = atest::TestRequest::default()
+                .method(Method::from_bytes(method.to_uppercase().as_bytes()).unwrap())
+                .uri(&uri);
+            let request = match body {
+                Some(body) => request.set_json(body),
+                None => request,
+            };
+            let response = atest::call_service(&app, request.to_request()).await;
+            let status = response.status();
+            statuses.insert((method, status.as_u16()));
+            let documented = &spec["paths"][format!("/api/v1{}", path)][method]["responses"][status.as_str()];
+            assert!(!documented.is_null(), "{} {} returned undocumented status {}", method, uri, status);
+
+            let body = atest::read_body(response).await;
+            let schema = &documented["content"]["application/json"]["schema"];
+            if schema.is_null() {
+                assert!(body.is_empty(), "{} {} returned an undocumented body", method, uri);
+                continue;
+            }
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            let errors = validate(&spec, schema, &body, &uri);
+            assert!(errors.is_empty(), "{} {} drifted from the spec: {:#?}", method, uri, errors);
+        }
+
+        // Éxitos y errores de todos los métodos
+        for expected in [
+            ("get", 200),
+            ("get", 400),
+            ("get", 503),
+            ("put", 204),
+            ("post", 200),
+            ("post", 202),
+            ("post", 204),
+            ("post", 404),
+            ("post", 503),
+            ("delete", 204),
+            ("delete", 404),
+        ] {
+            assert!(statuses.contains(&expected), "no {:?} response checked", expected);
+        }
+    }
+}
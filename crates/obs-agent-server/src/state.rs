@@ -0,0 +1,158 @@
+use crate::auth::Auth;
+use crate::board::AnomalyBoard;
+use crate::error::ApiError;
+use crate::fleet::FleetRegistry;
+use crate::live::EventHub;
+use obs_agent_core::application::ports::*;
+use obs_agent_core::domain::events::DomainEvent;
+use obs_agent_core::domain::services::*;
+use obs_agent_infra::{OpenMetricsExporter, SamplerHandle};
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Estado compartido por todos los workers del servidor
+pub struct AppState {
+    pub obs: Arc<dyn OBSPort>,
+    /// Lecturas de hardware desde el sampler (no se relee el sistema por petición)
+    pub monitor: Arc<dyn MonitorPort>,
+    pub network: Option<Arc<dyn NetworkPort>>,
+    pub ai: Option<Arc<dyn AIPort>>,
+    pub sampler: SamplerHandle,
+    /// Eventos en vivo para los clientes SSE / WebSocket
+    pub events: EventHub,
+    /// Límite para las llamadas a OBS y a la IA
+    pub timeout: Duration,
+    /// Tokens y auditoría; `None` deja la API abierta (solo en loopback)
+    pub auth: Option<Auth>,
+    /// Anomalías abiertas y quién las reconoció
+    pub board: AnomalyBoard,
+    /// Agentes remotos registrados en este servidor
+    pub fleet: FleetRegistry,
+    /// Detector único del proceso: las reglas con estado (throttling sostenido)
+    /// tienen que ver todas las muestras
+    pub detector: Arc<AnomalyDetector>,
+    /// Última muestra en formato OpenMetrics para `GET /metrics`
+    pub metrics: Arc<OpenMetricsExporter>,
+    /// Orígenes de navegador aceptados además del propio (CORS)
+    pub allowed_origins: Vec<String>,
+    remediation: OnceLock<RemediationEngine>,
+    last_health: Mutex<Option<HealthReport>>,
+}
+
+impl AppState {
+    pub fn new(obs: Arc<dyn OBSPort>, sampler: SamplerHandle) -> Self {
+        Self {
+            obs,
+            monitor: sampler.monitor(),
+            network: None,
+            ai: None,
+            sampler,
+            events: EventHub::new(),
+            timeout: Duration::from_secs(10),
+            auth: None,
+            board: AnomalyBoard::new(),
+            fleet: FleetRegistry::default(),
+            detector: Arc::new(AnomalyDetector::with_default_rules()),
+            metrics: Arc::new(OpenMetricsExporter::new()),
+            allowed_origins: Vec::new(),
+            remediation: OnceLock::new(),
+            last_health: Mutex::new(None),
+        }
+    }
+
+    pub fn with_network(mut self, network: Arc<dyn NetworkPort>) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn with_ai(mut self, ai: Arc<dyn AIPort>) -> Self {
+        self.ai = Some(ai);
+        self
+    }
+
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Intervalo de latido que se pide a los agentes de la flota
+    pub fn with_fleet_heartbeat(mut self, interval: Duration) -> Self {
+        self.fleet = FleetRegistry::new(interval);
+        self
+    }
+
+    pub fn health_service(&self) -> HealthCheckService {
+        let service = HealthCheckService::new(Arc::clone(&self.obs), Arc::clone(&self.monitor))
+            .with_detector(Arc::clone(&self.detector));
+        match &self.network {
+            Some(network) => service.with_network(Arc::clone(network)),
+            None => service,
+        }
+    }
+
+    /// Cambia la escena de programa si existe y avisa a los clientes en vivo
+    pub async fn switch_scene(&self, name: &str) -> Result<(), ApiError> {
+        if name.trim().is_empty() {
+            return Err(ApiError::BadRequest("Scene name must not be empty".to_string()));
+        }
+        let scenes = self.upstream("Listing OBS scenes", self.obs.get_scenes()).await?;
+        if !scenes.iter().any(|scene| scene.name == name) {
+            return Err(ApiError::NotFound(format!("Scene '{}' not found", name)));
+        }
+        self.upstream("Switching the OBS program scene", self.obs.set_current_scene(name)).await?;
+        self.events.publish_event(DomainEvent::config_changed(vec![format!("scene: {}", name)]));
+        Ok(())
+    }
+
+    /// Chequeo completo: avisa a los clientes en vivo y queda como el último reporte
+    pub async fn check_health(&self) -> Result<HealthReport, ApiError> {
+        let report = self.upstream("Health check", self.health_service().check()).await?;
+        self.events
+            .publish_event(DomainEvent::health_check_completed(report.is_healthy, report.anomalies.len()));
+        *self.last_health.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+        Ok(report)
+    }
+
+    /// Último reporte de `check_health`, si ya se hizo alguno
+    pub fn last_health(&self) -> Option<HealthReport> {
+        self.last_health.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Motor de remediación compartido (cuenta los intentos por tipo de anomalía)
+    ///
+    /// Quien pide el arreglo desde la API ya lo está confirmando, así que no
+    /// se vuelve a preguntar; tras aplicarlo se verifica con una muestra nueva.
+    pub fn remediation(&self) -> &RemediationEngine {
+        self.remediation.get_or_init(|| {
+            RemediationEngine::new(Arc::clone(&self.obs))
+                .with_policy(RemediationPolicy {
+                    confirmation: ConfirmationPolicy::Never,
+                    ..RemediationPolicy::default()
+                })
+                .with_verifier(Arc::new(self.health_service()), Arc::clone(&self.detector))
+        })
+    }
+
+    /// Ejecuta una llamada a un servicio externo (OBS, IA) con el límite de tiempo
+    pub async fn upstream<T>(
+        &self,
+        what: &str,
+        call: impl Future<Output = anyhow::Result<T>>,
+    ) -> Result<T, ApiError> {
+        match tokio::time::timeout(self.timeout, call).await {
+            Ok(result) => result.map_err(|e| ApiError::Upstream(e.context(format!("{} failed", what)))),
+            Err(_) => Err(ApiError::Timeout(format!("{} timed out after {:?}", what, self.timeout))),
+        }
+    }
+}